@@ -0,0 +1,132 @@
+//! Adaptive sampling module. Per-pixel running statistics used to
+//! stop sampling a pixel once its estimated error is small enough.
+
+use crate::vectors::{Vec3, Vec3Methods};
+
+/// Luminance below which the error threshold becomes absolute
+/// instead of relative, so that dark pixels also converge.
+const MIN_LUMINANCE: f32 = 5e-2;
+
+/// Adaptive sampling settings.
+#[derive(Copy, Clone)]
+pub struct AdaptiveSampling {
+    /// Samples taken on every pixel, also the batch size between
+    /// convergence checks.
+    pub min_samples: u16,
+    /// Maximum samples per pixel.
+    pub max_samples: u16,
+    /// Relative standard error of the pixel's mean luminance
+    /// below which sampling stops.
+    pub threshold: f32,
+}
+
+/// Running mean and variance (Welford's algorithm) of a pixel's samples.
+#[derive(Copy, Clone)]
+pub struct PixelEstimate {
+    /// Number of samples.
+    n: u32,
+    /// Mean color.
+    mean: Vec3,
+    /// Mean luminance.
+    mean_lum: f32,
+    /// Sum of squared luminance deviations.
+    m2_lum: f32,
+}
+
+/// Relative luminance of a linear sRGB color (Rec. 709 weights).
+pub fn luminance(col: Vec3) -> f32 {
+    0.2126 * col.r() + 0.7152 * col.g() + 0.0722 * col.b()
+}
+
+impl AdaptiveSampling {
+    /// AdaptiveSampling constructor.
+    ///
+    /// # Parameters:
+    /// * `min_samples` - Samples per pixel before the first convergence check.
+    /// * `max_samples` - Maximum samples per pixel.
+    /// * `threshold` - Relative error at which a pixel is converged.
+    pub fn new(min_samples: u16, max_samples: u16, threshold: f32) -> AdaptiveSampling {
+        let min_samples: u16 = min_samples.max(2);
+        AdaptiveSampling { min_samples, max_samples: max_samples.max(min_samples), threshold }
+    }
+
+    /// Whether sampling of the pixel must go on, never past
+    /// ``max_samples``.
+    pub fn needs_samples(&self, estimate: &PixelEstimate) -> bool {
+        let n: u32 = estimate.samples();
+        match n < self.max_samples as u32 {
+            true => n < self.min_samples as u32 || !estimate.converged(self.threshold),
+            false => false,
+        }
+    }
+
+    /// Samples to take before the next convergence check: a batch of
+    /// ``min_samples`` (at least one) up to ``max_samples``.
+    pub fn batch(&self, estimate: &PixelEstimate) -> u32 {
+        let left: u32 = (self.max_samples as u32).saturating_sub(estimate.samples());
+        (self.min_samples.max(1) as u32).min(left)
+    }
+}
+
+impl Default for PixelEstimate {
+    fn default() -> PixelEstimate {
+        PixelEstimate::new()
+    }
+}
+
+impl PixelEstimate {
+    /// Estimate without samples.
+    pub fn new() -> PixelEstimate {
+        PixelEstimate { n: 0, mean: Vec3::zeros(), mean_lum: 0e0, m2_lum: 0e0 }
+    }
+
+    /// Adds a sample.
+    pub fn add(&mut self, col: Vec3) {
+        self.n += 1;
+        let n: f32 = self.n as f32;
+        self.mean += (col - self.mean) / n;
+        let lum: f32 = luminance(col);
+        let delta: f32 = lum - self.mean_lum;
+        self.mean_lum += delta / n;
+        self.m2_lum += delta * (lum - self.mean_lum);
+    }
+
+    /// Number of samples.
+    pub fn samples(&self) -> u32 { self.n }
+
+    /// Mean color.
+    pub fn mean(&self) -> Vec3 { self.mean }
+
+    /// Standard error of the mean luminance,
+    /// $\sigma_{\bar L}=\sqrt{\frac{s^2}{n}}$.
+    pub fn error(&self) -> f32 {
+        match self.n > 1 {
+            true => (self.m2_lum / ((self.n - 1) as f32) / (self.n as f32)).sqrt(),
+            false => f32::MAX,
+        }
+    }
+
+    /// Whether the relative error is below ``threshold``.
+    pub fn converged(&self, threshold: f32) -> bool {
+        self.error() <= threshold * self.mean_lum.max(MIN_LUMINANCE)
+    }
+}
+
+/// False color of a sample count, from blue (``min``) through
+/// green to red (``max``).
+pub fn heatmap_color(n: u32, min: u32, max: u32) -> Vec3 {
+    match max > min {
+        true => ramp_color((n as f32 - min as f32) / (max as f32 - min as f32)),
+        false => ramp_color(0e0),
+    }
+}
+
+/// False color of a value from 0 (blue) through 0.5 (green) to
+/// 1 (red), clamped to that range.
+pub fn ramp_color(x: f32) -> Vec3 {
+    let x: f32 = x.clamp(0e0, 1e0);
+    match x < 5e-1 {
+        true => Vec3::new(0e0, 2e0 * x, 1e0 - 2e0 * x),
+        false => Vec3::new(2e0 * x - 1e0, 2e0 - 2e0 * x, 0e0),
+    }
+}
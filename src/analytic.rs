@@ -0,0 +1,334 @@
+//! Analytic view factor module. Catalogue of closed form configuration
+//! factors, to validate the Monte Carlo estimators and to replace them
+//! on pairs of forms matching a known configuration (see
+//! `ExactViewFactors`). Differential patches are areas small enough
+//! for the factor not to vary over them.
+
+use std::f32::consts::PI;
+
+use crate::vectors::{Vec3, Vec3Methods};
+use crate::objects::{Form, HittableList, SurfaceFunctions};
+use crate::radiation::{VfMethod, VfTarget, VfEstimate, Vfs};
+
+/// Relative tolerance of the configuration matching, on the size of
+/// the forms.
+const MATCH_TOL: f32 = 1e-4;
+
+/// Identical, parallel, directly opposed rectangles of sides $a$ and
+/// $b$, a distance $c$ apart (Howell C-11):
+/// $$F_{12}=\frac{2}{\pi XY}\left[\ln\sqrt{\frac{(1+X^2)(1+Y^2)}{1+X^2+Y^2}}
+/// +X\sqrt{1+Y^2}\tan^{-1}\frac{X}{\sqrt{1+Y^2}}+Y\sqrt{1+X^2}\tan^{-1}\frac{Y}{\sqrt{1+X^2}}
+/// -X\tan^{-1}X-Y\tan^{-1}Y\right]$$
+/// with $X=a/c$ and $Y=b/c$.
+pub fn parallel_rectangles(a: f32, b: f32, c: f32) -> f32 {
+    let (x, y): (f32, f32) = (a / c, b / c);
+    let (x1, y1): (f32, f32) = ((1e0 + x.powi(2)).sqrt(), (1e0 + y.powi(2)).sqrt());
+    let log: f32 = 5e-1 * (x1.powi(2) * y1.powi(2) / (1e0 + x.powi(2) + y.powi(2))).ln();
+    2e0 / (PI * x * y) * (log + x * y1 * (x / y1).atan() + y * x1 * (y / x1).atan() - x * x.atan() - y * y.atan())
+}
+
+/// Perpendicular rectangles with a common edge of length $l$, from the
+/// one of width $w$ to the one of height $h$ (Incropera, table 13.2):
+/// $$F_{12}=\frac{1}{\pi W}\left[W\tan^{-1}\frac{1}{W}+H\tan^{-1}\frac{1}{H}
+/// -\sqrt{H^2+W^2}\tan^{-1}\frac{1}{\sqrt{H^2+W^2}}+\frac{1}{4}\ln\Lambda\right]$$
+/// with $W=w/l$, $H=h/l$ and
+/// $$\Lambda=\frac{(1+W^2)(1+H^2)}{1+W^2+H^2}
+/// \left[\frac{W^2(1+W^2+H^2)}{(1+W^2)(W^2+H^2)}\right]^{W^2}
+/// \left[\frac{H^2(1+H^2+W^2)}{(1+H^2)(H^2+W^2)}\right]^{H^2}$$
+pub fn perpendicular_rectangles(l: f32, w: f32, h: f32) -> f32 {
+    let (w, h): (f32, f32) = (w / l, h / l);
+    let (w2, h2): (f32, f32) = (w.powi(2), h.powi(2));
+    let d: f32 = (w2 + h2).sqrt();
+    let log: f32 = ((1e0 + w2) * (1e0 + h2) / (1e0 + w2 + h2)).ln()
+        + w2 * (w2 * (1e0 + w2 + h2) / ((1e0 + w2) * (w2 + h2))).ln()
+        + h2 * (h2 * (1e0 + h2 + w2) / ((1e0 + h2) * (h2 + w2))).ln();
+    (w * w.recip().atan() + h * h.recip().atan() - d * d.recip().atan() + log / 4e0) / (PI * w)
+}
+
+/// Parallel coaxial disks, from the one of radius $r_1$ to the one of
+/// radius $r_2$, a distance $h$ apart (Howell C-41):
+/// $$F_{12}=\frac{1}{2}\left[S-\sqrt{S^2-4\left(\frac{R_2}{R_1}\right)^2}\right],
+/// \quad S=1+\frac{1+R_2^2}{R_1^2}$$
+/// with $R_i=r_i/h$.
+pub fn coaxial_disks(r1: f32, r2: f32, h: f32) -> f32 {
+    let (r1, r2): (f32, f32) = (r1 / h, r2 / h);
+    let s: f32 = 1e0 + (1e0 + r2.powi(2)) / r1.powi(2);
+    let q: f32 = 4e0 * (r2 / r1).powi(2);
+    // Rationalized, free of cancellation for small disks.
+    5e-1 * q / (s + (s.powi(2) - q).sqrt())
+}
+
+/// Differential patch to a parallel coaxial disk of radius $r$ a
+/// distance $h$ away (Howell B-13): $F=\frac{r^2}{h^2+r^2}$.
+pub fn patch_to_disk(r: f32, h: f32) -> f32 {
+    r.powi(2) / (h.powi(2) + r.powi(2))
+}
+
+/// Differential patch to a parallel rectangle of sides $a$ and $b$, a
+/// distance $c$ away, whose corner lies on the patch normal (Howell B-3):
+/// $$F=\frac{1}{2\pi}\left[\frac{X}{\sqrt{1+X^2}}\tan^{-1}\frac{Y}{\sqrt{1+X^2}}
+/// +\frac{Y}{\sqrt{1+Y^2}}\tan^{-1}\frac{X}{\sqrt{1+Y^2}}\right]$$
+/// with $X=a/c$ and $Y=b/c$.
+pub fn patch_to_rectangle_corner(a: f32, b: f32, c: f32) -> f32 {
+    let (x, y): (f32, f32) = (a / c, b / c);
+    let (x1, y1): (f32, f32) = ((1e0 + x.powi(2)).sqrt(), (1e0 + y.powi(2)).sqrt());
+    (x / x1 * (y / x1).atan() + y / y1 * (x / y1).atan()) / (2e0 * PI)
+}
+
+/// Differential patch facing a sphere of radius $r$ whose center lies
+/// on the patch normal, a distance $h$ away (Howell B-58):
+/// $F=\left(\frac{r}{h}\right)^2$.
+pub fn patch_to_frontal_sphere(r: f32, h: f32) -> f32 {
+    (r / h).powi(2)
+}
+
+/// Differential patch whose plane contains the center of a sphere of
+/// radius $r$, a distance $h$ away (Howell B-60):
+/// $$F=\frac{1}{\pi}\left[\tan^{-1}\frac{1}{X}-\frac{X}{H^2}\right]$$
+/// with $H=h/r$ and $X=\sqrt{H^2-1}$.
+pub fn patch_to_leveled_sphere(r: f32, h: f32) -> f32 {
+    let h: f32 = h / r;
+    let x: f32 = (h.powi(2) - 1e0).sqrt();
+    (x.recip().atan() - x / h.powi(2)) / PI
+}
+
+/// Small sphere to a sphere of radius $r$ whose center is a distance
+/// $d$ away (Howell C-136 in the limit): the fraction of the directions
+/// within the cone tangent to the sphere,
+/// $F=\frac{1}{2}\left[1-\sqrt{1-\left(\frac{r}{d}\right)^2}\right]$.
+pub fn small_sphere_to_sphere(r: f32, d: f32) -> f32 {
+    5e-1 * (1e0 - (1e0 - (r / d).powi(2)).max(0e0).sqrt())
+}
+
+/// Sphere to a coaxial disk of radius $r$, the sphere center a distance
+/// $h$ away from the disk (Howell C-44):
+/// $F=\frac{1}{2}\left[1-\frac{1}{\sqrt{1+(r/h)^2}}\right]$.
+pub fn sphere_to_disk(r: f32, h: f32) -> f32 {
+    5e-1 * (1e0 - (1e0 + (r / h).powi(2)).sqrt().recip())
+}
+
+/// Hollow sphere of radius $r_{out}$ enclosing a sphere of radius
+/// $r_{in}$, from the enclosure to the inner sphere (which only sees
+/// the enclosure): $F=\left(\frac{r_{in}}{r_{out}}\right)^2$.
+pub fn sphere_enclosure(r_in: f32, r_out: f32) -> f32 {
+    (r_in / r_out).powi(2)
+}
+
+/// Known configuration of a pair of forms.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Configuration {
+    /// Identical, parallel, directly opposed rectangles: sides and distance.
+    ParallelRectangles(f32, f32, f32),
+    /// Perpendicular rectangles with a common edge: edge length, width
+    /// of the first one and height of the second one.
+    PerpendicularRectangles(f32, f32, f32),
+    /// Hollow sphere enclosing a sphere: inner and outer radii.
+    EnclosureToSphere(f32, f32),
+    /// Sphere inside a hollow sphere.
+    SphereToEnclosure,
+}
+
+impl Configuration {
+    /// View factor of the configuration, $F_{12}$.
+    pub fn view_factor(&self) -> f32 {
+        match *self {
+            Configuration::ParallelRectangles(a, b, c) => parallel_rectangles(a, b, c),
+            Configuration::PerpendicularRectangles(l, w, h) => perpendicular_rectangles(l, w, h),
+            Configuration::EnclosureToSphere(r_in, r_out) => sphere_enclosure(r_in, r_out),
+            Configuration::SphereToEnclosure => 1e0,
+        }
+    }
+}
+
+/// Analytic view factors trait for world.
+pub trait ExactViewFactors {
+    /// Known configuration of two forms, if they match one and nothing
+    /// else on the world may stand between them: no other form (nor
+    /// participating medium) overlaps their bounding box.
+    ///
+    /// # Parameters:
+    ///
+    /// * `self`: world of objects.
+    /// * `form_1_idx`: World index pointing at the first (main) object.
+    /// * `form_2_idx`: World index pointing at the second object.
+    fn configuration(&self, form_1_idx: usize, form_2_idx: usize) -> Option<Configuration>;
+
+    /// Exact view factor $F_{12}$, if the forms match a known
+    /// configuration (see `ExactViewFactors::configuration`).
+    fn exact_view_factor(&self, form_1_idx: usize, form_2_idx: usize) -> Option<f32>;
+
+    /// Same as `ViewFactors::view_factors_converged`, taking the exact
+    /// view factor of every pair of forms matching a known configuration
+    /// and sampling only the others.
+    fn view_factors_hybrid(&self, method: VfMethod, target: &VfTarget) -> Vfs;
+}
+
+impl ExactViewFactors for HittableList {
+    fn configuration(&self, form_1_idx: usize, form_2_idx: usize) -> Option<Configuration> {
+        if form_1_idx == form_2_idx {
+            return None;
+        }
+        let (form_1, form_2): (&Form, &Form) = (&self.forms[form_1_idx], &self.forms[form_2_idx]);
+        let configuration: Configuration = match (form_1, form_2) {
+            (Form::Sphere(_), Form::Sphere(_)) => sphere_configuration(form_1, form_2)?,
+            _ => rectangle_configuration(flat_corners(form_1)?, flat_corners(form_2)?)?,
+        };
+        let (low_1, high_1) = bounds(form_1)?;
+        let (low_2, high_2) = bounds(form_2)?;
+        let low: Vec3 = Vec3::new(low_1.e[0].min(low_2.e[0]), low_1.e[1].min(low_2.e[1]), low_1.e[2].min(low_2.e[2]));
+        let high: Vec3 = Vec3::new(high_1.e[0].max(high_2.e[0]), high_1.e[1].max(high_2.e[1]), high_1.e[2].max(high_2.e[2]));
+        let tol: f32 = MATCH_TOL * (high - low).length();
+        let blocked: bool = self.forms.iter().enumerate()
+            .filter(|(k, _)| *k != form_1_idx && *k != form_2_idx)
+            .any(|(_, form)| match bounds(form) {
+                // Forms of unknown extent may block the view.
+                None => true,
+                Some((l, h)) => (0..3).all(|a| l.e[a] < high.e[a] - tol && low.e[a] < h.e[a] - tol),
+            });
+        match blocked {
+            true => None,
+            false => Some(configuration),
+        }
+    }
+
+    fn exact_view_factor(&self, form_1_idx: usize, form_2_idx: usize) -> Option<f32> {
+        self.configuration(form_1_idx, form_2_idx).map(|configuration| configuration.view_factor())
+    }
+
+    fn view_factors_hybrid(&self, method: VfMethod, target: &VfTarget) -> Vfs {
+        let n_objs: usize = self.forms.len();
+        let mut self_vfs: Vec<f32> = vec![0e0; n_objs];
+        let mut vfs: Vec<Vec<f32>> = Vec::new();
+        for i in 0..n_objs {
+            let exact: Vec<Option<f32>> = ((i + 1)..n_objs).map(|j| self.exact_view_factor(i, j)).collect();
+            let sees_itself: bool = self.forms[i].sees_itself();
+            // Pairs left to sample, the self view factor first.
+            let targets: Vec<usize> = (i..n_objs)
+                .filter(|j| match *j == i {
+                    true => sees_itself,
+                    false => exact[j - i - 1].is_none(),
+                })
+                .collect();
+            let mut estimates = self.estimate_row(i, &targets, method, target).into_iter();
+            if sees_itself {
+                self_vfs[i] = estimates.next().map_or(0e0, |e: VfEstimate| e.value());
+            }
+            vfs.push(exact.iter().map(|f| f.unwrap_or_else(|| estimates.next().map_or(0e0, |e| e.value()))).collect());
+        }
+        Vfs::with_self(vfs, self_vfs)
+    }
+}
+
+/// Center and edges $(\vec c, \vec e_s, \vec e_t)$ of a flat form, its
+/// points being $\vec c+(s-\frac{1}{2})\vec e_s+(t-\frac{1}{2})\vec e_t$.
+fn flat_corners(form: &Form) -> Option<(Vec3, Vec3, Vec3)> {
+    match form {
+        Form::Rectangle(_) | Form::Square(_) => {
+            let origin: Vec3 = form.point(0e0, 0e0);
+            Some((form.point(5e-1, 5e-1), form.point(1e0, 0e0) - origin, form.point(0e0, 1e0) - origin))
+        }
+        _ => None,
+    }
+}
+
+/// Configuration of two flat forms, $\vec w=\vec e_s\times\vec e_t$
+/// being their front side normals.
+fn rectangle_configuration((c1, s1, t1): (Vec3, Vec3, Vec3), (c2, s2, t2): (Vec3, Vec3, Vec3)) -> Option<Configuration> {
+    let (n1, n2): (Vec3, Vec3) = (s1.cross(&t1).unit_vector(), s2.cross(&t2).unit_vector());
+    let tol: f32 = MATCH_TOL * (s1.length() + t1.length() + s2.length() + t2.length());
+    let r12: Vec3 = c2 - c1;
+    // Each form in front of the other.
+    if r12.dot(&n1) <= tol || r12.dot(&n2) >= -tol {
+        return None;
+    }
+    let corners = |c: Vec3, s: Vec3, t: Vec3| -> [Vec3; 4] {
+        [c - (s + t) * 5e-1, c + (s - t) * 5e-1, c + (s + t) * 5e-1, c - (s - t) * 5e-1]
+    };
+    let (corners_1, corners_2): ([Vec3; 4], [Vec3; 4]) = (corners(c1, s1, t1), corners(c2, s2, t2));
+    match (n1.dot(&n2) + 1e0 < MATCH_TOL, n1.dot(&n2).abs() < MATCH_TOL) {
+        // Opposed: the second one is the first one moved along its normal.
+        (true, _) => {
+            let c: f32 = r12.dot(&n1);
+            let opposed: bool = corners_1.iter()
+                .all(|p| corners_2.iter().any(|q| (*q - *p - n1 * c).length() < tol));
+            match opposed {
+                true => Some(Configuration::ParallelRectangles(s1.length(), t1.length(), c)),
+                false => None,
+            }
+        }
+        // Perpendicular: two shared corners make a whole common edge.
+        (_, true) => {
+            let shared: Vec<Vec3> = corners_1.iter()
+                .filter(|p| corners_2.iter().any(|q| (*q - **p).length() < tol))
+                .copied()
+                .collect();
+            match shared.len() == 2 {
+                true => {
+                    let l: f32 = (shared[1] - shared[0]).length();
+                    let (w, h): (f32, f32) = (s1.length() * t1.length() / l, s2.length() * t2.length() / l);
+                    match (s1.length() - l).abs().min((t1.length() - l).abs()) < tol
+                        && (s2.length() - l).abs().min((t2.length() - l).abs()) < tol {
+                        true => Some(Configuration::PerpendicularRectangles(l, w, h)),
+                        false => None,
+                    }
+                }
+                false => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Configuration of two spheres: a sphere inside a hollow one.
+fn sphere_configuration(form_1: &Form, form_2: &Form) -> Option<Configuration> {
+    let (r1, r2): (f32, f32) = (sphere_radius(form_1)?, sphere_radius(form_2)?);
+    let d: f32 = (sphere_center(form_2)? - sphere_center(form_1)?).length();
+    let inside = |r_in: f32, r_out: f32| r_in > 0e0 && r_out < 0e0 && d + r_in < -r_out * (1e0 - MATCH_TOL);
+    match (inside(r2, r1), inside(r1, r2)) {
+        (true, _) => Some(Configuration::EnclosureToSphere(r2, -r1)),
+        (_, true) => Some(Configuration::SphereToEnclosure),
+        _ => None,
+    }
+}
+
+/// Signed radius of a sphere, negative if hollow.
+fn sphere_radius(form: &Form) -> Option<f32> {
+    match form {
+        Form::Sphere(sphere) => Some(match sphere.is_hollow() {
+            true => -sphere.radius(),
+            false => sphere.radius(),
+        }),
+        _ => None,
+    }
+}
+
+/// Center of a sphere at rest pose, where the Monte Carlo estimators
+/// take it (``get_center`` misses the offset of the first keyframe).
+fn sphere_center(form: &Form) -> Option<Vec3> {
+    match form {
+        Form::Sphere(sphere) => Some(sphere.center_at(0e0)),
+        _ => None,
+    }
+}
+
+/// Axis aligned bounding box of a form, none if unknown.
+fn bounds(form: &Form) -> Option<(Vec3, Vec3)> {
+    match form {
+        Form::Sphere(_) => {
+            let r: f32 = sphere_radius(form)?.abs();
+            let center: Vec3 = sphere_center(form)?;
+            Some((center - Vec3::new(r, r, r), center + Vec3::new(r, r, r)))
+        }
+        Form::Rectangle(_) | Form::Square(_) => {
+            let corners: Vec<Vec3> = [(0e0, 0e0), (1e0, 0e0), (0e0, 1e0), (1e0, 1e0)].iter()
+                .map(|(s, t)| form.point(*s, *t))
+                .collect();
+            let fold = |f: fn(f32, f32) -> f32, a: usize| corners.iter().map(|p| p.e[a]).fold(corners[0].e[a], f);
+            Some((
+                Vec3::new(fold(f32::min, 0), fold(f32::min, 1), fold(f32::min, 2)),
+                Vec3::new(fold(f32::max, 0), fold(f32::max, 1), fold(f32::max, 2))
+            ))
+        }
+        _ => None,
+    }
+}
@@ -0,0 +1,144 @@
+//! Animation module. Keyframed camera parameters and frame
+//! sequence timing. Object transforms are animated through their
+//! `Motion` (see `objects::Motion`).
+
+use crate::vectors::Vec3;
+use crate::cameras::{Camera, CameraSimple, CameraFocus};
+
+/// Camera parameters at a given time.
+#[derive(Copy, Clone)]
+pub struct CameraKeyframe {
+    /// Keyframe time.
+    pub time: f32,
+    /// Camera origin.
+    pub look_from: Vec3,
+    /// Point at which the camera points.
+    pub look_at: Vec3,
+    /// FOV in degrees along vertical axis.
+    pub vfov: f32,
+    /// Distance to the plane that is being focused.
+    pub focus_dist: f32,
+}
+
+/// Keyframed camera. Parameters are linearly interpolated between
+/// keyframes and held before the first and after the last one.
+pub struct CameraAnimation {
+    /// Keyframes sorted by time.
+    keys: Vec<CameraKeyframe>,
+    /// World's 'up' vector.
+    vup: Vec3,
+    /// Width to Height ratio.
+    aspect: f32,
+    /// Lens' aperture, a simple camera is used when it is 0.
+    aperture: f32,
+}
+
+/// Frame sequence timing.
+#[derive(Copy, Clone)]
+pub struct Sequence {
+    /// Time of the first frame.
+    pub start: f32,
+    /// Frames per time unit.
+    pub fps: f32,
+    /// Number of frames.
+    pub n_frames: usize,
+    /// Fraction of the frame time during which the shutter is open
+    /// (0.5 for a 180° shutter, 0 for no motion blur).
+    pub shutter: f32,
+}
+
+impl CameraKeyframe {
+    /// CameraKeyframe constructor.
+    ///
+    /// # Parameters:
+    /// * `time` - Keyframe time.
+    /// * `look_from` - Camera origin.
+    /// * `look_at` - Point at which the camera points.
+    /// * `vfov` - FOV in degrees along vertical axis.
+    /// * `focus_dist` - Distance to the plane that is being focused.
+    pub fn new(time: f32, look_from: Vec3, look_at: Vec3, vfov: f32, focus_dist: f32) -> CameraKeyframe {
+        CameraKeyframe { time, look_from, look_at, vfov, focus_dist }
+    }
+
+    /// Linear interpolation towards ``other`` by the fraction ``f``.
+    fn lerp(&self, other: &CameraKeyframe, f: f32) -> CameraKeyframe {
+        CameraKeyframe {
+            time: self.time + (other.time - self.time) * f,
+            look_from: self.look_from + (other.look_from - self.look_from) * f,
+            look_at: self.look_at + (other.look_at - self.look_at) * f,
+            vfov: self.vfov + (other.vfov - self.vfov) * f,
+            focus_dist: self.focus_dist + (other.focus_dist - self.focus_dist) * f,
+        }
+    }
+}
+
+impl CameraAnimation {
+    /// CameraAnimation constructor, without keyframes.
+    ///
+    /// # Parameters:
+    /// * `vup` - World's 'up' vector.
+    /// * `aspect` - Width to Height ratio.
+    /// * `aperture` - Lens' aperture (0 for a pinhole camera).
+    pub fn new(vup: Vec3, aspect: f32, aperture: f32) -> CameraAnimation {
+        CameraAnimation { keys: Vec::new(), vup, aspect, aperture }
+    }
+
+    /// Adds a keyframe, keeping them sorted by time.
+    pub fn push(&mut self, key: CameraKeyframe) {
+        let idx: usize = self.keys.iter().take_while(|k| k.time <= key.time).count();
+        self.keys.insert(idx, key);
+    }
+
+    /// Interpolated camera parameters at ``time``, `None` if there
+    /// are no keyframes.
+    pub fn keyframe_at(&self, time: f32) -> Option<CameraKeyframe> {
+        let first: CameraKeyframe = *self.keys.first()?;
+        if time <= first.time {
+            return Some(first);
+        }
+        for pair in self.keys.windows(2) {
+            if time <= pair[1].time {
+                let f: f32 = (time - pair[0].time) / (pair[1].time - pair[0].time).max(1e-9);
+                return Some(pair[0].lerp(&pair[1], f));
+            }
+        }
+        self.keys.last().cloned()
+    }
+
+    /// Camera at ``time``, `None` if there are no keyframes.
+    pub fn camera_at(&self, time: f32) -> Option<Camera> {
+        let key: CameraKeyframe = self.keyframe_at(time)?;
+        match self.aperture > 0e0 {
+            true => Some(Camera::Focus(CameraFocus::new(
+                key.look_from, key.look_at, self.vup, key.vfov, self.aspect, self.aperture, key.focus_dist
+            ))),
+            false => Some(Camera::Simple(CameraSimple::new(
+                key.look_from, key.look_at, self.vup, key.vfov, self.aspect
+            ))),
+        }
+    }
+}
+
+impl Sequence {
+    /// Sequence constructor.
+    ///
+    /// # Parameters:
+    /// * `start` - Time of the first frame.
+    /// * `fps` - Frames per time unit.
+    /// * `n_frames` - Number of frames.
+    /// * `shutter` - Fraction of the frame time with the shutter open.
+    pub fn new(start: f32, fps: f32, n_frames: usize, shutter: f32) -> Sequence {
+        Sequence { start, fps, n_frames, shutter }
+    }
+
+    /// Time at which frame ``k`` starts.
+    pub fn frame_time(&self, k: usize) -> f32 {
+        self.start + (k as f32) / self.fps
+    }
+
+    /// Shutter opening and closing times of frame ``k``.
+    pub fn frame_shutter(&self, k: usize) -> (f32, f32) {
+        let open: f32 = self.frame_time(k);
+        (open, open + self.shutter / self.fps)
+    }
+}
@@ -0,0 +1,272 @@
+//! Auxiliary output variables (AOVs) module. Per-pixel data of the
+//! primary hits (first surface seen through each pixel): distance,
+//! normal, albedo, position and object index, used for compositing,
+//! debugging and as denoising guides.
+
+use std::io::Result;
+
+use crate::vectors::{Vec3, Vec3Methods};
+use crate::rays::Ray;
+use crate::hittable::HitRecord;
+use crate::materials::MaterialAlbedo;
+use crate::film::Framebuffer;
+use crate::postprocess::{PostProcess, Transfer};
+
+/// Auxiliary buffer kinds.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Aov {
+    /// Distance from the camera to the hit point (``t`` times the
+    /// ray direction's length), infinite where nothing is hit.
+    Depth,
+    /// World space surface normal.
+    Normal,
+    /// First hit surface color (see `MaterialAlbedo`).
+    Albedo,
+    /// World space hit point.
+    Position,
+    /// Index of the hit form on the world (`HitRecord::hit_elem`),
+    /// -1 where nothing is hit.
+    ObjectId,
+}
+
+impl Aov {
+    /// Every buffer kind.
+    pub const ALL: [Aov; 5] = [Aov::Depth, Aov::Normal, Aov::Albedo, Aov::Position, Aov::ObjectId];
+
+    /// Buffer name, used as file suffix.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Aov::Depth => "depth",
+            Aov::Normal => "normal",
+            Aov::Albedo => "albedo",
+            Aov::Position => "position",
+            Aov::ObjectId => "object_id",
+        }
+    }
+}
+
+/// Primary hit data of the rays through one pixel.
+/// Normals and albedos are averaged over every ray (misses count
+/// as zero, as the background does on the image), distances and
+/// positions over the rays that hit something.
+pub struct AovEstimate {
+    /// Number of rays.
+    samples: u32,
+    /// Number of rays that hit a form.
+    hits: u32,
+    /// Sum of the hit distances.
+    depth: f32,
+    /// Sum of the normals.
+    normal: Vec3,
+    /// Sum of the albedos.
+    albedo: Vec3,
+    /// Sum of the hit points.
+    position: Vec3,
+    /// Form seen through the pixel's center.
+    object: Option<usize>,
+}
+
+impl Default for AovEstimate {
+    fn default() -> AovEstimate {
+        AovEstimate::new()
+    }
+}
+
+impl AovEstimate {
+    /// Empty estimate.
+    pub fn new() -> AovEstimate {
+        AovEstimate {
+            samples: 0,
+            hits: 0,
+            depth: 0e0,
+            normal: Vec3::zeros(),
+            albedo: Vec3::zeros(),
+            position: Vec3::zeros(),
+            object: None,
+        }
+    }
+
+    /// Adds the primary hit ``rec``, if any, of ``ray``.
+    pub fn add(&mut self, ray: &Ray, rec: Option<&HitRecord>) {
+        self.samples += 1;
+        if let Some(hit_rec) = rec {
+            self.hits += 1;
+            self.depth += hit_rec.t * ray.direction().length();
+            self.normal += hit_rec.normal;
+            self.albedo += hit_rec.material.albedo(hit_rec);
+            self.position += hit_rec.p;
+        }
+    }
+
+    /// Sets the form seen through the pixel's center. Object indices
+    /// are not averaged, since a mean index is meaningless.
+    pub fn set_object(&mut self, rec: Option<&HitRecord>) {
+        self.object = rec.map(|hit_rec| hit_rec.hit_elem);
+    }
+
+    /// Mean hit distance, infinite if no ray hit.
+    pub fn depth(&self) -> f32 {
+        match self.hits {
+            0 => f32::INFINITY,
+            n => self.depth / n as f32,
+        }
+    }
+
+    /// Mean normal (not normalized, shorter on silhouettes).
+    pub fn normal(&self) -> Vec3 { self.normal / self.samples.max(1) as f32 }
+
+    /// Mean albedo.
+    pub fn albedo(&self) -> Vec3 { self.albedo / self.samples.max(1) as f32 }
+
+    /// Mean hit point, the origin if no ray hit.
+    pub fn position(&self) -> Vec3 { self.position / self.hits.max(1) as f32 }
+
+    /// Form seen through the pixel's center.
+    pub fn object(&self) -> Option<usize> { self.object }
+}
+
+/// Auxiliary buffers, one framebuffer per `Aov` kind. Scalar
+/// buffers (depth and object index) repeat their value on the
+/// three channels.
+pub struct AovBuffers {
+    /// Hit distances.
+    pub depth: Framebuffer,
+    /// Surface normals.
+    pub normal: Framebuffer,
+    /// Surface albedos.
+    pub albedo: Framebuffer,
+    /// Hit points.
+    pub position: Framebuffer,
+    /// Form indices.
+    pub object_id: Framebuffer,
+}
+
+impl AovBuffers {
+    /// Empty buffers constructor.
+    ///
+    /// # Parameters:
+    /// * `width_px` - Width in pixels.
+    /// * `height_px` - Height in pixels.
+    pub fn new(width_px: u16, height_px: u16) -> AovBuffers {
+        AovBuffers {
+            depth: Framebuffer::new(width_px, height_px),
+            normal: Framebuffer::new(width_px, height_px),
+            albedo: Framebuffer::new(width_px, height_px),
+            position: Framebuffer::new(width_px, height_px),
+            object_id: Framebuffer::new(width_px, height_px),
+        }
+    }
+
+    /// Buffer of the given kind.
+    pub fn layer(&self, aov: Aov) -> &Framebuffer {
+        match aov {
+            Aov::Depth => &self.depth,
+            Aov::Normal => &self.normal,
+            Aov::Albedo => &self.albedo,
+            Aov::Position => &self.position,
+            Aov::ObjectId => &self.object_id,
+        }
+    }
+
+    /// Stores the estimate of pixel ``(i, j)`` on every buffer.
+    pub fn set(&mut self, i: u16, j: u16, estimate: &AovEstimate) {
+        let object: f32 = match estimate.object() {
+            Some(idx) => idx as f32,
+            None => -1e0,
+        };
+        self.depth.set(i, j, Vec3::ones() * estimate.depth());
+        self.normal.set(i, j, estimate.normal());
+        self.albedo.set(i, j, estimate.albedo());
+        self.position.set(i, j, estimate.position());
+        self.object_id.set(i, j, Vec3::ones() * object);
+    }
+
+    /// Displayable version of a buffer, with values from 0 to 1:
+    /// * `Depth` - white at the camera, fading to black at the farthest hit.
+    /// * `Normal` - $\frac{1}{2}(\vec n+1)$.
+    /// * `Albedo` - unchanged.
+    /// * `Position` - scaled to the bounding box of the hit points.
+    /// * `ObjectId` - a distinct color per form, black where nothing is hit.
+    pub fn preview(&self, aov: Aov) -> Framebuffer {
+        let layer: &Framebuffer = self.layer(aov);
+        let hits: Vec<bool> = self.object_id.pixels.iter().map(|id| id.x() >= 0e0).collect();
+        let pixels: Vec<Vec3> = match aov {
+            Aov::Depth => {
+                let far: f32 = layer.pixels.iter()
+                    .map(|d| d.x())
+                    .filter(|d| d.is_finite())
+                    .fold(0e0, f32::max);
+                layer.pixels.iter().map(|d| match d.x().is_finite() && far > 0e0 {
+                    true => Vec3::ones() * (1e0 - d.x() / far),
+                    false => Vec3::zeros(),
+                }).collect()
+            }
+            Aov::Normal => layer.pixels.iter().map(|n| (*n + Vec3::ones()) * 5e-1).collect(),
+            Aov::Albedo => layer.pixels.clone(),
+            Aov::Position => {
+                let mut lo: Vec3 = Vec3::ones() * f32::MAX;
+                let mut hi: Vec3 = Vec3::ones() * f32::MIN;
+                for (p, hit) in layer.pixels.iter().zip(hits.iter()) {
+                    if *hit {
+                        for c in 0..3 {
+                            lo.e[c] = lo.e[c].min(p.e[c]);
+                            hi.e[c] = hi.e[c].max(p.e[c]);
+                        }
+                    }
+                }
+                layer.pixels.iter().zip(hits.iter()).map(|(p, hit)| match hit {
+                    true => {
+                        let mut col: Vec3 = Vec3::zeros();
+                        for c in 0..3 {
+                            col.e[c] = (p.e[c] - lo.e[c]) / (hi.e[c] - lo.e[c]).max(f32::EPSILON);
+                        }
+                        col
+                    }
+                    false => Vec3::zeros(),
+                }).collect()
+            }
+            Aov::ObjectId => layer.pixels.iter().map(|id| match id.x() >= 0e0 {
+                true => object_color(id.x() as usize),
+                false => Vec3::zeros(),
+            }).collect(),
+        };
+        Framebuffer { pixels, ..Framebuffer::new(layer.width_px, layer.height_px) }
+    }
+
+    /// Writes every buffer as a float (PFM) image, ``<prefix>_<name>.pfm``,
+    /// and its preview as a PPM image, ``<prefix>_<name>.ppm``.
+    ///
+    /// # Parameters:
+    /// * `prefix` - Path prefix of the images.
+    ///
+    /// # Returns:
+    /// * `Result<Vec<String>>` - Paths of the written images.
+    pub fn write(&self, prefix: &str) -> Result<Vec<String>> {
+        // Previews are display values already, no encoding.
+        let post: PostProcess = PostProcess { transfer: Transfer::Linear, ..PostProcess::new() };
+        let mut paths: Vec<String> = Vec::new();
+        for aov in Aov::ALL.iter() {
+            let pfm: String = format!("{}_{}.pfm", prefix, aov.name());
+            let ppm: String = format!("{}_{}.ppm", prefix, aov.name());
+            self.layer(*aov).write_pfm(&pfm)?;
+            self.preview(*aov).write_ppm(&ppm, &post)?;
+            paths.push(pfm);
+            paths.push(ppm);
+        }
+        Ok(paths)
+    }
+}
+
+/// Distinct false color of a form index (golden ratio hue steps).
+pub fn object_color(idx: usize) -> Vec3 {
+    let hue: f32 = (idx as f32 * 0.618_034).fract() * 6e0;
+    let x: f32 = 1e0 - (hue % 2e0 - 1e0).abs();
+    match hue as u8 {
+        0 => Vec3::new(1e0, x, 0e0),
+        1 => Vec3::new(x, 1e0, 0e0),
+        2 => Vec3::new(0e0, 1e0, x),
+        3 => Vec3::new(0e0, x, 1e0),
+        4 => Vec3::new(x, 0e0, 1e0),
+        _ => Vec3::new(1e0, 0e0, x),
+    }
+}
@@ -2,14 +2,20 @@
 //! which will collide with the world objects and change colors,
 //! thus composing the world image.
 
+use std::f32::consts::PI;
+
 use crate::vectors::{Vec3, Vec3Methods};
 use crate::rays::Ray;
+use crate::objects::{Form, SurfaceFunctions};
 use rand::Rng;
 
 /// Camera structure (enum).
 pub enum Camera {
     Simple(CameraSimple),
     Focus(CameraFocus),
+    Orthographic(CameraOrthographic),
+    Fisheye(CameraFisheye),
+    Equirectangular(CameraEquirectangular),
 }
 
 
@@ -25,6 +31,75 @@ pub trait CameraRay{
     /// * `s` - Horizontal parameter from 0 to 1.
     /// * `t` - Vertical parameter from 0 to 1.
     fn get_ray(&self, s: f32, t: f32) -> Ray;
+
+    /// Whether the image point defined by ``s`` and ``t`` is
+    /// covered by the camera's projection (pixels out of it are black).
+    fn covers(&self, _s: f32, _t: f32) -> bool { true }
+
+    /// Same as `get_ray`, with the point on the lens given by two
+    /// uniform numbers ``lens`` instead of random ones (cameras
+    /// without aperture ignore them).
+    fn get_ray_lens(&self, s: f32, t: f32, _lens: (f32, f32)) -> Ray { self.get_ray(s, t) }
+}
+
+/// Shutter interval. Camera rays are uniformly spread in
+/// time between the shutter opening and closing times.
+#[derive(Copy, Clone)]
+pub struct Shutter {
+    /// Opening time.
+    pub open: f32,
+    /// Closing time.
+    pub close: f32,
+}
+
+impl Shutter {
+    /// Shutter constructor.
+    ///
+    /// # Parameters:
+    /// * `open` - Opening time.
+    /// * `close` - Closing time.
+    pub fn new(open: f32, close: f32) -> Shutter { Shutter { open, close } }
+
+    /// Instantaneous shutter at time 0 (no motion blur).
+    pub fn instant() -> Shutter { Shutter::new(0e0, 0e0) }
+
+    /// Random time within the shutter interval.
+    pub fn sample(&self) -> f32 {
+        match self.close > self.open {
+            true => self.open + (self.close - self.open) * rand::random::<f32>(),
+            false => self.open,
+        }
+    }
+}
+
+impl Camera {
+    /// Camera's shutter interval.
+    pub fn shutter(&self) -> Shutter {
+        match self {
+            Camera::Simple(simple) => simple.shutter,
+            Camera::Focus(focus) => focus.shutter,
+            Camera::Orthographic(ortho) => ortho.shutter,
+            Camera::Fisheye(fisheye) => fisheye.shutter,
+            Camera::Equirectangular(equirect) => equirect.shutter,
+        }
+    }
+
+    /// Sets the shutter interval, objects moving while the
+    /// shutter is open are motion blurred.
+    ///
+    /// # Parameters:
+    /// * `open` - Shutter opening time.
+    /// * `close` - Shutter closing time.
+    pub fn set_shutter(&mut self, open: f32, close: f32) {
+        let shutter: Shutter = Shutter::new(open, close);
+        match self {
+            Camera::Simple(simple) => simple.shutter = shutter,
+            Camera::Focus(focus) => focus.shutter = shutter,
+            Camera::Orthographic(ortho) => ortho.shutter = shutter,
+            Camera::Fisheye(fisheye) => fisheye.shutter = shutter,
+            Camera::Equirectangular(equirect) => equirect.shutter = shutter,
+        }
+    }
 }
 
 
@@ -33,6 +108,23 @@ impl CameraRay for Camera {
         match self {
             Camera::Simple(simple) => simple.get_ray(s, t),
             Camera::Focus(focus) => focus.get_ray(s, t),
+            Camera::Orthographic(ortho) => ortho.get_ray(s, t),
+            Camera::Fisheye(fisheye) => fisheye.get_ray(s, t),
+            Camera::Equirectangular(equirect) => equirect.get_ray(s, t),
+        }
+    }
+
+    fn get_ray_lens(&self, s: f32, t: f32, lens: (f32, f32)) -> Ray {
+        match self {
+            Camera::Focus(focus) => focus.get_ray_lens(s, t, lens),
+            _ => self.get_ray(s, t),
+        }
+    }
+
+    fn covers(&self, s: f32, t: f32) -> bool {
+        match self {
+            Camera::Fisheye(fisheye) => fisheye.covers(s, t),
+            _ => true,
         }
     }
 }
@@ -42,7 +134,8 @@ pub struct CameraSimple {
     origin: Vec3,
     lower_left_corner: Vec3,
     horizontal: Vec3,
-    vertical: Vec3
+    vertical: Vec3,
+    shutter: Shutter,
 }
 
 impl CameraSimple {
@@ -70,13 +163,14 @@ impl CameraSimple {
         lower_left_corner: look_from - u * half_width - v * half_height - w,
         horizontal: u * half_width * 2e0,
         vertical: v * half_height * 2e0,
+        shutter: Shutter::instant(),
         }
     }
 }
 
 impl CameraRay for CameraSimple {
     fn get_ray(&self, s: f32, t: f32) -> Ray{
-        Ray::new(self.origin, self.lower_left_corner + self.horizontal * s + self.vertical * t - self.origin)
+        Ray::timed(self.origin, self.lower_left_corner + self.horizontal * s + self.vertical * t - self.origin, self.shutter.sample())
     }
 }
 
@@ -90,6 +184,8 @@ pub struct CameraFocus{
     v: Vec3,
     _w: Vec3,
     lens_radius: f32,
+    focus_dist: f32,
+    shutter: Shutter,
 }
 
 impl CameraFocus{
@@ -121,24 +217,251 @@ impl CameraFocus{
             vertical: v * half_height * focus_dist * 2e0,
             u, v, _w: w,
             lens_radius: aperture / 2e0,
+            focus_dist,
+            shutter: Shutter::instant(),
         }
     }
+
+    /// Working f-number of the lens, ratio of the focus distance
+    /// to the aperture's diameter, $N = d_f / D$.
+    pub fn f_number(&self) -> f32 {
+        self.focus_dist / (2e0 * self.lens_radius)
+    }
+}
+
+impl CameraFocus{
+    /// Ray through the image point ``(s, t)`` from the lens point ``rd``
+    /// (on the unit disk).
+    fn ray_from_lens(&self, s: f32, t: f32, rd: Vec3) -> Ray {
+        let rd: Vec3 = rd * self.lens_radius;
+        let offset: Vec3 = self.u * rd.x() + self.v * rd.y();
+        Ray::timed(self.origin + offset, self.lower_left_corner + self.horizontal * s + self.vertical * t - self.origin - offset, self.shutter.sample())
+    }
 }
 
 impl CameraRay for CameraFocus{
     fn get_ray(&self, s: f32, t: f32) -> Ray{
-        let rd: Vec3 = random_in_unit_disk() * self.lens_radius;
-        let offset: Vec3 = self.u * rd.x() + self.v * rd.y();
-        Ray::new(self.origin + offset, self.lower_left_corner + self.horizontal * s + self.vertical * t - self.origin - offset)
+        self.ray_from_lens(s, t, random_in_unit_disk())
+    }
+
+    fn get_ray_lens(&self, s: f32, t: f32, lens: (f32, f32)) -> Ray {
+        self.ray_from_lens(s, t, sample_unit_disk(lens.0, lens.1))
+    }
+}
+
+/// Orthographic camera structure. All rays are parallel to
+/// the viewing direction, so that sizes do not depend on depth.
+pub struct CameraOrthographic {
+    lower_left_corner: Vec3,
+    horizontal: Vec3,
+    vertical: Vec3,
+    direction: Vec3,
+    shutter: Shutter,
+}
+
+impl CameraOrthographic {
+    /// CameraOrthographic constructor.
+    ///
+    /// # Parameters:
+    ///
+    /// * `look_from` - center of the image plane.
+    /// * `look_at` - point at which the camera points.
+    /// * `vup` - world's 'up' vector. Camera's vertical horizontal axis will be parallel to the world.
+    /// * `height` - height of the viewed region (world units).
+    /// * `aspect` - Width to Height ratio.
+    ///
+    /// # Returns:
+    /// * `CameraOrthographic` - Camera object.
+    pub fn new(look_from: Vec3, look_at: Vec3, vup: Vec3, height: f32, aspect: f32) -> CameraOrthographic {
+        let w: Vec3 = (look_from - look_at).unit_vector();
+        let u: Vec3 = vup.cross(&w).unit_vector();
+        let v: Vec3 = w.cross(&u).unit_vector();
+        let half_height: f32 = height / 2e0;
+        let half_width: f32 = aspect * half_height;
+        CameraOrthographic {
+            lower_left_corner: look_from - u * half_width - v * half_height,
+            horizontal: u * half_width * 2e0,
+            vertical: v * half_height * 2e0,
+            direction: -w,
+            shutter: Shutter::instant(),
+        }
+    }
+}
+
+impl CameraRay for CameraOrthographic {
+    fn get_ray(&self, s: f32, t: f32) -> Ray {
+        Ray::timed(self.lower_left_corner + self.horizontal * s + self.vertical * t, self.direction, self.shutter.sample())
+    }
+}
+
+/// Fisheye lens projections, radial image distance $r$ (normalized
+/// to 1 at the image circle) as a function of the angle $\theta$ to the axis.
+#[derive(Copy, Clone)]
+pub enum FisheyeProjection {
+    /// Equidistant projection, $r\propto\theta$.
+    Equidistant,
+    /// Orthographic (Nusselt analog) projection, $r\propto\sin\theta$.
+    /// With a 180° field of view, the fraction of the image circle covered
+    /// by an object equals the view factor from the camera's surface
+    /// element to the object.
+    Nusselt,
+}
+
+/// Fisheye camera structure. The image circle is inscribed in the image.
+pub struct CameraFisheye {
+    origin: Vec3,
+    u: Vec3,
+    v: Vec3,
+    w: Vec3,
+    half_fov: f32,
+    aspect: f32,
+    projection: FisheyeProjection,
+    shutter: Shutter,
+}
+
+impl CameraFisheye {
+    /// CameraFisheye constructor.
+    ///
+    /// # Parameters:
+    ///
+    /// * `look_from` - camera origin.
+    /// * `axis` - viewing direction (image circle center).
+    /// * `vup` - world's 'up' vector, sets the image's vertical axis.
+    /// * `fov` - full field of view in degrees (up to 360).
+    /// * `aspect` - Width to Height ratio.
+    /// * `projection` - lens projection.
+    ///
+    /// # Returns:
+    /// * `CameraFisheye` - Camera object.
+    pub fn new(look_from: Vec3, axis: Vec3, vup: Vec3, fov: f32, aspect: f32, projection: FisheyeProjection) -> CameraFisheye {
+        let w: Vec3 = axis.unit_vector();
+        let (u_any, _) = w.orthonormal_basis();
+        let u: Vec3 = match vup.cross(&w).length() > 1e-6 {
+            true => w.cross(&vup).unit_vector(),
+            false => u_any,
+        };
+        let v: Vec3 = u.cross(&w);
+        CameraFisheye {
+            origin: look_from,
+            u,
+            v,
+            w,
+            half_fov: fov * PI / 360e0,
+            aspect,
+            projection,
+            shutter: Shutter::instant(),
+        }
+    }
+
+    /// Hemispherical (180°, Nusselt analog) camera lying on the surface
+    /// of ``form`` at the adimensional parameters ``s`` and ``t``, looking
+    /// along the surface normal.
+    ///
+    /// # Parameters:
+    ///
+    /// * `form` - surface on which the camera is placed.
+    /// * `s` - First adimensional surface parameter from 0 to 1.
+    /// * `t` - Second adimensional surface parameter from 0 to 1.
+    /// * `vup` - world's 'up' vector, sets the image's vertical axis.
+    /// * `aspect` - Width to Height ratio.
+    pub fn on_surface(form: &Form, s: f32, t: f32, vup: Vec3, aspect: f32) -> CameraFisheye {
+        let normal: Vec3 = form.normal(s, t).unit_vector();
+        // Slightly above the surface, so that it does not hide itself.
+        let origin: Vec3 = form.point(s, t) + normal * 1e-4;
+        CameraFisheye::new(origin, normal, vup, 180e0, aspect, FisheyeProjection::Nusselt)
+    }
+
+    /// Image coordinates centered on the image circle, with radius 1 at its edge.
+    fn image_coordinates(&self, s: f32, t: f32) -> (f32, f32) {
+        let (x, y) = (2e0 * s - 1e0, 2e0 * t - 1e0);
+        match self.aspect >= 1e0 {
+            true => (x * self.aspect, y),
+            false => (x, y / self.aspect),
+        }
+    }
+}
+
+impl CameraRay for CameraFisheye {
+    fn get_ray(&self, s: f32, t: f32) -> Ray {
+        let (x, y) = self.image_coordinates(s, t);
+        let r: f32 = (x.powi(2) + y.powi(2)).sqrt().min(1e0);
+        let theta: f32 = match self.projection {
+            FisheyeProjection::Equidistant => r * self.half_fov,
+            FisheyeProjection::Nusselt => (r * self.half_fov.min(PI / 2e0).sin()).asin(),
+        };
+        let phi: f32 = y.atan2(x);
+        let direction: Vec3 = (self.u * phi.cos() + self.v * phi.sin()) * theta.sin() + self.w * theta.cos();
+        Ray::timed(self.origin, direction, self.shutter.sample())
+    }
+
+    fn covers(&self, s: f32, t: f32) -> bool {
+        let (x, y) = self.image_coordinates(s, t);
+        x.powi(2) + y.powi(2) <= 1e0
+    }
+}
+
+/// Equirectangular (latitude-longitude) 360° panorama camera.
+/// Horizontal image axis spans the longitude from -180° to 180°
+/// around ``vup``, vertical axis spans the latitude from -90° to 90°.
+pub struct CameraEquirectangular {
+    origin: Vec3,
+    forward: Vec3,
+    right: Vec3,
+    up: Vec3,
+    shutter: Shutter,
+}
+
+impl CameraEquirectangular {
+    /// CameraEquirectangular constructor.
+    ///
+    /// # Parameters:
+    ///
+    /// * `look_from` - camera origin.
+    /// * `look_at` - point shown at the image center.
+    /// * `vup` - world's 'up' vector (panorama's pole).
+    ///
+    /// # Returns:
+    /// * `CameraEquirectangular` - Camera object.
+    pub fn new(look_from: Vec3, look_at: Vec3, vup: Vec3) -> CameraEquirectangular {
+        let up: Vec3 = vup.unit_vector();
+        let view: Vec3 = look_at - look_from;
+        let forward: Vec3 = (view - up * view.dot(&up)).unit_vector();
+        let right: Vec3 = forward.cross(&up);
+        CameraEquirectangular { origin: look_from, forward, right, up, shutter: Shutter::instant() }
+    }
+}
+
+impl CameraRay for CameraEquirectangular {
+    fn get_ray(&self, s: f32, t: f32) -> Ray {
+        let longitude: f32 = 2e0 * PI * (s - 5e-1);
+        let latitude: f32 = PI * (t - 5e-1);
+        let direction: Vec3 = (self.forward * longitude.cos() + self.right * longitude.sin()) * latitude.cos()
+            + self.up * latitude.sin();
+        Ray::timed(self.origin, direction, self.shutter.sample())
     }
 }
 
 /// Random point on a unit-disk on plane Z=0.
 fn random_in_unit_disk() -> Vec3{
     let mut rng = rand::thread_rng();
-    let mut p: Vec3 = Vec3::new(2e0, 2e0, 2e0);
-    while p.square_length() >= 1e0 {
-        p = Vec3::new(rng.gen::<f32>(), rng.gen::<f32>(), 0e0) * 2e0 - Vec3::new(1e0, 1e0, 0e0);
+    sample_unit_disk(rng.gen::<f32>(), rng.gen::<f32>())
+}
+
+/// Point on a unit-disk on plane Z=0 from two uniform numbers
+/// (Shirley-Chiu concentric mapping, which keeps the stratification
+/// of the input points).
+///
+/// # Parameters:
+/// * `u1` - First uniform number from 0 to 1.
+/// * `u2` - Second uniform number from 0 to 1.
+pub fn sample_unit_disk(u1: f32, u2: f32) -> Vec3 {
+    let (a, b) = (2e0 * u1 - 1e0, 2e0 * u2 - 1e0);
+    if a == 0e0 && b == 0e0 {
+        return Vec3::zeros();
     }
-    return p;
+    let (r, phi) = match a.abs() > b.abs() {
+        true => (a, PI / 4e0 * (b / a)),
+        false => (b, PI / 2e0 - PI / 4e0 * (a / b)),
+    };
+    Vec3::new(r * phi.cos(), r * phi.sin(), 0e0)
 }
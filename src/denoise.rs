@@ -0,0 +1,135 @@
+//! Denoising module. Edge preserving filter of the linear framebuffer,
+//! guided by the auxiliary (albedo and normal) buffers, so that
+//! renders with few samples per pixel look smooth.
+
+use crate::vectors::{Vec3, Vec3Methods};
+use crate::film::Framebuffer;
+use crate::aov::AovBuffers;
+
+/// Albedo below which the color is not demodulated.
+const MIN_ALBEDO: f32 = 1e-2;
+
+/// Joint (cross) bilateral filter.
+///
+/// The radiance is first divided by the albedo, so that textures are
+/// not blurred, and each pixel $p$ is replaced by the weighted mean of
+/// its neighbours $q$ within ``radius`` pixels:
+/// $$w_{pq}=\exp\left(-\frac{\left|p-q\right|^2}{2\sigma_s^2}
+/// -\frac{\left|a_p-a_q\right|^2}{2\sigma_a^2}
+/// -\frac{\left|n_p-n_q\right|^2}{2\sigma_n^2}
+/// -\frac{\left|c_p-c_q\right|^2}{2\sigma_c^2\left(\epsilon+\left|c_p\right|^2+\left|c_q\right|^2\right)}\right)$$
+/// where $a$ are the albedos, $n$ the normals and $c$ the demodulated
+/// colors. The result is blended with the input by ``strength``.
+/// Details the guides cannot see (reflections on mirrors, shadows on
+/// flat surfaces) are only kept by the color term.
+#[derive(Copy, Clone)]
+pub struct Denoiser {
+    /// Filter half width, in pixels.
+    pub radius: u16,
+    /// Spatial standard deviation $\sigma_s$, in pixels.
+    pub sigma_spatial: f32,
+    /// Albedo standard deviation $\sigma_a$.
+    pub sigma_albedo: f32,
+    /// Normal standard deviation $\sigma_n$.
+    pub sigma_normal: f32,
+    /// Relative color standard deviation $\sigma_c$.
+    pub sigma_color: f32,
+    /// Blend from 0 (input image) to 1 (filtered image).
+    pub strength: f32,
+}
+
+impl Denoiser {
+    /// Denoiser with default standard deviations.
+    ///
+    /// # Parameters:
+    /// * `strength` - Blend from 0 (no denoising) to 1 (full denoising).
+    pub fn new(strength: f32) -> Denoiser {
+        Denoiser {
+            radius: 4,
+            sigma_spatial: 2.5,
+            sigma_albedo: 5e-2,
+            sigma_normal: 2e-1,
+            sigma_color: 1e0,
+            strength: strength.clamp(0e0, 1e0),
+        }
+    }
+
+    /// Filters a framebuffer.
+    ///
+    /// # Parameters:
+    /// * `film` - Noisy linear image.
+    /// * `aovs` - Auxiliary buffers of the same view (see `render_aovs`).
+    ///
+    /// # Returns:
+    /// * `Framebuffer` - Denoised image, with the sample counts and
+    ///   path statistics of ``film``.
+    pub fn denoise(&self, film: &Framebuffer, aovs: &AovBuffers) -> Framebuffer {
+        let (w, h): (usize, usize) = (film.width_px as usize, film.height_px as usize);
+        let albedo: &Vec<Vec3> = &aovs.albedo.pixels;
+        let normal: &Vec<Vec3> = &aovs.normal.pixels;
+        let divisor: Vec<Vec3> = albedo.iter().map(|a| demodulation(*a)).collect();
+        let irradiance: Vec<Vec3> = film.pixels.iter().zip(divisor.iter())
+            .map(|(c, a)| Vec3::new(c.x() / a.x(), c.y() / a.y(), c.z() / a.z()))
+            .collect();
+
+        let r: isize = self.radius as isize;
+        let inv_s: f32 = 1e0 / (2e0 * self.sigma_spatial.powi(2));
+        let inv_a: f32 = 1e0 / (2e0 * self.sigma_albedo.powi(2));
+        let inv_n: f32 = 1e0 / (2e0 * self.sigma_normal.powi(2));
+        let inv_c: f32 = 1e0 / (2e0 * self.sigma_color.powi(2));
+
+        let mut pixels: Vec<Vec3> = Vec::with_capacity(w * h);
+        for y in 0..h as isize {
+            for x in 0..w as isize {
+                let p: usize = y as usize * w + x as usize;
+                let c_p: Vec3 = irradiance[p];
+                // Non-finite values are left to the post-processing.
+                if !c_p.e.iter().all(|c| c.is_finite()) {
+                    pixels.push(film.pixels[p]);
+                    continue;
+                }
+                let mut sum: Vec3 = Vec3::zeros();
+                let mut total: f32 = 0e0;
+                for qy in (y - r).max(0)..(y + r + 1).min(h as isize) {
+                    for qx in (x - r).max(0)..(x + r + 1).min(w as isize) {
+                        let q: usize = qy as usize * w + qx as usize;
+                        let c_q: Vec3 = irradiance[q];
+                        if !c_q.e.iter().all(|c| c.is_finite()) {
+                            continue;
+                        }
+                        let d2: f32 = ((qx - x).pow(2) + (qy - y).pow(2)) as f32;
+                        let a2: f32 = (albedo[q] - albedo[p]).square_length();
+                        let n2: f32 = (normal[q] - normal[p]).square_length();
+                        let c2: f32 = (c_q - c_p).square_length()
+                            / (1e-4 + c_p.square_length() + c_q.square_length());
+                        let weight: f32 = (-d2 * inv_s - a2 * inv_a - n2 * inv_n - c2 * inv_c).exp();
+                        sum += c_q * weight;
+                        total += weight;
+                    }
+                }
+                // The center pixel has weight 1, total is never 0.
+                let filtered: Vec3 = sum / total * divisor[p];
+                pixels.push(film.pixels[p] * (1e0 - self.strength) + filtered * self.strength);
+            }
+        }
+        Framebuffer {
+            width_px: film.width_px,
+            height_px: film.height_px,
+            pixels,
+            samples: film.samples.clone(),
+            paths: film.paths.clone(),
+        }
+    }
+}
+
+/// Per channel divisor removing the albedo from the radiance,
+/// 1 on (almost) black surfaces and background.
+fn demodulation(albedo: Vec3) -> Vec3 {
+    let mut divisor: Vec3 = Vec3::ones();
+    for c in 0..3 {
+        if albedo.e[c] > MIN_ALBEDO {
+            divisor.e[c] = albedo.e[c];
+        }
+    }
+    divisor
+}
@@ -0,0 +1,199 @@
+//! Radiative exchange module. Gebhart absorption factors of the world
+//! forms, tracing rays through diffuse and specular reflections until
+//! they are absorbed, so that mirrors and low emissivity surfaces are
+//! accounted for (geometric view factors assume black surfaces).
+
+use std::fmt::{Display, Formatter, Error};
+
+use crate::samplers::{Sampler, SamplerKind};
+use crate::vectors::Vec3;
+use crate::rays::Ray;
+use crate::objects::{Form, ObjectGetters, SurfaceFunctions, HittableList};
+use crate::hittable::HitRecord;
+use crate::materials::{MetalKind, MaterialThermoOptics, ThermoOptics};
+
+/// Maximum number of reflections of a ray, the energy still bouncing
+/// afterwards is lost (e.g. between two perfect mirrors).
+const MAX_REFLECTIONS: usize = 1000;
+
+/// Spectral bands of the radiation computations.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Band {
+    /// Thermal infrared, surfaces emit and absorb with their emissivity.
+    Infrared,
+    /// Solar spectrum, surfaces absorb with their solar absorptivity.
+    Solar,
+}
+
+/// Grey opaque surface radiative properties: the emissivity (equal to
+/// the absorptivity) and the diffuse and specular reflectivities add
+/// up to 1.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct SurfaceOptics {
+    /// Hemispherical emissivity $\varepsilon$.
+    pub emissivity: f32,
+    /// Diffuse reflectivity $\rho_d$.
+    pub diffuse: f32,
+    /// Specular reflectivity $\rho_s$.
+    pub specular: f32,
+}
+
+impl SurfaceOptics {
+    /// SurfaceOptics constructor.
+    ///
+    /// # Parameters:
+    /// * `emissivity` - Emissivity, from 0 to 1.
+    /// * `specularity` - Specular fraction of the reflectivity, from 0 to 1:
+    ///   $\rho_s=(1-\varepsilon)\\,s$ and $\rho_d=(1-\varepsilon)(1-s)$.
+    pub fn new(emissivity: f32, specularity: f32) -> SurfaceOptics {
+        let emissivity: f32 = emissivity.clamp(0e0, 1e0);
+        let specularity: f32 = specularity.clamp(0e0, 1e0);
+        SurfaceOptics {
+            emissivity,
+            diffuse: (1e0 - emissivity) * (1e0 - specularity),
+            specular: (1e0 - emissivity) * specularity,
+        }
+    }
+
+    /// Radiative properties of a surface in one band: the emissivity is
+    /// the infrared emissivity or the solar absorptivity.
+    pub fn from_thermo(optics: ThermoOptics, band: Band) -> SurfaceOptics {
+        match band {
+            Band::Infrared => SurfaceOptics::new(optics.emissivity, optics.specularity),
+            Band::Solar => SurfaceOptics::new(optics.absorptivity, optics.specularity),
+        }
+    }
+
+    /// Black body, $\varepsilon=1$.
+    pub fn black() -> SurfaceOptics { SurfaceOptics::new(1e0, 0e0) }
+
+    /// Total reflectivity, $\rho=\rho_d+\rho_s=1-\varepsilon$.
+    pub fn reflectivity(&self) -> f32 { self.diffuse + self.specular }
+}
+
+/// Gebhart factors of the world forms.
+pub struct Gebhart {
+    /// ``factors[i][j]`` is $B_{ij}$, the fraction of the power emitted
+    /// by form $i$ that form $j$ absorbs, after any number of
+    /// reflections. $1-\sum_j B_{ij}$ escapes the world.
+    pub factors: Vec<Vec<f32>>
+}
+
+impl Gebhart {
+    /// Radiative conductors, $GR_{ij}=\varepsilon_iA_iB_{ij}$, so that
+    /// the net power exchanged is $\sigma GR_{ij}\left(T_i^4-T_j^4\right)$.
+    ///
+    /// # Parameters:
+    /// * `world` - World the factors were computed on.
+    /// * `optics` - Radiative properties of each form.
+    pub fn radiative_conductors(&self, world: &HittableList, optics: &[SurfaceOptics]) -> Vec<Vec<f32>> {
+        self.factors.iter().enumerate()
+            .map(|(i, row)| {
+                let ea: f32 = optics[i].emissivity * world.forms[i].area();
+                row.iter().map(|b| ea * b).collect()
+            })
+            .collect()
+    }
+}
+
+/// Display trait implementation on Gebhart.
+impl Display for Gebhart {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        for (i, row) in self.factors.iter().enumerate() {
+            for (j, b) in row.iter().enumerate() {
+                writeln!(f, "B({},{}) = {:.4}", i, j, b)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Radiative exchange factors trait for world.
+pub trait ExchangeFactors {
+    /// Gebhart factors from one form to every form on the world,
+    /// $B_{i*}$, by Monte Carlo ray tracing: ``n`` rays are emitted
+    /// diffusely from random points of the form, and each one is
+    /// absorbed, reflected diffusely or reflected specularly at every
+    /// hit with probabilities $\varepsilon$, $\rho_d$ and $\rho_s$ of
+    /// the surface hit. The ray weight is the transmittance of the
+    /// participating media it crosses.
+    ///
+    /// # Parameters:
+    ///
+    /// * `self`: world of objects.
+    /// * `n`: number of rays.
+    /// * `form_idx`: World index of the emitting form.
+    /// * `optics`: Radiative properties of each form.
+    ///
+    /// # Returns:
+    ///
+    /// * `Vec<f32>`: Gebhart factors $B_{ij}$, indexed by $j$
+    ///   (including $B_{ii}$).
+    fn gebhart_row(&self, n: usize, form_idx: usize, optics: &[SurfaceOptics]) -> Vec<f32>;
+
+    /// Gebhart factors between every pair of forms, ``n`` rays per form.
+    /// With black surfaces they are the view factors.
+    fn gebhart_factors(&self, n: usize, optics: &[SurfaceOptics]) -> Gebhart;
+}
+
+impl ExchangeFactors for HittableList {
+    fn gebhart_row(&self, n: usize, form_idx: usize, optics: &[SurfaceOptics]) -> Vec<f32> {
+        let mut sampler: Sampler = Sampler::new(SamplerKind::Sobol, n);
+        let mut row: Vec<f32> = vec![0e0; self.forms.len()];
+        for k in 0..n {
+            sampler.start_sample(k);
+            if let Some((j, weight)) = self.gebhart_sample(form_idx, optics, &mut sampler) {
+                row[j] += weight;
+            }
+        }
+        row.iter().map(|b| b / n as f32).collect()
+    }
+
+    fn gebhart_factors(&self, n: usize, optics: &[SurfaceOptics]) -> Gebhart {
+        Gebhart { factors: (0..self.forms.len()).map(|i| self.gebhart_row(n, i, optics)).collect() }
+    }
+}
+
+/// Radiative properties of the world forms.
+impl HittableList {
+    /// Radiative properties of every form in one band, from the
+    /// thermo-optical properties of its material (at its center, see
+    /// `MaterialThermoOptics`).
+    pub fn surface_optics(&self, band: Band) -> Vec<SurfaceOptics> {
+        self.forms.iter()
+            .map(|form| SurfaceOptics::from_thermo(form.material().thermo_optics(form.get_center()), band))
+            .collect()
+    }
+}
+
+/// Single ray Gebhart factor estimator.
+impl HittableList {
+    /// Traces one ray emitted by the form, drawing the point, the
+    /// directions and the absorption events from the current sample of
+    /// ``sampler``. Returns the form absorbing it, if any, and its weight.
+    fn gebhart_sample(&self, form_idx: usize, optics: &[SurfaceOptics], sampler: &mut Sampler) -> Option<(usize, f32)> {
+        let form: &Form = self.forms.get(form_idx).unwrap();
+        let (s, t) = sampler.next_2d();
+        // Points are uniform on (s, t), weighted by their area.
+        let mut weight: f32 = form.diff_a(s, t) / form.area();
+        let mut origin: Vec3 = form.point(s, t);
+        let mut hit: Option<(HitRecord, f32)> = self.shoot_cosine(origin, form.normal(s, t), sampler.next_2d());
+
+        for _ in 0..MAX_REFLECTIONS {
+            let (rec, tau) = hit?;
+            weight *= tau;
+            let surface: SurfaceOptics = optics[rec.hit_elem];
+            let event: f32 = sampler.next_1d();
+            if event < surface.emissivity {
+                return Some((rec.hit_elem, weight));
+            }
+            let normal: Vec3 = self.forms[rec.hit_elem].outward_normal(rec.p);
+            hit = match event < surface.emissivity + surface.specular {
+                true => self.shoot(&Ray::new(rec.p, MetalKind::reflect(rec.p - origin, normal))),
+                false => self.shoot_cosine(rec.p, normal, sampler.next_2d()),
+            };
+            origin = rec.p;
+        }
+        None
+    }
+}
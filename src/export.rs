@@ -0,0 +1,223 @@
+//! Export module. Writes the view factor and radiative conductor
+//! matrices of the world forms to files for other tools: CSV, JSON,
+//! NumPy ``.npy`` arrays and the radiative conductor cards of
+//! SINDA/FLUINT and ESATAN thermal network input decks.
+
+use std::fs::File;
+use std::io::{Write, Result};
+
+use crate::objects::HittableList;
+use crate::radiation::Vfs;
+
+/// Export file formats.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum ExportFormat {
+    /// Comma separated values, with the node names heading the rows and columns.
+    Csv,
+    /// JSON object with the ``names`` and the ``values`` matrix.
+    Json,
+    /// NumPy array (``<f4``, row major), without the names.
+    Npy,
+    /// SINDA/FLUINT conductor data block of the given submodel.
+    Sinda(&'static str),
+    /// ESATAN ``$CONDUCTORS`` block.
+    Esatan,
+}
+
+impl ExportFormat {
+    /// Usual file extension of the format.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Json => "json",
+            ExportFormat::Npy => "npy",
+            ExportFormat::Sinda(_) => "inp",
+            ExportFormat::Esatan => "d",
+        }
+    }
+}
+
+/// Square matrix of a quantity between named nodes, ``values[i][j]``
+/// from node $i$ to node $j$.
+pub struct NodeTable {
+    /// Node names.
+    pub names: Vec<String>,
+    /// Node to node values.
+    pub values: Vec<Vec<f32>>,
+}
+
+impl NodeTable {
+    /// NodeTable constructor.
+    ///
+    /// # Parameters:
+    /// * `names` - Node names.
+    /// * `values` - Square matrix, as many rows as names.
+    pub fn new(names: Vec<String>, values: Vec<Vec<f32>>) -> NodeTable {
+        NodeTable { names, values }
+    }
+
+    /// View factors $F_{ij}$ between the named world forms, completed
+    /// by reciprocity (see `Vfs::matrix`).
+    pub fn view_factors(world: &HittableList, vfs: &Vfs) -> NodeTable {
+        NodeTable::new(world.names(), vfs.matrix(world))
+    }
+
+    /// Radiative conductors $GR_{ij}$ (m²) between the named world
+    /// forms, e.g. from `Gebhart::radiative_conductors`.
+    pub fn conductors(world: &HittableList, conductors: &[Vec<f32>]) -> NodeTable {
+        NodeTable::new(world.names(), conductors.to_vec())
+    }
+
+    /// Comma separated values: a header line with the node names, then
+    /// a line per node, its name followed by its row.
+    pub fn csv(&self) -> String {
+        let mut out: String = String::from("node");
+        for name in self.names.iter() {
+            out.push(',');
+            out.push_str(&csv_field(name));
+        }
+        out.push('\n');
+        for (name, row) in self.names.iter().zip(self.values.iter()) {
+            out.push_str(&csv_field(name));
+            for value in row.iter() {
+                out.push_str(&format!(",{}", value));
+            }
+            out.push('\n');
+        }
+        out
+    }
+
+    /// JSON object, ``{"names": [...], "values": [[...], ...]}``.
+    /// Non finite values are written as ``null``.
+    pub fn json(&self) -> String {
+        let names: Vec<String> = self.names.iter().map(|name| json_string(name)).collect();
+        let rows: Vec<String> = self.values.iter()
+            .map(|row| {
+                let values: Vec<String> = row.iter()
+                    .map(|value| match value.is_finite() {
+                        true => format!("{}", value),
+                        false => String::from("null"),
+                    })
+                    .collect();
+                format!("[{}]", values.join(", "))
+            })
+            .collect();
+        format!("{{\n  \"names\": [{}],\n  \"values\": [\n    {}\n  ]\n}}\n", names.join(", "), rows.join(",\n    "))
+    }
+
+    /// NumPy ``.npy`` (version 1.0) array of little-endian f32, of
+    /// shape (rows, columns).
+    pub fn npy(&self) -> Vec<u8> {
+        let rows: usize = self.values.len();
+        let cols: usize = self.values.first().map_or(0, |row| row.len());
+        let mut header: String = format!("{{'descr': '<f4', 'fortran_order': False, 'shape': ({}, {}), }}", rows, cols);
+        // Magic, version and header length take 10 bytes, the data
+        // starts aligned to 64 bytes after the newline ending the header.
+        while (10 + header.len() + 1) % 64 != 0 {
+            header.push(' ');
+        }
+        header.push('\n');
+        let mut data: Vec<u8> = Vec::with_capacity(10 + header.len() + 4 * rows * cols);
+        data.extend_from_slice(b"\x93NUMPY\x01\x00");
+        data.extend_from_slice(&(header.len() as u16).to_le_bytes());
+        data.extend_from_slice(header.as_bytes());
+        for row in self.values.iter() {
+            for value in row.iter() {
+                data.extend_from_slice(&value.to_le_bytes());
+            }
+        }
+        data
+    }
+
+    /// SINDA/FLUINT conductor data block: a radiation conductor (negative
+    /// id) per linked pair of nodes, the nodes numbered from 1 in the
+    /// table order. The values are taken as radiative conductors, the
+    /// solver multiplies them by its ``SIGMA``.
+    ///
+    /// # Parameters:
+    /// * `submodel` - Thermal submodel name.
+    pub fn sinda(&self, submodel: &str) -> String {
+        let mut out: String = String::from("C Radiative conductors GR (m2)\n");
+        for (i, name) in self.names.iter().enumerate() {
+            out.push_str(&format!("C Node {} = {}\n", i + 1, name));
+        }
+        out.push_str(&format!("HEADER CONDUCTOR DATA, {}\n", submodel));
+        for (id, (i, j, gr)) in self.links().into_iter().enumerate() {
+            out.push_str(&format!("-{}, {}, {}, {:.6E}\n", id + 1, i + 1, j + 1, gr));
+        }
+        out
+    }
+
+    /// ESATAN ``$CONDUCTORS`` block: a ``GR`` conductor per linked pair
+    /// of nodes, the nodes numbered from 1 in the table order.
+    pub fn esatan(&self) -> String {
+        let mut out: String = String::from("# Radiative conductors GR (m2)\n");
+        for (i, name) in self.names.iter().enumerate() {
+            out.push_str(&format!("# Node {} = {}\n", i + 1, name));
+        }
+        out.push_str("$CONDUCTORS\n");
+        for (i, j, gr) in self.links() {
+            out.push_str(&format!("GR({}, {}) = {:.6E};\n", i + 1, j + 1, gr));
+        }
+        out
+    }
+
+    /// Writes the table to a file.
+    ///
+    /// # Parameters:
+    /// * `path` - Output file.
+    /// * `format` - File format.
+    pub fn write(&self, path: &str, format: ExportFormat) -> Result<()> {
+        let data: Vec<u8> = match format {
+            ExportFormat::Csv => self.csv().into_bytes(),
+            ExportFormat::Json => self.json().into_bytes(),
+            ExportFormat::Npy => self.npy(),
+            ExportFormat::Sinda(submodel) => self.sinda(submodel).into_bytes(),
+            ExportFormat::Esatan => self.esatan().into_bytes(),
+        };
+        let mut buffer = File::create(path)?;
+        buffer.write_all(&data)?;
+        drop(buffer);
+        Ok(())
+    }
+
+    /// Linked pairs of nodes, $i<j$, with the mean of both directions,
+    /// which reciprocity makes equal up to the Monte Carlo noise.
+    fn links(&self) -> Vec<(usize, usize, f32)> {
+        let n: usize = self.values.len();
+        let mut links: Vec<(usize, usize, f32)> = Vec::new();
+        for i in 0..n {
+            for j in (i + 1)..n {
+                let gr: f32 = 5e-1 * (self.values[i][j] + self.values[j][i]);
+                if gr > 0e0 {
+                    links.push((i, j, gr));
+                }
+            }
+        }
+        links
+    }
+}
+
+/// CSV field, quoted if needed.
+fn csv_field(field: &str) -> String {
+    match field.contains(|c: char| c == ',' || c == '"' || c == '\n') {
+        true => format!("\"{}\"", field.replace('"', "\"\"")),
+        false => field.to_string(),
+    }
+}
+
+/// JSON string literal.
+fn json_string(string: &str) -> String {
+    let mut out: String = String::from("\"");
+    for c in string.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
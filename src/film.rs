@@ -0,0 +1,254 @@
+//! Film module. Linear (high dynamic range) framebuffer, written
+//! to image files through a post-processing pipeline, and saved to
+//! checkpoint files so that renders can be resumed.
+
+use std::fs::File;
+use std::io::{Read, Write};
+use std::io::{Error, ErrorKind, Result};
+
+use crate::vectors::Vec3;
+use crate::postprocess::PostProcess;
+use crate::adaptive::heatmap_color;
+use crate::integrator::PathStats;
+
+/// Linear radiance framebuffer.
+/// Pixel ``(i, j)`` is the ``i``-th column from the left and the
+/// ``j``-th row from the bottom, as in the camera's ``(u, v)`` coordinates.
+pub struct Framebuffer {
+    /// Width in pixels.
+    pub width_px: u16,
+    /// Height in pixels.
+    pub height_px: u16,
+    /// Pixel values, stored from the top row to the bottom one.
+    pub pixels: Vec<Vec3>,
+    /// Samples taken on each pixel, stored as ``pixels``.
+    pub samples: Vec<u32>,
+    /// Length statistics of the traced paths.
+    pub paths: PathStats,
+}
+
+impl Framebuffer {
+    /// Black framebuffer constructor.
+    ///
+    /// # Parameters:
+    /// * `width_px` - Width in pixels.
+    /// * `height_px` - Height in pixels.
+    pub fn new(width_px: u16, height_px: u16) -> Framebuffer {
+        Framebuffer {
+            width_px,
+            height_px,
+            pixels: vec![Vec3::zeros(); width_px as usize * height_px as usize],
+            samples: vec![0; width_px as usize * height_px as usize],
+            paths: PathStats::new(),
+        }
+    }
+
+    /// Storage index of pixel ``(i, j)``.
+    fn index(&self, i: u16, j: u16) -> usize {
+        (self.height_px - 1 - j) as usize * self.width_px as usize + i as usize
+    }
+
+    /// Value of pixel ``(i, j)``.
+    pub fn get(&self, i: u16, j: u16) -> Vec3 {
+        self.pixels[self.index(i, j)]
+    }
+
+    /// Sets the value of pixel ``(i, j)``.
+    pub fn set(&mut self, i: u16, j: u16, col: Vec3) {
+        let index: usize = self.index(i, j);
+        self.pixels[index] = col;
+    }
+
+    /// Samples taken on pixel ``(i, j)``.
+    pub fn samples(&self, i: u16, j: u16) -> u32 {
+        self.samples[self.index(i, j)]
+    }
+
+    /// Sets the number of samples taken on pixel ``(i, j)``.
+    pub fn set_samples(&mut self, i: u16, j: u16, n: u32) {
+        let index: usize = self.index(i, j);
+        self.samples[index] = n;
+    }
+
+    /// Adds ``n`` samples of mean ``col`` to pixel ``(i, j)``,
+    /// updating its mean with the samples already taken.
+    pub fn add_samples(&mut self, i: u16, j: u16, col: Vec3, n: u32) {
+        let index: usize = self.index(i, j);
+        let total: u32 = self.samples[index] + n;
+        if total > 0 {
+            self.pixels[index] = (self.pixels[index] * self.samples[index] as f32 + col * n as f32) / total as f32;
+        }
+        self.samples[index] = total;
+    }
+
+    /// False color image of the samples taken on each pixel,
+    /// blue for the fewest and red for the most.
+    pub fn heatmap(&self) -> Framebuffer {
+        let min: u32 = self.samples.iter().cloned().min().unwrap_or(0);
+        let max: u32 = self.samples.iter().cloned().max().unwrap_or(0);
+        Framebuffer {
+            width_px: self.width_px,
+            height_px: self.height_px,
+            pixels: self.samples.iter().map(|n| heatmap_color(*n, min, max)).collect(),
+            samples: self.samples.clone(),
+            paths: self.paths.clone(),
+        }
+    }
+
+    /// Writes the framebuffer as a plain (P3) PPM image.
+    ///
+    /// # Parameters:
+    /// * `path` - Output file.
+    /// * `post` - Post-processing pipeline converting radiance to 8-bit colors.
+    pub fn write_ppm(&self, path: &str, post: &PostProcess) -> Result<()> {
+        let mut buffer = File::create(path)?;
+        buffer.write_all(b"P3\n")?;
+        buffer.write_fmt(format_args!("{} {}\n", self.width_px, self.height_px))?;
+        buffer.write_all(b"255\n")?;
+        for col in self.pixels.iter() {
+            let rgb: [u8; 3] = post.apply(*col);
+            buffer.write_fmt(format_args!("{} {} {}\n", rgb[0], rgb[1], rgb[2]))?;
+        }
+        drop(buffer);
+        Ok(())
+    }
+
+    /// Writes the unprocessed framebuffer as a color PFM (portable
+    /// float map) image: little-endian RGB floats, from the bottom row.
+    ///
+    /// # Parameters:
+    /// * `path` - Output file.
+    pub fn write_pfm(&self, path: &str) -> Result<()> {
+        let mut buffer = File::create(path)?;
+        buffer.write_fmt(format_args!("PF\n{} {}\n-1.0\n", self.width_px, self.height_px))?;
+        let mut data: Vec<u8> = Vec::with_capacity(12 * self.pixels.len());
+        for j in 0..self.height_px {
+            for i in 0..self.width_px {
+                for c in self.get(i, j).e.iter() {
+                    data.extend_from_slice(&c.to_le_bytes());
+                }
+            }
+        }
+        buffer.write_all(&data)?;
+        drop(buffer);
+        Ok(())
+    }
+
+    /// Writes the framebuffer to a binary checkpoint file: pixel means,
+    /// sample counts and path statistics, as little-endian numbers.
+    /// The file is written next to ``path`` and then renamed, so that
+    /// an interrupted write does not spoil the previous checkpoint.
+    ///
+    /// # Parameters:
+    /// * `path` - Checkpoint file.
+    pub fn write_checkpoint(&self, path: &str) -> Result<()> {
+        let mut data: Vec<u8> = Vec::with_capacity(CHECKPOINT_MAGIC.len() + 16 * self.pixels.len());
+        data.extend_from_slice(CHECKPOINT_MAGIC);
+        data.extend_from_slice(&self.width_px.to_le_bytes());
+        data.extend_from_slice(&self.height_px.to_le_bytes());
+        for (col, n) in self.pixels.iter().zip(self.samples.iter()) {
+            for c in col.e.iter() {
+                data.extend_from_slice(&c.to_le_bytes());
+            }
+            data.extend_from_slice(&n.to_le_bytes());
+        }
+        let counts: [u64; 5] = [
+            self.paths.escaped,
+            self.paths.absorbed,
+            self.paths.roulette,
+            self.paths.max_depth,
+            self.paths.histogram.len() as u64,
+        ];
+        for count in counts.iter().chain(self.paths.histogram.iter()) {
+            data.extend_from_slice(&count.to_le_bytes());
+        }
+
+        let tmp: String = format!("{}.tmp", path);
+        let mut buffer = File::create(&tmp)?;
+        buffer.write_all(&data)?;
+        buffer.sync_all()?;
+        drop(buffer);
+        std::fs::rename(&tmp, path)
+    }
+
+    /// Reads a framebuffer written by `write_checkpoint`.
+    ///
+    /// # Parameters:
+    /// * `path` - Checkpoint file.
+    ///
+    /// # Returns:
+    /// * `Result<Framebuffer>` - Framebuffer, or an `InvalidData`
+    ///   error if the file is not a complete checkpoint.
+    pub fn read_checkpoint(path: &str) -> Result<Framebuffer> {
+        let mut data: Vec<u8> = Vec::new();
+        File::open(path)?.read_to_end(&mut data)?;
+        let mut reader: CheckpointReader = CheckpointReader { data: &data, pos: 0 };
+        if reader.take(CHECKPOINT_MAGIC.len())? != CHECKPOINT_MAGIC {
+            return Err(Error::new(ErrorKind::InvalidData, "not a framebuffer checkpoint"));
+        }
+        let width_px: u16 = u16::from_le_bytes([reader.byte()?, reader.byte()?]);
+        let height_px: u16 = u16::from_le_bytes([reader.byte()?, reader.byte()?]);
+        // Pixels (three means and a count) and the path counts must be
+        // there before allocating a framebuffer of the header's size.
+        if reader.left() < 16 * width_px as usize * height_px as usize + 40 {
+            return Err(Error::new(ErrorKind::InvalidData, "truncated framebuffer checkpoint"));
+        }
+        let mut film: Framebuffer = Framebuffer::new(width_px, height_px);
+        for k in 0..film.pixels.len() {
+            for c in 0..3 {
+                film.pixels[k].e[c] = f32::from_bits(reader.u32()?);
+            }
+            film.samples[k] = reader.u32()?;
+        }
+        film.paths.escaped = reader.u64()?;
+        film.paths.absorbed = reader.u64()?;
+        film.paths.roulette = reader.u64()?;
+        film.paths.max_depth = reader.u64()?;
+        let n_lengths: u64 = reader.u64()?;
+        for _ in 0..n_lengths {
+            film.paths.histogram.push(reader.u64()?);
+        }
+        Ok(film)
+    }
+}
+
+/// Checkpoint file signature (and format version).
+const CHECKPOINT_MAGIC: &[u8] = b"RTCKPT01";
+
+/// Little-endian reader of checkpoint data.
+struct CheckpointReader<'a> {
+    /// File contents.
+    data: &'a [u8],
+    /// Read position.
+    pos: usize,
+}
+
+impl CheckpointReader<'_> {
+    /// Next ``n`` bytes.
+    fn take(&mut self, n: usize) -> Result<&[u8]> {
+        match self.data.len() >= self.pos + n {
+            true => {
+                self.pos += n;
+                Ok(&self.data[self.pos - n..self.pos])
+            }
+            false => Err(Error::new(ErrorKind::InvalidData, "truncated framebuffer checkpoint")),
+        }
+    }
+
+    /// Number of bytes not read yet.
+    fn left(&self) -> usize { self.data.len() - self.pos }
+
+    fn byte(&mut self) -> Result<u8> { Ok(self.take(1)?[0]) }
+
+    fn u32(&mut self) -> Result<u32> {
+        let mut bytes: [u8; 4] = [0; 4];
+        bytes.copy_from_slice(self.take(4)?);
+        Ok(u32::from_le_bytes(bytes))
+    }
+
+    fn u64(&mut self) -> Result<u64> {
+        let mut bytes: [u8; 8] = [0; 8];
+        bytes.copy_from_slice(self.take(8)?);
+        Ok(u64::from_le_bytes(bytes))
+    }
+}
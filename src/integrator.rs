@@ -0,0 +1,149 @@
+//! Path tracing settings and path length statistics.
+
+use std::fmt::{Display, Formatter, Error};
+
+/// Bounce from which Russian roulette is applied by default.
+pub const RR_DEPTH: usize = 3;
+/// Default maximum survival probability of Russian roulette.
+pub const RR_MAX_SURVIVAL: f32 = 0.95;
+
+/// Path tracing settings.
+#[derive(Copy, Clone)]
+pub struct PathSettings {
+    /// Maximum number of scattering events (hard limit, the path
+    /// radiance is biased if it is reached).
+    pub max_depth: usize,
+    /// Number of scattering events after which paths are randomly
+    /// terminated (Russian roulette), ``None`` to disable it.
+    pub rr_depth: Option<usize>,
+    /// Upper bound of the survival probability, so that paths through
+    /// lossless materials (glass, white diffusers) also end.
+    pub rr_max_survival: f32,
+}
+
+/// Reason why a path ended.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum PathEnd {
+    /// Left the world (background reached).
+    Escaped,
+    /// Absorbed by a material (no scattered ray).
+    Absorbed,
+    /// Terminated by Russian roulette.
+    Roulette,
+    /// Maximum depth reached.
+    MaxDepth,
+}
+
+/// Path length statistics.
+#[derive(Clone)]
+pub struct PathStats {
+    /// Number of paths with ``k`` scattering events, at index ``k``.
+    pub histogram: Vec<u64>,
+    /// Paths that escaped to the background.
+    pub escaped: u64,
+    /// Paths absorbed by a material.
+    pub absorbed: u64,
+    /// Paths terminated by Russian roulette.
+    pub roulette: u64,
+    /// Paths truncated at the maximum depth.
+    pub max_depth: u64,
+}
+
+impl PathSettings {
+    /// Settings with Russian roulette from bounce `RR_DEPTH` on.
+    ///
+    /// # Parameters:
+    /// * `max_depth` - Maximum number of scattering events.
+    pub fn new(max_depth: usize) -> PathSettings {
+        PathSettings { max_depth, rr_depth: Some(RR_DEPTH), rr_max_survival: RR_MAX_SURVIVAL }
+    }
+
+    /// Settings without Russian roulette, paths only end at ``max_depth``.
+    pub fn fixed(max_depth: usize) -> PathSettings {
+        PathSettings { max_depth, rr_depth: None, rr_max_survival: 1e0 }
+    }
+
+    /// Survival probability of a path with the given ``throughput``
+    /// (largest channel) after ``depth`` scattering events.
+    pub fn survival(&self, depth: usize, throughput: f32) -> f32 {
+        match self.rr_depth {
+            Some(rr_depth) if depth >= rr_depth => throughput.min(self.rr_max_survival).max(0e0),
+            _ => 1e0,
+        }
+    }
+}
+
+impl Default for PathStats {
+    fn default() -> PathStats {
+        PathStats::new()
+    }
+}
+
+impl PathStats {
+    /// Empty statistics.
+    pub fn new() -> PathStats {
+        PathStats { histogram: Vec::new(), escaped: 0, absorbed: 0, roulette: 0, max_depth: 0 }
+    }
+
+    /// Records a path of ``length`` scattering events.
+    pub fn record(&mut self, length: usize, end: PathEnd) {
+        if self.histogram.len() <= length {
+            self.histogram.resize(length + 1, 0);
+        }
+        self.histogram[length] += 1;
+        match end {
+            PathEnd::Escaped => self.escaped += 1,
+            PathEnd::Absorbed => self.absorbed += 1,
+            PathEnd::Roulette => self.roulette += 1,
+            PathEnd::MaxDepth => self.max_depth += 1,
+        }
+    }
+
+    /// Adds the statistics of ``other``.
+    pub fn merge(&mut self, other: &PathStats) {
+        if self.histogram.len() < other.histogram.len() {
+            self.histogram.resize(other.histogram.len(), 0);
+        }
+        for (count, other_count) in self.histogram.iter_mut().zip(other.histogram.iter()) {
+            *count += other_count;
+        }
+        self.escaped += other.escaped;
+        self.absorbed += other.absorbed;
+        self.roulette += other.roulette;
+        self.max_depth += other.max_depth;
+    }
+
+    /// Number of recorded paths.
+    pub fn paths(&self) -> u64 {
+        self.histogram.iter().sum()
+    }
+
+    /// Mean number of scattering events per path.
+    pub fn mean_length(&self) -> f32 {
+        let total: u64 = self.histogram.iter().enumerate().map(|(k, n)| k as u64 * n).sum();
+        match self.paths() {
+            0 => 0e0,
+            n => total as f32 / n as f32,
+        }
+    }
+
+    /// Longest recorded path.
+    pub fn max_length(&self) -> usize {
+        self.histogram.iter().rposition(|n| *n > 0).unwrap_or(0)
+    }
+}
+
+impl Display for PathStats {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        writeln!(f, "Paths: {}, mean length: {:.3}, max length: {}", self.paths(), self.mean_length(), self.max_length())?;
+        writeln!(
+            f,
+            "Escaped: {}, absorbed: {}, roulette: {}, max depth: {}",
+            self.escaped, self.absorbed, self.roulette, self.max_depth
+        )?;
+        for (k, n) in self.histogram.iter().enumerate() {
+            writeln!(f, "{:>4}: {}", k, n)?;
+        }
+        Ok(())
+    }
+}
@@ -2,10 +2,8 @@
 //! * Generating the view of a world by means of raytracing.
 //! * Calculating the view factors of all objects on the world.
 
-use std::fs::File;
-use std::io::Write;
 use std::vec::Vec;
-use std::io::Result;
+use std::io::{Error, ErrorKind, Result};
 
 pub mod vectors;
 pub mod rays;
@@ -14,14 +12,39 @@ pub mod objects;
 pub mod cameras;
 pub mod materials;
 pub mod radiation;
+pub mod spectrum;
+pub mod postprocess;
+pub mod film;
+pub mod animation;
+pub mod adaptive;
+pub mod samplers;
+pub mod integrator;
+pub mod aov;
+pub mod denoise;
+pub mod progressive;
+pub mod patches;
+pub mod exchange;
+pub mod orbital;
+pub mod network;
+pub mod export;
+pub mod analytic;
 #[cfg(test)]
 pub mod tests;
 
-use vectors::{Vec3,Vec3Methods};
+use vectors::Vec3;
 use rays::{Ray};
 use objects::{Sphere, Cube, Square, Form, HittableList};
 use materials::{Material, LambertianKind, MetalKind, DielectricKind};
 use cameras::{Camera, CameraRay};
+use postprocess::PostProcess;
+use film::Framebuffer;
+use animation::{CameraAnimation, Sequence};
+use adaptive::{AdaptiveSampling, PixelEstimate};
+use samplers::{Sampler, SamplerKind};
+use integrator::{PathSettings, PathStats};
+use aov::{AovBuffers, AovEstimate};
+use progressive::Progressive;
+use hittable::{HitRecord, Hittable};
 
 extern crate rand;
 use rand::random;
@@ -36,6 +59,7 @@ struct FormChoice {
     kind: u8,
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn random_world(
     n: usize,
     x_lim: [f32; 2],
@@ -95,13 +119,13 @@ pub fn random_world(
             };
         }
 
-        let mut form: Form = Form::Square(Square::horizontal_surface(center, length, mat));
+        let mut form: Form = Form::Square(Square::horizontal_surface(center, length, mat.clone()));
         for form_type in &forms {
             if choose_form < form_type.prob {
                 form = match form_type.kind {
-                    0 => Form::Sphere(Sphere::new(center, length / 2e0, mat)),
-                    1 => Form::Cube(Cube::new(center, length, mat)),
-                    _ => Form::Square(Square::horizontal_surface(center, length, mat)),
+                    0 => Form::Sphere(Sphere::new(center, length / 2e0, mat.clone())),
+                    1 => Form::Cube(Cube::new(center, length, mat.clone())),
+                    _ => Form::Square(Square::horizontal_surface(center, length, mat.clone())),
                 }
             };
         }
@@ -113,6 +137,50 @@ pub fn random_world(
     world
 }
 
+/// Image and path tracing settings shared by the render functions.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct RenderSettings {
+    /// Image width in pixels.
+    pub width_px: u16,
+    /// Image height in pixels.
+    pub height_px: u16,
+    /// Pixel jitter, in pixels.
+    pub dev: f32,
+    /// Samples per pixel.
+    pub n_smooth: u16,
+    /// Maximum number of bounces (paths usually end earlier by
+    /// Russian roulette).
+    pub max_depth: usize,
+    /// Whether to use spectral path tracing.
+    pub spectral: bool,
+    /// Generator of the pixel, lens and wavelength samples.
+    pub sampler: SamplerKind,
+}
+
+impl RenderSettings {
+    /// RenderSettings constructor.
+    ///
+    /// # Parameters:
+    /// * `width_px` - Image width in pixels.
+    /// * `height_px` - Image height in pixels.
+    /// * `dev` - Pixel jitter, in pixels.
+    /// * `n_smooth` - Samples per pixel.
+    /// * `max_depth` - Maximum number of bounces.
+    /// * `spectral` - Whether to use spectral path tracing.
+    /// * `sampler` - Generator of the pixel, lens and wavelength samples.
+    pub fn new(
+        width_px: u16,
+        height_px: u16,
+        dev: f32,
+        n_smooth: u16,
+        max_depth: usize,
+        spectral: bool,
+        sampler: SamplerKind) -> RenderSettings
+    {
+        RenderSettings { width_px, height_px, dev, n_smooth, max_depth, spectral, sampler }
+    }
+}
+
 pub fn print_world(
     world: &HittableList,
     cam: &Camera,
@@ -122,32 +190,266 @@ pub fn print_world(
     n_smooth: u16,
     max_depth: usize) -> Result<()>
 {
-    let mut buffer = File::create("ray_tracing.ppm")?;
-    buffer.write(b"P3\n")?;
-    buffer.write_fmt(format_args!("{} {}\n", width_px, height_px))?;
-    buffer.write(b"255\n")?;
-    for j in (0..height_px).rev() {
-        for i in 0..width_px {
-            let mut col: Vec3 = Vec3::zeros();
-            for _s in 0..n_smooth {
-                let u: f32 = (i as f32 + dev * random::<f32>()) / (width_px as f32);
-                let v: f32 = (j as f32 + dev * random::<f32>()) / (height_px as f32);
-                let ray: Ray = cam.get_ray(u, v);
-                col += ray.color(&world, 0, max_depth);
+    write_world(world, cam, &RenderSettings::new(width_px, height_px, dev, n_smooth, max_depth, false, SamplerKind::Sobol))
+}
+
+/// Same as `print_world`, but using spectral path tracing: every
+/// camera ray carries a sampled wavelength, materials are evaluated
+/// at that wavelength (RGB data is upsampled) and the monochromatic
+/// radiance is converted to sRGB through the CIE color matching functions.
+pub fn print_world_spectral(
+    world: &HittableList,
+    cam: &Camera,
+    width_px: u16,
+    height_px: u16,
+    dev: f32,
+    n_smooth: u16,
+    max_depth: usize) -> Result<()>
+{
+    write_world(world, cam, &RenderSettings::new(width_px, height_px, dev, n_smooth, max_depth, true, SamplerKind::Sobol))
+}
+
+/// Color of a single ray through a point of the pixel ``(i, j)``.
+/// The pixel jitter, lens point and wavelength are the first five
+/// dimensions of the current sample of ``sampler``. Paths are ended
+/// by Russian roulette (see `PathSettings::new`), their lengths are
+/// recorded on ``stats``.
+fn pixel_sample(
+    world: &HittableList,
+    cam: &Camera,
+    i: u16,
+    j: u16,
+    settings: &RenderSettings,
+    sampler: &mut Sampler,
+    stats: &mut PathStats) -> Vec3
+{
+    let (du, dv) = sampler.next_2d();
+    let lens: (f32, f32) = sampler.next_2d();
+    let u: f32 = (i as f32 + settings.dev * du) / (settings.width_px as f32);
+    let v: f32 = (j as f32 + settings.dev * dv) / (settings.height_px as f32);
+    // Out of the camera's projection, black.
+    if !cam.covers(u, v) {
+        return Vec3::zeros();
+    }
+    let mut ray: Ray = cam.get_ray_lens(u, v, lens);
+    let weight: Vec3 = match settings.spectral {
+        true => {
+            let (lambda, _pdf) = spectrum::sample_wavelength(sampler.next_1d());
+            ray.wavelength = Some(lambda);
+            spectrum::wavelength_to_rgb(lambda)
+        }
+        false => Vec3::ones(),
+    };
+    let (radiance, length, end) = ray.trace(world, &PathSettings::new(settings.max_depth));
+    stats.record(length, end);
+    radiance * weight
+}
+
+/// Averaged color of the pixel ``(i, j)`` over ``n_smooth`` rays.
+fn pixel_color(
+    world: &HittableList,
+    cam: &Camera,
+    i: u16,
+    j: u16,
+    settings: &RenderSettings,
+    n_smooth: u16,
+    stats: &mut PathStats) -> Vec3
+{
+    let mut sampler: Sampler = Sampler::new(settings.sampler, n_smooth as usize);
+    let mut col: Vec3 = Vec3::zeros();
+    for s in 0..n_smooth {
+        sampler.start_sample(s as usize);
+        col += pixel_sample(world, cam, i, j, settings, &mut sampler, stats);
+    }
+    col / n_smooth as f32
+}
+
+/// Renders the linear radiance of the world seen by the camera.
+///
+/// # Parameters:
+/// * `world` - Objects to render.
+/// * `cam` - Camera.
+/// * `settings` - Image size, samples per pixel and path tracing settings.
+///
+/// # Returns:
+/// * `Framebuffer` - Unprocessed (linear) image, with the path length
+///   statistics of the render.
+pub fn render_world(world: &HittableList, cam: &Camera, settings: &RenderSettings) -> Framebuffer {
+    let mut film: Framebuffer = Framebuffer::new(settings.width_px, settings.height_px);
+    for j in (0..settings.height_px).rev() {
+        for i in 0..settings.width_px {
+            let col: Vec3 = pixel_color(world, cam, i, j, settings, settings.n_smooth, &mut film.paths);
+            film.set(i, j, col);
+            film.set_samples(i, j, settings.n_smooth as u32);
+        }
+    }
+    film
+}
+
+/// Same as `render_world`, but the number of samples of each pixel
+/// adapts to its estimated error: every pixel gets at least
+/// ``adaptive.min_samples`` rays, and batches of as many rays are
+/// added until the relative error of its mean luminance falls below
+/// ``adaptive.threshold`` or ``adaptive.max_samples`` are reached.
+/// The samples taken on each pixel are stored on the framebuffer
+/// (see `Framebuffer::heatmap`). ``settings.n_smooth`` is not used.
+pub fn render_world_adaptive(
+    world: &HittableList,
+    cam: &Camera,
+    settings: &RenderSettings,
+    adaptive: &AdaptiveSampling) -> Framebuffer
+{
+    let mut film: Framebuffer = Framebuffer::new(settings.width_px, settings.height_px);
+    for j in (0..settings.height_px).rev() {
+        for i in 0..settings.width_px {
+            let mut estimate: PixelEstimate = PixelEstimate::new();
+            let mut pixel_sampler: Sampler = Sampler::new(settings.sampler, adaptive.max_samples as usize);
+            while adaptive.needs_samples(&estimate) {
+                for _s in 0..adaptive.batch(&estimate) {
+                    pixel_sampler.start_sample(estimate.samples() as usize);
+                    estimate.add(pixel_sample(world, cam, i, j, settings, &mut pixel_sampler, &mut film.paths));
+                }
             }
-            col /= n_smooth as f32;
-            // Correction 'Gamma 2': (0 <= r|g|b < 1)^(1/(gamma == 2))
-            col.gamma2();
+            film.set(i, j, estimate.mean());
+            film.set_samples(i, j, estimate.samples());
+        }
+    }
+    film
+}
 
-            let ir: u8 = (255.99 * col.r()) as u8;
-            let ig: u8 = (255.99 * col.g()) as u8;
-            let ib: u8 = (255.99 * col.b()) as u8;
+/// Same as `render_world`, but in passes: each pass adds
+/// ``progressive.pass_samples`` samples to every pixel of ``film``
+/// until all of them have ``progressive.target_samples``. The
+/// accumulated framebuffer is written to the checkpoint file, if
+/// any, every ``progressive.checkpoint_every`` passes and at the end.
+///
+/// # Parameters:
+/// * `world` - Objects to render.
+/// * `cam` - Camera.
+/// * `settings` - Path tracing settings. The image size is the one of
+///   ``film`` and the samples per pixel are set by ``progressive``.
+/// * `progressive` - Pass and checkpoint settings.
+/// * `film` - Framebuffer to accumulate on: a new one, or one read
+///   with `Framebuffer::read_checkpoint` to resume a render.
+///
+/// # Returns:
+/// * `Result<Framebuffer>` - Accumulated image, or the error of a
+///   checkpoint write.
+pub fn render_progressive(
+    world: &HittableList,
+    cam: &Camera,
+    settings: &RenderSettings,
+    progressive: &Progressive,
+    mut film: Framebuffer) -> Result<Framebuffer>
+{
+    let settings: RenderSettings = RenderSettings { width_px: film.width_px, height_px: film.height_px, ..*settings };
+    let mut pass: usize = 0;
+    while film.samples.iter().any(|n| *n < progressive.target_samples) {
+        for j in (0..settings.height_px).rev() {
+            for i in 0..settings.width_px {
+                let n: u16 = progressive.pass_samples(film.samples(i, j));
+                if n > 0 {
+                    let col: Vec3 = pixel_color(world, cam, i, j, &settings, n, &mut film.paths);
+                    film.add_samples(i, j, col, n as u32);
+                }
+            }
+        }
+        pass += 1;
+        let done: bool = film.samples.iter().all(|n| *n >= progressive.target_samples);
+        if let Some(path) = &progressive.checkpoint {
+            if done || pass % progressive.checkpoint_every.max(1) == 0 {
+                film.write_checkpoint(path)?;
+            }
+        }
+    }
+    Ok(film)
+}
 
-            buffer.write_fmt(format_args!("{} {} {}\n", ir, ig, ib))?;
+/// Renders the auxiliary buffers (depth, normal, albedo, position and
+/// object index) of the primary hits of the camera rays.
+///
+/// # Parameters:
+/// * `world` - Objects to render.
+/// * `cam` - Camera.
+/// * `settings` - Image size, pixel jitter, sampler and rays per pixel
+///   averaged on the buffers (the object index is the one seen through
+///   the pixel's center).
+///
+/// # Returns:
+/// * `AovBuffers` - One framebuffer per auxiliary variable.
+pub fn render_aovs(world: &HittableList, cam: &Camera, settings: &RenderSettings) -> AovBuffers {
+    let (width_px, height_px, dev): (u16, u16, f32) = (settings.width_px, settings.height_px, settings.dev);
+    let primary_hit = |u: f32, v: f32, lens: (f32, f32)| -> (Ray, Option<HitRecord>) {
+        let ray: Ray = cam.get_ray_lens(u, v, lens);
+        let mut rec: Option<HitRecord> = None;
+        match cam.covers(u, v) && world.hit(&ray, 1e-3, f32::MAX, &mut rec) {
+            true => (ray, rec),
+            false => (ray, None),
+        }
+    };
+    let mut aovs: AovBuffers = AovBuffers::new(width_px, height_px);
+    for j in (0..height_px).rev() {
+        for i in 0..width_px {
+            let mut estimate: AovEstimate = AovEstimate::new();
+            let mut pixel_sampler: Sampler = Sampler::new(settings.sampler, settings.n_smooth as usize);
+            for s in 0..settings.n_smooth {
+                pixel_sampler.start_sample(s as usize);
+                let (du, dv) = pixel_sampler.next_2d();
+                let lens: (f32, f32) = pixel_sampler.next_2d();
+                let u: f32 = (i as f32 + dev * du) / (width_px as f32);
+                let v: f32 = (j as f32 + dev * dv) / (height_px as f32);
+                let (ray, rec) = primary_hit(u, v, lens);
+                estimate.add(&ray, rec.as_ref());
+            }
+            let u: f32 = (i as f32 + 5e-1 * dev) / (width_px as f32);
+            let v: f32 = (j as f32 + 5e-1 * dev) / (height_px as f32);
+            estimate.set_object(primary_hit(u, v, (5e-1, 5e-1)).1.as_ref());
+            aovs.set(i, j, &estimate);
         }
     }
-    drop(buffer);
-    Ok(())
+    aovs
+}
+
+/// Renders an animated sequence, writing one numbered PPM image
+/// per frame (``<prefix>_0000.ppm``, ``<prefix>_0001.ppm``...).
+///
+/// The world is shared by every frame: moving forms are intersected
+/// at each ray's time, so nothing is rebuilt between frames.
+///
+/// # Parameters:
+/// * `world` - Objects to render.
+/// * `animation` - Keyframed camera.
+/// * `sequence` - Frame timing.
+/// * `settings` - Image size, samples per pixel and path tracing settings.
+/// * `post` - Post-processing pipeline.
+/// * `prefix` - Path prefix of the frame images.
+///
+/// # Returns:
+/// * `Result<Vec<String>>` - Paths of the written frames.
+pub fn render_sequence(
+    world: &HittableList,
+    animation: &CameraAnimation,
+    sequence: &Sequence,
+    settings: &RenderSettings,
+    post: &PostProcess,
+    prefix: &str) -> Result<Vec<String>>
+{
+    let mut paths: Vec<String> = Vec::new();
+    for k in 0..sequence.n_frames {
+        let (open, close) = sequence.frame_shutter(k);
+        let mut cam: Camera = animation.camera_at(open).ok_or_else(
+            || Error::new(ErrorKind::InvalidInput, "camera animation without keyframes")
+        )?;
+        cam.set_shutter(open, close);
+        let path: String = format!("{}_{:04}.ppm", prefix, k);
+        render_world(world, &cam, settings).write_ppm(&path, post)?;
+        paths.push(path);
+    }
+    Ok(paths)
+}
+
+fn write_world(world: &HittableList, cam: &Camera, settings: &RenderSettings) -> Result<()> {
+    render_world(world, cam, settings).write_ppm("ray_tracing.ppm", &PostProcess::new())
 }
 
 /*
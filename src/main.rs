@@ -6,25 +6,95 @@ Program that generates a random image of basic shapes and materials using ray-tr
 * `NY`: u16 = 800; Number of pixels on Y-Axis.
 * `NS`: Number of slightly deviated rays per pixel for color smoothing.
 * `N_OBJ`: usize = 500; Number of objects to be created.
-* `MAX_DEPTH`: Maximum number of new ray spawns.
+* `MAX_DEPTH`: Maximum number of new ray spawns (paths usually end earlier by Russian roulette).
 * `LOOK_FROM`: Vec3 = Vec3::new_const(13e0, 2e0, 3e0); Camera position.
 * `LOOK_AT`: Vec3 = Vec3::new_const(0e0, 0e0, 0e0); Camera focus point.
 * `VUP`: Vec3 = Vec3::new_const(0e0, 1e0, 0e0); World's upward direction.
 * `VFOV`: f32 = 20e0; Camera's field of view (degrees).
 * `ASPECT`: f32 = (NX as f32) / (NY as f32); Camera's aspect.
 * `FOCUS`: bool = false; If false, a simple camera is used, else, a focus-featured one.
+* `VF_METHOD`: VfMethod = VfMethod::AreaIntegration; Estimator of the view factors printed when `TEST_VF` is set.
+* `VF_RELATIVE_ERROR`: f32 = 1e-2; Relative standard error at which the view factor sampling stops.
+* `VF_MAX_SAMPLES`: usize = 1 << 20; Maximum number of samples per view factor.
+* `VF_EXACT`: bool = false; If true, the view factors printed when `TEST_VF` is set are exact on the
+  unobstructed pairs of forms matching a known configuration (parallel or perpendicular rectangles,
+  spheres in a hollow sphere), and sampled on the others.
+* `EXPORT`: &[ExportFormat] = &[]; Formats the view factors printed when `TEST_VF` is set are also
+  written in, to ``view_factors.<extension>`` (and the infrared radiative conductors, if `GEBHART`
+  is set, to ``radiative_conductors.<extension>``).
+* `PATCH_GRID`: (usize, usize) = (0, 0); Patches per form along $s$ and $t$. If not zero and `TEST_VF`
+  is set, the view factors are also computed between patches, aggregated back to the forms, and
+  the share of each patch's view taken by the other forms is written to ``patch_view_factors.ppm``.
+* `PATCH_NS`: usize = 4096; Rays per patch.
+* `GEBHART`: bool = false; If true and `TEST_VF` is set, the infrared Gebhart factors are printed
+  too, from the thermo-optical properties of the form materials.
+* `GEBHART_NS`: usize = 1 << 16; Rays per form of the Gebhart factors.
+* `HEAT_LOADS`: bool = false; If true and `TEST_VF` is set, the orbital heat loads of the forms
+  (in meters, nadir along -Y and flying along +X) are printed too.
+* `ALTITUDE`: f32 = 400e3; Orbit altitude (m).
+* `BETA_ANGLE`: f32 = 0e0; Orbit beta angle (degrees).
+* `ORBIT_ANOMALY`: f32 = 0e0; Position along the orbit from the noon point (degrees).
+* `THERMAL`: bool = false; If true and `TEST_VF` is set, the steady state and transient temperatures
+  of the forms, radiating between them (infrared Gebhart factors) and to space under the orbital
+  heat loads, are printed too.
+* `THERMAL_CAPACITANCE`: f32 = 1e3; Thermal capacitance of each form (J/K).
+* `THERMAL_STEP`: (f32, usize) = (60e0, 100); Time step (s) and number of steps of the transient.
+* `SPECTRAL`: bool = false; If true, the image is rendered with spectral path tracing.
+* `EXPOSURE_EV`: f32 = 0e0; Exposure compensation (stops).
+* `TONE_MAPPING`: ToneMapping = ToneMapping::Clamp; Tone mapping operator.
+* `DITHER`: bool = false; If true, 8-bit quantization is dithered.
+* `SHUTTER`: (f32, f32) = (0e0, 0e0); Shutter opening and closing times (motion blur).
+* `SAMPLER`: SamplerKind = SamplerKind::Sobol; Generator of the pixel and lens samples.
+* `PATH_STATS`: bool = true; If true, path length statistics are printed after rendering.
+* `AOVS`: bool = false; If true, the auxiliary buffers (depth, normal, albedo, position and
+  object index) are written to ``aov_<name>.pfm`` (float) and ``aov_<name>.ppm`` (preview).
+* `DENOISE`: bool = false; If true, a denoised image, guided by the albedo and normal buffers,
+  is written to ``ray_tracing_denoised.ppm``.
+* `DENOISE_STRENGTH`: f32 = 1e0; Denoising strength, from 0 (none) to 1 (full).
+* `ADAPTIVE`: bool = false; If true, pixels get from `NS` to `MAX_NS` samples depending on
+  their noise, and a sample count heatmap is written to ``samples_heatmap.ppm``.
+* `MAX_NS`: u16 = 256; Maximum number of rays per pixel with adaptive sampling.
+* `PROGRESSIVE`: bool = false; If true, the image is rendered in passes of `PASS_NS` rays per
+  pixel up to `NS`, checkpointing the accumulated image to `CHECKPOINT`. A run finding the
+  checkpoint resumes from it, so raising `NS` adds samples to a finished render. Checkpoints
+  only match the scene they were rendered from (the random worlds change on every run).
+* `PASS_NS`: u16 = 4; Rays per pixel of each progressive pass.
+* `CHECKPOINT`: &str = "ray_tracing.ckpt"; Progressive rendering checkpoint file.
+* `CHECKPOINT_EVERY`: usize = 1; Progressive passes between checkpoints.
+* `ADAPTIVE_THRESHOLD`: f32 = 2e-2; Relative error at which adaptive sampling stops.
+* `N_FRAMES`: usize = 36; Default number of frames of the `animate` command.
+* `FPS`: f32 = 24e0; Frames per time unit of the `animate` command.
+* `SHUTTER_FRACTION`: f32 = 5e-1; Fraction of the frame time with the shutter open.
+
+# Commands
+* `animate [n_frames] [prefix]`: renders an orbit around the world to the
+  numbered images ``<prefix>_0000.ppm``, ``<prefix>_0001.ppm``...
 */
 
 
-use raytracing::{random_world, print_world};
+use raytracing::{random_world, render_world, render_world_adaptive, render_progressive, render_sequence, render_aovs, RenderSettings};
+use raytracing::adaptive::AdaptiveSampling;
+use raytracing::progressive::Progressive;
+use raytracing::samplers::SamplerKind;
+use raytracing::film::Framebuffer;
+use raytracing::aov::AovBuffers;
+use raytracing::denoise::Denoiser;
+use raytracing::animation::{CameraAnimation, CameraKeyframe, Sequence};
+use raytracing::postprocess::{PostProcess, Exposure, ToneMapping, Transfer};
 use raytracing::objects::HittableList;
-use raytracing::vectors::Vec3;
+use raytracing::vectors::{Vec3, Vec3Methods};
 use raytracing::cameras::{Camera, CameraFocus, CameraSimple};
 use raytracing::objects::{Form, Sphere, Cube, Square};
 use raytracing::materials::{Material, LambertianKind, MetalKind, DielectricKind};
 
 use rand::Rng;
-use raytracing::radiation::ViewFactors;
+use raytracing::radiation::{ViewFactors, VfMethod, VfTarget, VfEstimates, Vfs};
+use raytracing::patches::Mesh;
+use raytracing::exchange::{ExchangeFactors, Gebhart, SurfaceOptics, Band};
+use raytracing::orbital::{Orbit, Attitude, Environment, OrbitalHeatLoads};
+use raytracing::network::{ThermalNetwork, TimeScheme};
+use raytracing::export::{ExportFormat, NodeTable};
+use raytracing::analytic::ExactViewFactors;
 
 extern crate rand;
 
@@ -41,7 +111,43 @@ const ASPECT: f32 = (NX as f32) / (NY as f32);
 const FOCUS: bool = false;
 const TEST_WORLD: bool = false;
 const TEST_VF: bool = false;
+const VF_METHOD: VfMethod = VfMethod::AreaIntegration;
+const VF_RELATIVE_ERROR: f32 = 1e-2;
+const VF_MAX_SAMPLES: usize = 1 << 20;
+const VF_EXACT: bool = false;
+const EXPORT: &[ExportFormat] = &[];
+const PATCH_GRID: (usize, usize) = (0, 0);
+const PATCH_NS: usize = 4096;
+const GEBHART: bool = false;
+const GEBHART_NS: usize = 1 << 16;
+const HEAT_LOADS: bool = false;
+const ALTITUDE: f32 = 400e3;
+const BETA_ANGLE: f32 = 0e0;
+const ORBIT_ANOMALY: f32 = 0e0;
+const THERMAL: bool = false;
+const THERMAL_CAPACITANCE: f32 = 1e3;
+const THERMAL_STEP: (f32, usize) = (60e0, 100);
 const DO_PRINT: bool = true;
+const SPECTRAL: bool = false;
+const EXPOSURE_EV: f32 = 0e0;
+const TONE_MAPPING: ToneMapping = ToneMapping::Clamp;
+const DITHER: bool = false;
+const SHUTTER: (f32, f32) = (0e0, 0e0);
+const SAMPLER: SamplerKind = SamplerKind::Sobol;
+const PATH_STATS: bool = true;
+const AOVS: bool = false;
+const DENOISE: bool = false;
+const DENOISE_STRENGTH: f32 = 1e0;
+const ADAPTIVE: bool = false;
+const MAX_NS: u16 = 256;
+const PROGRESSIVE: bool = false;
+const PASS_NS: u16 = 4;
+const CHECKPOINT: &str = "ray_tracing.ckpt";
+const CHECKPOINT_EVERY: usize = 1;
+const ADAPTIVE_THRESHOLD: f32 = 2e-2;
+const N_FRAMES: usize = 36;
+const FPS: f32 = 24e0;
+const SHUTTER_FRACTION: f32 = 5e-1;
 
 
 fn random_old_world(n: usize) -> HittableList{
@@ -240,7 +346,7 @@ fn main_OLD() -> std::io::Result<()> {
 */
 
 fn main() -> std::io::Result<()> {
-    let camera: Camera = match TEST_VF {
+    let mut camera: Camera = match TEST_VF {
         true => Camera::Simple(CameraSimple::new(
             Vec3::new(1.8, 1.1, 1.0),
             Vec3::new(0.4, 0.05, -0.2),
@@ -270,6 +376,8 @@ fn main() -> std::io::Result<()> {
         }
     };
 
+    camera.set_shutter(SHUTTER.0, SHUTTER.1);
+
     let world: HittableList = match TEST_WORLD {
         true => random_world(
             N_OBJ,
@@ -306,19 +414,164 @@ fn main() -> std::io::Result<()> {
     */
 
     if TEST_VF {
-        println!("{}", world.view_factors(10240));
+        let target: VfTarget = VfTarget::new(0e0, VF_RELATIVE_ERROR, VF_MAX_SAMPLES);
+        let vfs: Vfs = match VF_EXACT {
+            true => {
+                let vfs: Vfs = world.view_factors_hybrid(VF_METHOD, &target);
+                println!("{}", vfs);
+                vfs
+            }
+            false => {
+                let estimates: VfEstimates = world.view_factors_converged(VF_METHOD, &target);
+                println!("{}", estimates);
+                estimates.vfs()
+            }
+        };
+        for format in EXPORT.iter() {
+            NodeTable::view_factors(&world, &vfs).write(&format!("view_factors.{}", format.extension()), *format)?;
+        }
+        if PATCH_GRID.0 * PATCH_GRID.1 > 0 {
+            let mesh: Mesh = Mesh::new(&world, PATCH_GRID.0, PATCH_GRID.1);
+            let vfs: Vec<Vec<f32>> = mesh.view_factors(&world, PATCH_NS);
+            let forms: Vec<Vec<f32>> = mesh.aggregate(&vfs);
+            println!("{}", Vfs::with_self(
+                forms.iter().enumerate().map(|(i, row)| row[(i + 1)..].to_vec()).collect(),
+                forms.iter().enumerate().map(|(i, row)| row[i]).collect()
+            ));
+            let seen: Vec<f32> = vfs.iter().zip(mesh.patches.iter())
+                .map(|(row, patch)| row.iter().zip(mesh.patches.iter())
+                    .filter(|(_, q)| q.form != patch.form)
+                    .map(|(f, _)| f)
+                    .sum())
+                .collect();
+            let post: PostProcess = PostProcess { transfer: Transfer::Linear, ..PostProcess::new() };
+            mesh.false_color(&world, &camera, NX, NY, &seen).write_ppm("patch_view_factors.ppm", &post)?;
+        }
+        if GEBHART {
+            let optics: Vec<SurfaceOptics> = world.surface_optics(Band::Infrared);
+            let gebhart: Gebhart = world.gebhart_factors(GEBHART_NS, &optics);
+            println!("{}", gebhart);
+            let conductors: NodeTable = NodeTable::conductors(&world, &gebhart.radiative_conductors(&world, &optics));
+            for format in EXPORT.iter() {
+                conductors.write(&format!("radiative_conductors.{}", format.extension()), *format)?;
+            }
+        }
+        if HEAT_LOADS {
+            let orbit: Orbit = Orbit::new(ALTITUDE, BETA_ANGLE.to_radians(), ORBIT_ANOMALY.to_radians());
+            let attitude: Attitude = Attitude::new(Vec3::new(0e0, -1e0, 0e0), Vec3::new(1e0, 0e0, 0e0));
+            println!("{}", world.heat_loads(GEBHART_NS, &Environment::new(orbit, attitude)));
+        }
+        if THERMAL {
+            let optics: Vec<SurfaceOptics> = world.surface_optics(Band::Infrared);
+            let conductors: Vec<Vec<f32>> = world.gebhart_factors(GEBHART_NS, &optics).radiative_conductors(&world, &optics);
+            let capacitances: Vec<f32> = vec![THERMAL_CAPACITANCE; world.forms.len()];
+            let mut network: ThermalNetwork = ThermalNetwork::from_world(&world, &optics, &conductors, &capacitances, 293e0, 3e0);
+            let orbit: Orbit = Orbit::new(ALTITUDE, BETA_ANGLE.to_radians(), ORBIT_ANOMALY.to_radians());
+            let attitude: Attitude = Attitude::new(Vec3::new(0e0, -1e0, 0e0), Vec3::new(1e0, 0e0, 0e0));
+            let loads: Vec<f32> = world.heat_loads(GEBHART_NS, &Environment::new(orbit, attitude)).loads.iter()
+                .map(|load| load.total())
+                .collect();
+            network.set_loads(&loads);
+            match network.steady_state() {
+                Ok(temps) => println!("Steady state: {:?}", temps),
+                Err(error) => println!("Steady state: {}", error),
+            }
+            match network.transient(THERMAL_STEP.0, THERMAL_STEP.1, TimeScheme::CrankNicolson) {
+                Ok(transient) => print!("{}", transient),
+                Err(error) => println!("Transient: {}", error),
+            }
+        }
     }
 
-    return match DO_PRINT {
-        true => print_world(
-            &world,
-            &camera,
-            NX,
-            NY,
-            0e0,
-            NS,
-            MAX_DEPTH
-        ),
-        false => Ok(()),
+    let post: PostProcess = PostProcess {
+        exposure: Exposure::Ev(EXPOSURE_EV),
+        tone_mapping: TONE_MAPPING,
+        transfer: Transfer::Srgb,
+        dither: DITHER,
     };
+
+    let settings: RenderSettings = RenderSettings::new(NX, NY, 0e0, NS, MAX_DEPTH, SPECTRAL, SAMPLER);
+
+    // `animate [n_frames] [prefix]`: orbit around the world, one image per frame.
+    let args: Vec<String> = std::env::args().collect();
+    if args.len() > 1 && args[1] == "animate" {
+        let n_frames: usize = args.get(2).and_then(|n| n.parse().ok()).unwrap_or(N_FRAMES);
+        if n_frames == 0 {
+            return Err(std::io::Error::new(std::io::ErrorKind::InvalidInput, "animate needs at least one frame"));
+        }
+        let prefix: &str = args.get(3).map(|p| p.as_str()).unwrap_or("frame");
+        let sequence: Sequence = Sequence::new(0e0, FPS, n_frames, SHUTTER_FRACTION);
+        let paths: Vec<String> = render_sequence(
+            &world,
+            &orbit(&sequence),
+            &sequence,
+            &settings,
+            &post,
+            prefix
+        )?;
+        println!("Wrote {} frames ({} ... {}).", paths.len(), paths[0], paths[paths.len() - 1]);
+        return Ok(());
+    }
+
+    if DO_PRINT {
+        let film: Framebuffer = match (ADAPTIVE, PROGRESSIVE) {
+            (true, _) => {
+                let adaptive: AdaptiveSampling = AdaptiveSampling::new(NS, MAX_NS, ADAPTIVE_THRESHOLD);
+                let film: Framebuffer = render_world_adaptive(&world, &camera, &RenderSettings { dev: 1e0, ..settings }, &adaptive);
+                film.heatmap().write_ppm("samples_heatmap.ppm", &PostProcess::new())?;
+                film
+            }
+            (false, true) => {
+                let progressive: Progressive = Progressive::new(PASS_NS, NS as u32, Some(CHECKPOINT), CHECKPOINT_EVERY);
+                // Resume from the checkpoint, if any of the same size.
+                let film: Framebuffer = match Framebuffer::read_checkpoint(CHECKPOINT) {
+                    Ok(film) if film.width_px == NX && film.height_px == NY => film,
+                    _ => Framebuffer::new(NX, NY),
+                };
+                let taken: u32 = film.samples.iter().cloned().min().unwrap_or(0);
+                println!("{} samples per pixel taken, {} passes left.", taken, progressive.passes_left(taken));
+                render_progressive(&world, &camera, &settings, &progressive, film)?
+            }
+            (false, false) => render_world(&world, &camera, &settings),
+        };
+        film.write_ppm("ray_tracing.ppm", &post)?;
+        if PATH_STATS {
+            print!("{}", film.paths);
+        }
+        if AOVS || DENOISE {
+            let aovs: AovBuffers = render_aovs(&world, &camera, &RenderSettings { dev: 1e0, ..settings });
+            if AOVS {
+                aovs.write("aov")?;
+            }
+            if DENOISE {
+                Denoiser::new(DENOISE_STRENGTH).denoise(&film, &aovs)
+                    .write_ppm("ray_tracing_denoised.ppm", &post)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Camera making a full turn around ``LOOK_AT`` during the sequence,
+/// keyframed every 10 degrees.
+fn orbit(sequence: &Sequence) -> CameraAnimation {
+    let mut animation: CameraAnimation = CameraAnimation::new(VUP, ASPECT, 0e0);
+    let radius: Vec3 = LOOK_FROM - LOOK_AT;
+    let duration: f32 = sequence.n_frames as f32 / sequence.fps;
+    for k in 0..=36 {
+        let ang: f32 = (k as f32) * std::f32::consts::PI / 18e0;
+        let look_from: Vec3 = LOOK_AT + Vec3::new(
+            radius.x() * ang.cos() - radius.z() * ang.sin(),
+            radius.y(),
+            radius.x() * ang.sin() + radius.z() * ang.cos()
+        );
+        animation.push(CameraKeyframe::new(
+            sequence.start + duration * (k as f32) / 36e0,
+            look_from,
+            LOOK_AT,
+            VFOV,
+            radius.length()
+        ));
+    }
+    animation
 }
@@ -0,0 +1,189 @@
+//! Blended and layered materials.
+
+use std::f32::consts::PI;
+use std::sync::Arc;
+
+extern crate rand;
+use self::rand::random;
+
+use crate::rays::Ray;
+use crate::hittable::HitRecord;
+use crate::vectors::{Vec3, Vec3Methods};
+use crate::spectrum::Spectrum;
+use crate::materials::{Material, random_unit_vector};
+use crate::materials::texture::Texture;
+use crate::materials::microfacet::fresnel_dielectric;
+
+/// Blend of two materials.
+/// Each ray interacts with the ``first`` material with
+/// probability $w$, given by the ``weight`` texture at the
+/// hit point, or with the ``second`` one otherwise, so that
+/// $$f = w f_1 + (1 - w) f_2$$
+#[derive(Clone)]
+pub struct MixKind{
+    /// Material chosen with probability $w$.
+    first: Arc<Material>,
+    /// Material chosen with probability $1 - w$.
+    second: Arc<Material>,
+    /// Blend weight $w$ from 0 to 1.
+    weight: Texture
+}
+
+/// Coated diffuse material.
+/// A lambertian base under a smooth dielectric clear coat of
+/// refractive index ``n``. Light is specularly reflected on the coat
+/// following the Fresnel equations, while the transmitted light is
+/// diffusely reflected by the base, including the internal
+/// reflections between the base and the coat.
+#[derive(Copy, Clone)]
+pub struct CoatedDiffuseKind{
+    /// Base RGB color.
+    albedo: Vec3,
+    /// Base spectral reflectance.
+    spectrum: Spectrum,
+    /// Coat's refractive index.
+    n: f32
+}
+
+impl MixKind{
+    /// Constant weight blend constructor.
+    ///
+    /// # Parameters:
+    /// * `first` - Material chosen with probability ``weight``.
+    /// * `second` - Material chosen with probability ``1 - weight``.
+    /// * `weight` - Blend weight from 0 to 1.
+    pub fn new(first: Material, second: Material, weight: f32) -> MixKind {
+        MixKind::textured(first, second, Texture::Constant(weight))
+    }
+
+    /// Texture driven blend constructor.
+    pub fn textured(first: Material, second: Material, weight: Texture) -> MixKind {
+        MixKind {
+            first: Arc::new(first),
+            second: Arc::new(second),
+            weight
+        }
+    }
+
+    /// Blend weight at world point ``p``, clamped between 0 and 1.
+    pub fn weight(&self, p: Vec3) -> f32 {
+        self.weight.value(p).clamp(0e0, 1e0)
+    }
+
+    /// Component materials.
+    pub fn materials(&self) -> (&Material, &Material) {
+        (&self.first, &self.second)
+    }
+
+    /// Randomly chosen component material at world point ``p``.
+    pub fn choose(&self, p: Vec3) -> &Material {
+        match random::<f32>() < self.weight(p) {
+            true => &self.first,
+            false => &self.second,
+        }
+    }
+}
+
+impl CoatedDiffuseKind{
+    /// Coated diffuse constructor.
+    ///
+    /// # Parameters:
+    /// * `albedo` - Base RGB color.
+    /// * `n` - Coat's refractive index.
+    pub fn new(albedo: Vec3, n: f32) -> CoatedDiffuseKind {
+        CoatedDiffuseKind {albedo, spectrum: Spectrum::Rgb(albedo), n}
+    }
+
+    /// Coated diffuse with a spectral base reflectance.
+    pub fn spectral(spectrum: Spectrum, n: f32) -> CoatedDiffuseKind {
+        CoatedDiffuseKind {albedo: spectrum.to_rgb(), spectrum, n}
+    }
+
+    /// Base RGB color.
+    pub fn albedo(&self) -> Vec3 { self.albedo }
+
+    /// Hemispherical (diffuse) reflectance of the inner side of the coat,
+    /// (Egan and Hilgeman fit):
+    /// $$F_{dr}(\eta)=-\frac{1.440}{\eta^2}+\frac{0.710}{\eta}+0.668+0.0636\eta$$
+    fn internal_diffuse_reflectance(&self) -> f32 {
+        -1.440 / self.n.powi(2) + 0.710 / self.n + 0.668 + 0.0636 * self.n
+    }
+
+    /// Base reflectance seen by the ray.
+    fn base(&self, ray_in: &Ray) -> Vec3 {
+        self.spectrum.value(ray_in.wavelength)
+    }
+
+    /// Diffuse lobe weight for an outgoing direction of cosine ``cos_o``,
+    /// excluding the transmission into the coat:
+    /// $$w=\frac{\left(1-F(\cos\theta_o)\right)R}{\eta^2\left(1-R F_{dr}\right)}$$
+    fn diffuse_weight(&self, ray_in: &Ray, cos_o: f32) -> Vec3 {
+        let base: Vec3 = self.base(ray_in);
+        let fdr: f32 = self.internal_diffuse_reflectance();
+        let t_o: f32 = 1e0 - fresnel_dielectric(cos_o, 1e0 / self.n);
+        Vec3::new(
+            base.r() / (1e0 - base.r() * fdr),
+            base.g() / (1e0 - base.g() * fdr),
+            base.b() / (1e0 - base.b() * fdr)
+        ) * (t_o / self.n.powi(2))
+    }
+
+    /// Specularly reflects with probability $F(\cos\theta_i)$, else
+    /// samples the diffuse base with a cosine weighted direction.
+    pub fn scatter(&self, ray_in: &Ray, hit_rec: &HitRecord, attenuation: &mut Vec3, scattered: &mut Ray) -> bool {
+        let wi: Vec3 = -ray_in.direction().unit_vector();
+        let n: Vec3 = match wi.dot(&hit_rec.normal) < 0e0 {
+            true => -hit_rec.normal,
+            false => hit_rec.normal,
+        };
+        let cos_i: f32 = wi.dot(&n);
+        let f_i: f32 = fresnel_dielectric(cos_i, 1e0 / self.n);
+
+        scattered.a = hit_rec.p;
+        // Clear coat reflection.
+        if random::<f32>() < f_i {
+            scattered.b = 2e0 * cos_i * n - wi;
+            *attenuation = Vec3::ones();
+            return true;
+        }
+
+        // Diffuse base.
+        let wo: Vec3 = (n + random_unit_vector()).unit_vector();
+        let cos_o: f32 = wo.dot(&n);
+        if cos_o <= 0e0 {
+            return false;
+        }
+        scattered.b = wo;
+        *attenuation = self.diffuse_weight(ray_in, cos_o);
+        true
+    }
+
+    /// Diffuse lobe times cosine, $f(\vec i,\vec o)\left|\vec o\cdot\vec n\right|$
+    /// (the clear coat reflection is a delta lobe).
+    pub fn eval(&self, ray_in: &Ray, hit_rec: &HitRecord, direction: Vec3) -> Vec3 {
+        let wi: Vec3 = -ray_in.direction().unit_vector();
+        let wo: Vec3 = direction.unit_vector();
+        let n: Vec3 = match wi.dot(&hit_rec.normal) < 0e0 {
+            true => -hit_rec.normal,
+            false => hit_rec.normal,
+        };
+        let cos_o: f32 = wo.dot(&n);
+        if cos_o <= 0e0 {
+            return Vec3::zeros();
+        }
+        let t_i: f32 = 1e0 - fresnel_dielectric(wi.dot(&n), 1e0 / self.n);
+        self.diffuse_weight(ray_in, cos_o) * (t_i * cos_o / PI)
+    }
+
+    /// Solid angle probability density of the diffuse lobe.
+    pub fn pdf(&self, ray_in: &Ray, hit_rec: &HitRecord, direction: Vec3) -> f32 {
+        let wi: Vec3 = -ray_in.direction().unit_vector();
+        let wo: Vec3 = direction.unit_vector();
+        let n: Vec3 = match wi.dot(&hit_rec.normal) < 0e0 {
+            true => -hit_rec.normal,
+            false => hit_rec.normal,
+        };
+        let t_i: f32 = 1e0 - fresnel_dielectric(wi.dot(&n), 1e0 / self.n);
+        t_i * wo.dot(&n).max(0e0) / PI
+    }
+}
@@ -0,0 +1,381 @@
+//! Microfacet materials based on the GGX (Trowbridge-Reitz) normal
+//! distribution and the separable Smith shadowing-masking function
+//! (Walter et al., 2007).
+//!
+//! Notation: $\vec i$ points from the hit point towards the incoming ray
+//! origin, $\vec o$ is the scattered direction, $\vec n$ the surface normal
+//! and $\vec m$ the microfacet normal (half vector).
+
+use std::f32::consts::PI;
+
+extern crate rand;
+use self::rand::random;
+
+use crate::rays::Ray;
+use crate::hittable::HitRecord;
+use crate::vectors::{Vec3, Vec3Methods};
+use crate::spectrum::Spectrum;
+
+/// Minimum GGX $\alpha$, avoids singular distributions on mirror-like surfaces.
+const ALPHA_MIN: f32 = 1e-3;
+
+/// Rough conductor (metal) material.
+/// Reflection only, with a complex refractive index $\eta + ik$
+/// per color channel, or at the ray's wavelength on spectral rays.
+#[derive(Copy, Clone)]
+pub struct MicrofacetConductorKind{
+    /// Real part of the refractive index.
+    eta: Spectrum,
+    /// Extinction coefficient, imaginary part of the refractive index.
+    k: Spectrum,
+    /// GGX roughness $\alpha$.
+    alpha: f32
+}
+
+/// Rough dielectric material.
+/// Reflects or refracts through a random microfacet
+/// depending on the Fresnel equations.
+#[derive(Copy, Clone)]
+pub struct RoughDielectricKind{
+    /// Material's refractive index.
+    n: f32,
+    /// GGX roughness $\alpha$.
+    alpha: f32
+}
+
+impl MicrofacetConductorKind{
+    /// Rough conductor constructor. Spectral rays see the RGB
+    /// indices upsampled (see `Spectrum::Rgb`).
+    ///
+    /// # Parameters:
+    /// * `eta` - Real part of the refractive index (RGB).
+    /// * `k` - Extinction coefficient (RGB).
+    /// * `roughness` - Perceptual roughness from 0 (mirror) to 1, $\alpha=\text{roughness}^2$.
+    pub fn new(eta: Vec3, k: Vec3, roughness: f32) -> MicrofacetConductorKind {
+        MicrofacetConductorKind::spectral(Spectrum::Rgb(eta), Spectrum::Rgb(k), roughness)
+    }
+
+    /// Rough conductor with a spectral refractive index, e.g. measured
+    /// $\eta(\lambda)$ and $k(\lambda)$ tables (see `Spectrum::from_samples`).
+    /// White rays see the color of both spectra.
+    ///
+    /// # Parameters:
+    /// * `eta` - Real part of the refractive index.
+    /// * `k` - Extinction coefficient.
+    /// * `roughness` - Perceptual roughness from 0 (mirror) to 1, $\alpha=\text{roughness}^2$.
+    pub fn spectral(eta: Spectrum, k: Spectrum, roughness: f32) -> MicrofacetConductorKind {
+        MicrofacetConductorKind {eta, k, alpha: ggx_alpha(roughness)}
+    }
+
+    /// Gold.
+    pub fn gold(roughness: f32) -> MicrofacetConductorKind {
+        MicrofacetConductorKind::new(Vec3::new(0.143, 0.374, 1.442), Vec3::new(3.983, 2.385, 1.603), roughness)
+    }
+
+    /// Copper.
+    pub fn copper(roughness: f32) -> MicrofacetConductorKind {
+        MicrofacetConductorKind::new(Vec3::new(0.200, 0.924, 1.102), Vec3::new(3.912, 2.452, 2.142), roughness)
+    }
+
+    /// Aluminium.
+    pub fn aluminium(roughness: f32) -> MicrofacetConductorKind {
+        MicrofacetConductorKind::new(Vec3::new(1.657, 0.880, 0.521), Vec3::new(9.224, 6.270, 4.837), roughness)
+    }
+
+    /// Silver.
+    pub fn silver(roughness: f32) -> MicrofacetConductorKind {
+        MicrofacetConductorKind::new(Vec3::new(0.155, 0.117, 0.138), Vec3::new(4.828, 3.122, 2.147), roughness)
+    }
+
+    /// Conductor preset by name (`gold`, `copper`, `aluminium`/`aluminum` or `silver`).
+    pub fn preset(name: &str, roughness: f32) -> Option<MicrofacetConductorKind> {
+        match name.to_lowercase().as_str() {
+            "gold" | "au" => Some(MicrofacetConductorKind::gold(roughness)),
+            "copper" | "cu" => Some(MicrofacetConductorKind::copper(roughness)),
+            "aluminium" | "aluminum" | "al" => Some(MicrofacetConductorKind::aluminium(roughness)),
+            "silver" | "ag" => Some(MicrofacetConductorKind::silver(roughness)),
+            _ => None,
+        }
+    }
+
+    /// Fresnel reflectance at an incidence of cosine ``cosine``: per
+    /// color channel, or at the ray's wavelength (nm), if any,
+    /// repeated on the three channels.
+    pub fn fresnel(&self, cosine: f32, wavelength: Option<f32>) -> Vec3 {
+        match wavelength {
+            Some(lambda) => Vec3::ones() * fresnel_conductor(cosine, self.eta.at(lambda), self.k.at(lambda)),
+            None => {
+                let (eta, k): (Vec3, Vec3) = (self.eta.to_rgb(), self.k.to_rgb());
+                Vec3::new(
+                    fresnel_conductor(cosine, eta.x(), k.x()),
+                    fresnel_conductor(cosine, eta.y(), k.y()),
+                    fresnel_conductor(cosine, eta.z(), k.z())
+                )
+            }
+        }
+    }
+
+    /// Samples a reflected direction about a microfacet normal drawn
+    /// proportionally to $D(\vec m)\left(\vec m\cdot\vec n\right)$
+    /// (see `ggx_sample`). The attenuation is the sample weight:
+    /// $$w=\frac{F(\vec i\cdot\vec m)G(\vec i,\vec o)\left|\vec i\cdot\vec m\right|}{\left|\vec i\cdot\vec n\right|\left|\vec m\cdot\vec n\right|}$$
+    pub fn scatter(&self, ray_in: &Ray, hit_rec: &HitRecord, attenuation: &mut Vec3, scattered: &mut Ray) -> bool {
+        let wi: Vec3 = -ray_in.direction().unit_vector();
+        let n: Vec3 = facing_normal(wi, hit_rec.normal);
+        let m: Vec3 = ggx_sample(n, self.alpha);
+        let wo: Vec3 = reflect(wi, m);
+
+        let cos_i: f32 = wi.dot(&n);
+        let cos_o: f32 = wo.dot(&n);
+        if cos_i <= 0e0 || cos_o <= 0e0 {
+            return false;
+        }
+        let im: f32 = wi.dot(&m);
+        let g: f32 = smith_g1(wi, m, n, self.alpha) * smith_g1(wo, m, n, self.alpha);
+
+        scattered.a = hit_rec.p;
+        scattered.b = wo;
+        *attenuation = self.fresnel(im, ray_in.wavelength) * (g * im.abs() / (cos_i * m.dot(&n)));
+        true
+    }
+
+    /// BSDF times cosine, $f(\vec i,\vec o)\left|\vec o\cdot\vec n\right|$.
+    pub fn eval(&self, ray_in: &Ray, hit_rec: &HitRecord, direction: Vec3) -> Vec3 {
+        let wi: Vec3 = -ray_in.direction().unit_vector();
+        let wo: Vec3 = direction.unit_vector();
+        let n: Vec3 = facing_normal(wi, hit_rec.normal);
+        let cos_i: f32 = wi.dot(&n);
+        let cos_o: f32 = wo.dot(&n);
+        if cos_i <= 0e0 || cos_o <= 0e0 {
+            return Vec3::zeros();
+        }
+        let m: Vec3 = (wi + wo).unit_vector();
+        let g: f32 = smith_g1(wi, m, n, self.alpha) * smith_g1(wo, m, n, self.alpha);
+        self.fresnel(wi.dot(&m), ray_in.wavelength) * (ggx_d(m, n, self.alpha) * g / (4e0 * cos_i))
+    }
+
+    /// Solid angle probability density of sampling ``direction``.
+    pub fn pdf(&self, ray_in: &Ray, hit_rec: &HitRecord, direction: Vec3) -> f32 {
+        let wi: Vec3 = -ray_in.direction().unit_vector();
+        let wo: Vec3 = direction.unit_vector();
+        let n: Vec3 = facing_normal(wi, hit_rec.normal);
+        if wi.dot(&n) <= 0e0 || wo.dot(&n) <= 0e0 {
+            return 0e0;
+        }
+        let m: Vec3 = (wi + wo).unit_vector();
+        ggx_pdf(m, n, self.alpha) / (4e0 * wo.dot(&m).abs())
+    }
+}
+
+impl RoughDielectricKind{
+    /// Rough dielectric constructor.
+    ///
+    /// # Parameters:
+    /// * `n` - Refractive index.
+    /// * `roughness` - Perceptual roughness from 0 (smooth) to 1, $\alpha=\text{roughness}^2$.
+    pub fn new(n: f32, roughness: f32) -> RoughDielectricKind {
+        RoughDielectricKind {n, alpha: ggx_alpha(roughness)}
+    }
+
+    /// Incident and transmitted side refractive indices plus the
+    /// normal on the incident side.
+    fn sides(&self, wi: Vec3, normal: Vec3) -> (f32, f32, Vec3) {
+        match wi.dot(&normal) > 0e0 {
+            // Air to dielectric.
+            true => (1e0, self.n, normal),
+            // Dielectric to air, normal is inwards.
+            false => (self.n, 1e0, -normal),
+        }
+    }
+
+    /// Samples a microfacet, then reflects with probability $F$ or
+    /// refracts otherwise. The attenuation is the sample weight:
+    /// $$w=\frac{G(\vec i,\vec o)\left|\vec i\cdot\vec m\right|}{\left|\vec i\cdot\vec n\right|\left|\vec m\cdot\vec n\right|}$$
+    pub fn scatter(&self, ray_in: &Ray, hit_rec: &HitRecord, attenuation: &mut Vec3, scattered: &mut Ray) -> bool {
+        let wi: Vec3 = -ray_in.direction().unit_vector();
+        let (eta_i, eta_o, n) = self.sides(wi, hit_rec.normal);
+        let m: Vec3 = ggx_sample(n, self.alpha);
+        let im: f32 = wi.dot(&m);
+        if im <= 0e0 {
+            return false;
+        }
+
+        let f: f32 = fresnel_dielectric(im, eta_i / eta_o);
+        let wo: Vec3 = match random::<f32>() < f {
+            true => reflect(wi, m),
+            false => refract(wi, m, eta_i / eta_o),
+        };
+
+        // Reflected below or refracted above the macro surface.
+        if (wo.dot(&n) > 0e0) != (wo.dot(&m) > 0e0) {
+            return false;
+        }
+
+        let g: f32 = smith_g1(wi, m, n, self.alpha) * smith_g1(wo, m, n, self.alpha);
+        scattered.a = hit_rec.p;
+        scattered.b = wo;
+        *attenuation = Vec3::ones() * (g * im / (wi.dot(&n).abs() * m.dot(&n)));
+        true
+    }
+
+    /// Microfacet normal and Jacobian $\left|\partial\omega_m/\partial\omega_o\right|$ linking
+    /// $\vec i$ and $\vec o$, plus whether $\vec o$ is a reflection.
+    fn half_vector(&self, wi: Vec3, wo: Vec3, n: Vec3, eta_i: f32, eta_o: f32) -> (Vec3, f32, bool) {
+        match wo.dot(&n) > 0e0 {
+            true => {
+                let m: Vec3 = (wi + wo).unit_vector();
+                (m, 1e0 / (4e0 * wo.dot(&m).abs()), true)
+            }
+            false => {
+                let mut m: Vec3 = -(wi * eta_i + wo * eta_o).unit_vector();
+                if m.dot(&n) < 0e0 {
+                    m = -m;
+                }
+                let denom: f32 = eta_i * wi.dot(&m) + eta_o * wo.dot(&m);
+                (m, eta_o.powi(2) * wo.dot(&m).abs() / denom.powi(2), false)
+            }
+        }
+    }
+
+    /// BSDF times cosine, $f(\vec i,\vec o)\left|\vec o\cdot\vec n\right|$.
+    pub fn eval(&self, ray_in: &Ray, hit_rec: &HitRecord, direction: Vec3) -> Vec3 {
+        let wi: Vec3 = -ray_in.direction().unit_vector();
+        let wo: Vec3 = direction.unit_vector();
+        let (eta_i, eta_o, n) = self.sides(wi, hit_rec.normal);
+        let (m, jacobian, is_reflection) = self.half_vector(wi, wo, n, eta_i, eta_o);
+        let im: f32 = wi.dot(&m);
+        if im <= 0e0 {
+            return Vec3::zeros();
+        }
+        let f: f32 = fresnel_dielectric(im, eta_i / eta_o);
+        let g: f32 = smith_g1(wi, m, n, self.alpha) * smith_g1(wo, m, n, self.alpha);
+        let lobe: f32 = match is_reflection {
+            true => f,
+            false => 1e0 - f,
+        };
+        // f |o.n| = |i.m| lobe D G |dw_m/dw_o| / |i.n|
+        Vec3::ones() * (im * lobe * ggx_d(m, n, self.alpha) * g * jacobian / wi.dot(&n).abs())
+    }
+
+    /// Solid angle probability density of sampling ``direction``.
+    pub fn pdf(&self, ray_in: &Ray, hit_rec: &HitRecord, direction: Vec3) -> f32 {
+        let wi: Vec3 = -ray_in.direction().unit_vector();
+        let wo: Vec3 = direction.unit_vector();
+        let (eta_i, eta_o, n) = self.sides(wi, hit_rec.normal);
+        let (m, jacobian, is_reflection) = self.half_vector(wi, wo, n, eta_i, eta_o);
+        let im: f32 = wi.dot(&m);
+        if im <= 0e0 {
+            return 0e0;
+        }
+        let f: f32 = fresnel_dielectric(im, eta_i / eta_o);
+        let lobe: f32 = match is_reflection {
+            true => f,
+            false => 1e0 - f,
+        };
+        lobe * ggx_pdf(m, n, self.alpha) * jacobian
+    }
+}
+
+/// GGX $\alpha$ from perceptual roughness.
+fn ggx_alpha(roughness: f32) -> f32 {
+    roughness.powi(2).max(ALPHA_MIN)
+}
+
+/// Normal flipped towards the ``wi`` side.
+fn facing_normal(wi: Vec3, normal: Vec3) -> Vec3 {
+    match wi.dot(&normal) < 0e0 {
+        true => -normal,
+        false => normal,
+    }
+}
+
+/// Mirror reflection of ``wi`` (pointing outwards) about ``m``.
+fn reflect(wi: Vec3, m: Vec3) -> Vec3 {
+    2e0 * wi.dot(&m) * m - wi
+}
+
+/// Refraction of ``wi`` (pointing outwards) through ``m``, falls back to
+/// reflection on total internal reflection.
+fn refract(wi: Vec3, m: Vec3, eta: f32) -> Vec3 {
+    let c: f32 = wi.dot(&m);
+    let k: f32 = 1e0 + eta.powi(2) * (c.powi(2) - 1e0);
+    match k < 0e0 {
+        true => reflect(wi, m),
+        false => (-wi * eta + m * (eta * c - k.sqrt())).unit_vector(),
+    }
+}
+
+/// GGX normal distribution function:
+/// $$D(\vec m)=\frac{\alpha^2}{\pi\left[\left(\vec m\cdot\vec n\right)^2\left(\alpha^2-1\right)+1\right]^2}$$
+pub fn ggx_d(m: Vec3, n: Vec3, alpha: f32) -> f32 {
+    let cos_m: f32 = m.dot(&n);
+    if cos_m <= 0e0 {
+        return 0e0;
+    }
+    let a2: f32 = alpha.powi(2);
+    let denom: f32 = cos_m.powi(2) * (a2 - 1e0) + 1e0;
+    a2 / (PI * denom.powi(2))
+}
+
+/// Smith shadowing-masking term for a single direction:
+/// $$G_1(\vec v,\vec m)=\frac{2}{1+\sqrt{1+\alpha^2\tan^2\theta_v}}$$
+pub fn smith_g1(v: Vec3, m: Vec3, n: Vec3, alpha: f32) -> f32 {
+    let cos_v: f32 = v.dot(&n);
+    if v.dot(&m) * cos_v <= 0e0 {
+        return 0e0;
+    }
+    let tan2: f32 = (1e0 - cos_v.powi(2)).max(0e0) / cos_v.powi(2);
+    2e0 / (1e0 + (1e0 + alpha.powi(2) * tan2).sqrt())
+}
+
+/// Microfacet normal sampled proportionally to $D(\vec m)\left(\vec m\cdot\vec n\right)$:
+/// $$\theta_m=\arctan\frac{\alpha\sqrt{\xi_1}}{\sqrt{1-\xi_1}},\quad\phi_m=2\pi\xi_2$$
+fn ggx_sample(n: Vec3, alpha: f32) -> Vec3 {
+    let xi_1: f32 = random::<f32>();
+    let xi_2: f32 = random::<f32>();
+    let tan2: f32 = alpha.powi(2) * xi_1 / (1e0 - xi_1).max(1e-7);
+    let cos_theta: f32 = 1e0 / (1e0 + tan2).sqrt();
+    let sin_theta: f32 = (1e0 - cos_theta.powi(2)).max(0e0).sqrt();
+    let phi: f32 = 2e0 * PI * xi_2;
+    let (u, v) = n.orthonormal_basis();
+    u * (sin_theta * phi.cos()) + v * (sin_theta * phi.sin()) + n * cos_theta
+}
+
+/// Probability density of ``ggx_sample`` over microfacet normals.
+fn ggx_pdf(m: Vec3, n: Vec3, alpha: f32) -> f32 {
+    ggx_d(m, n, alpha) * m.dot(&n).abs()
+}
+
+/// Exact unpolarized Fresnel reflectance of a dielectric interface.
+///
+/// # Parameters:
+/// * `cosine` - Incidence angle's cosine.
+/// * `eta` - Incident over transmitted refractive index fraction.
+pub fn fresnel_dielectric(cosine: f32, eta: f32) -> f32 {
+    let sin2_t: f32 = eta.powi(2) * (1e0 - cosine.powi(2)).max(0e0);
+    // Total internal reflection.
+    if sin2_t >= 1e0 {
+        return 1e0;
+    }
+    let cos_t: f32 = (1e0 - sin2_t).sqrt();
+    let r_s: f32 = (eta * cosine - cos_t) / (eta * cosine + cos_t);
+    let r_p: f32 = (cosine - eta * cos_t) / (cosine + eta * cos_t);
+    (r_s.powi(2) + r_p.powi(2)) / 2e0
+}
+
+/// Exact unpolarized Fresnel reflectance of a conductor with
+/// complex refractive index $\eta + ik$.
+pub fn fresnel_conductor(cosine: f32, eta: f32, k: f32) -> f32 {
+    let cos2: f32 = cosine.powi(2).min(1e0);
+    let sin2: f32 = 1e0 - cos2;
+    let t0: f32 = eta.powi(2) - k.powi(2) - sin2;
+    let a2b2: f32 = (t0.powi(2) + 4e0 * eta.powi(2) * k.powi(2)).sqrt();
+    let t1: f32 = a2b2 + cos2;
+    let a: f32 = (5e-1 * (a2b2 + t0)).max(0e0).sqrt();
+    let t2: f32 = 2e0 * cosine * a;
+    let r_s: f32 = (t1 - t2) / (t1 + t2);
+    let t3: f32 = cos2 * a2b2 + sin2.powi(2);
+    let t4: f32 = t2 * sin2;
+    let r_p: f32 = r_s * (t3 - t4) / (t3 + t4);
+    (r_s + r_p) / 2e0
+}
@@ -0,0 +1,789 @@
+//! Materials module. Defines the object's material, which consists
+//! in a set of available material kinds and the required methods.
+
+extern crate rand;
+use self::rand::random;
+
+use crate::rays::Ray;
+use crate::hittable::HitRecord;
+use crate::vectors::{Vec3, Vec3Methods};
+use crate::spectrum;
+use crate::spectrum::Spectrum;
+
+mod microfacet;
+mod layered;
+mod texture;
+mod thermal;
+
+pub use microfacet::{MicrofacetConductorKind, RoughDielectricKind};
+pub use layered::{MixKind, CoatedDiffuseKind};
+pub use texture::Texture;
+pub use thermal::{ThermoOptics, ThermoOpticalKind, DEFAULT_EMISSIVITY};
+
+
+/// Lambertian materials.
+/// Lambertian (diffuse) materials reflect the
+/// light preferentially around the surface's
+/// normal vector at the hit-point with a dye
+/// of its own surface color, ``albedo``.
+#[derive(Copy, Clone)]
+pub struct LambertianKind{
+    /// Surface RGB color.
+    albedo: Vec3,
+    /// Surface spectral reflectance.
+    spectrum: Spectrum
+}
+
+/// Metallic material.
+/// Light is reflected on the plane formed by
+/// the light direction and the surface normal
+/// at the hit-point. A fuzzy
+/// effect may be added, so that the reflected
+/// ray is deviated on around an sphere of
+/// radius ``fuzz``.
+#[derive(Copy, Clone)]
+pub struct MetalKind{
+    /// Metal RGB color.
+    albedo: Vec3,
+    /// Metal spectral reflectance.
+    spectrum: Spectrum,
+    /// Deviation from perfect reflection.
+    fuzz: f32
+}
+
+/// Dielectric material.
+/// Dielectrics may reflect or refract depending
+/// on the hit angle and the material's refractive
+/// index ``n`` (see Snell's Law). Light travelling
+/// a length $L$ inside the material is attenuated
+/// following the Beer-Lambert law, $e^{-\sigma_a L}$.
+#[derive(Copy, Clone)]
+pub struct DielectricKind{
+    /// Material's refractive index.
+    n: f32,
+    /// RGB absorption coefficient $\sigma_a$ (1 / length).
+    absorption: Vec3,
+    /// Wavelength dependence of the refractive index.
+    dispersion: Dispersion
+}
+
+/// Refractive index dependence on the wavelength $\lambda$ (in $\mu m$).
+#[derive(Copy, Clone)]
+pub enum Dispersion{
+    /// Constant refractive index.
+    None,
+    /// Cauchy's equation, $n(\lambda)=A+\frac{B}{\lambda^2}$.
+    Cauchy { a: f32, b: f32 },
+    /// Sellmeier equation, $n^2(\lambda)=1+\sum_i\frac{B_i\lambda^2}{\lambda^2-C_i}$.
+    Sellmeier { b: [f32; 3], c: [f32; 3] },
+    /// Measured refractive index, tabulated as a spectrum.
+    Tabulated(Spectrum),
+}
+
+/// Emissive (diffuse light) material.
+/// Surfaces emit the same radiance in every
+/// direction and absorb every incoming ray.
+#[derive(Copy, Clone)]
+pub struct EmissiveKind{
+    /// Emitted RGB radiance.
+    radiance: Vec3,
+    /// Emitted spectral radiance.
+    spectrum: Spectrum
+}
+
+/// Isotropic phase function material.
+/// Used inside participating media: the scattered
+/// ray direction is uniformly distributed over the
+/// unit sphere, regardless of the incoming direction.
+#[derive(Copy, Clone)]
+pub struct IsotropicKind{
+    /// Single scattering RGB albedo.
+    albedo: Vec3
+}
+
+/// Henyey-Greenstein phase function material.
+/// Used inside participating media: the scattered
+/// ray direction is distributed around the incoming
+/// direction according to the asymmetry parameter ``g``:
+/// $$p(\cos\theta)=\frac{1}{4\pi}\frac{1-g^2}{\left(1+g^2-2g\cos\theta\right)^{3/2}}$$
+#[derive(Copy, Clone)]
+pub struct HenyeyGreensteinKind{
+    /// Single scattering RGB albedo.
+    albedo: Vec3,
+    /// Asymmetry parameter, mean cosine of the scattering angle (-1 < g < 1).
+    g: f32
+}
+
+/// Material Structure.
+/// Enumerable type which includes several material
+/// kinds: lambertian, metallic or dielectric surfaces,
+/// rough (GGX microfacet) conductors and dielectrics,
+/// coated diffuse surfaces, blends of two materials,
+/// isotropic or Henyey-Greenstein volumes, light emitters
+/// and materials with explicit thermo-optical properties.
+#[derive(Clone)]
+pub enum Material{
+    Lambertian(LambertianKind),
+    Metal(MetalKind),
+    Dielectric(DielectricKind),
+    MicrofacetConductor(MicrofacetConductorKind),
+    RoughDielectric(RoughDielectricKind),
+    CoatedDiffuse(CoatedDiffuseKind),
+    Mix(MixKind),
+    Isotropic(IsotropicKind),
+    HenyeyGreenstein(HenyeyGreensteinKind),
+    Emissive(EmissiveKind),
+    ThermoOptical(ThermoOpticalKind),
+}
+
+/// Ray scattering methods upon hitting any material.
+pub trait MaterialScatter{
+
+    /// Checks whether a ray hits the material and has enough
+    /// power in order not to be absorbed. Feedback is sent
+    /// with collision data.
+    ///
+    /// # Parameters
+    ///
+    /// * `self` - Material struct.
+    /// * `ray_in` - Incoming ray.
+    /// * `hit_rec` - Information about the surface-ray hit point.
+    /// * `attenuation` - How much the output ray is attenuated.
+    /// * `scattered` - Reflected or refracted ray.
+    ///
+    /// # Returns
+    ///
+    /// * bool - Whether the ray is successfully reflected or refracted.
+    fn scatter(
+        &self,
+        ray_in: &Ray,
+        hit_rec: &HitRecord,
+        attenuation: &mut Vec3,
+        scattered: &mut Ray) -> bool;
+}
+
+/// BSDF (or phase function) evaluation methods, required for
+/// combining material sampling with other sampling strategies
+/// such as light sampling (multiple importance sampling).
+pub trait MaterialPdf{
+
+    /// Whether the material scatters along discrete (delta) directions,
+    /// which can only be found by ``scatter`` itself.
+    fn is_specular(&self) -> bool;
+
+    /// Scattering function times the cosine with the surface normal,
+    /// $f(\vec i,\vec o)\left|\vec o\cdot\vec n\right|$, for the outgoing ``direction``.
+    /// Phase functions return $p(\vec i,\vec o)$ times the albedo.
+    ///
+    /// # Parameters
+    ///
+    /// * `self` - Material struct.
+    /// * `ray_in` - Incoming ray.
+    /// * `hit_rec` - Information about the surface-ray hit point.
+    /// * `direction` - Outgoing direction.
+    ///
+    /// # Returns
+    ///
+    /// * `Vec3` - RGB value (zero for specular materials).
+    fn eval(&self, ray_in: &Ray, hit_rec: &HitRecord, direction: Vec3) -> Vec3;
+
+    /// Solid angle probability density with which ``scatter``
+    /// generates the outgoing ``direction``.
+    ///
+    /// # Parameters
+    ///
+    /// * `self` - Material struct.
+    /// * `ray_in` - Incoming ray.
+    /// * `hit_rec` - Information about the surface-ray hit point.
+    /// * `direction` - Outgoing direction.
+    ///
+    /// # Returns
+    ///
+    /// * `f32` - Probability density (zero for specular materials).
+    fn pdf(&self, ray_in: &Ray, hit_rec: &HitRecord, direction: Vec3) -> f32;
+}
+
+/// Light emission of materials.
+pub trait MaterialEmit{
+
+    /// Radiance emitted by the material towards the incoming ray.
+    ///
+    /// # Parameters
+    ///
+    /// * `self` - Material struct.
+    /// * `ray_in` - Incoming ray.
+    /// * `hit_rec` - Information about the surface-ray hit point.
+    ///
+    /// # Returns
+    ///
+    /// * `Vec3` - Emitted radiance, RGB or monochromatic at the ray's wavelength.
+    fn emitted(&self, ray_in: &Ray, hit_rec: &HitRecord) -> Vec3;
+}
+
+/// Surface color of materials, used by the auxiliary (AOV) buffers.
+pub trait MaterialAlbedo{
+
+    /// Reflectance of the material, independent of the light directions.
+    /// Specular materials return their normal incidence reflectance,
+    /// clear dielectrics white and emitters their (clamped) radiance.
+    ///
+    /// # Parameters
+    ///
+    /// * `self` - Material struct.
+    /// * `hit_rec` - Information about the surface-ray hit point.
+    ///
+    /// # Returns
+    ///
+    /// * `Vec3` - RGB albedo, from 0 to 1.
+    fn albedo(&self, hit_rec: &HitRecord) -> Vec3;
+}
+
+/// Thermo-optical properties of materials, used by the radiation
+/// computations instead of the visible ones.
+pub trait MaterialThermoOptics{
+
+    /// Infrared emissivity, solar absorptivity and specularity of the
+    /// material. Materials without explicit data (see `ThermoOpticalKind`)
+    /// have the default infrared emissivity (see `DEFAULT_EMISSIVITY`)
+    /// and absorb the sunlight they do not reflect in the visible:
+    /// diffusely for lambertian and coated surfaces, specularly for
+    /// conductors, clear dielectrics being perfect solar mirrors.
+    /// Emitters are black bodies.
+    ///
+    /// # Parameters
+    ///
+    /// * `self` - Material struct.
+    /// * `p` - World point, for textured blends.
+    ///
+    /// # Returns
+    ///
+    /// * `ThermoOptics` - Thermo-optical properties.
+    fn thermo_optics(&self, p: Vec3) -> ThermoOptics;
+}
+
+/// Lambertian surface constructor trait implementation.
+impl LambertianKind{
+    pub fn new(albedo: Vec3) -> LambertianKind { LambertianKind {albedo, spectrum: Spectrum::Rgb(albedo)} }
+
+    /// Lambertian surface with a spectral reflectance.
+    pub fn spectral(spectrum: Spectrum) -> LambertianKind { LambertianKind {albedo: spectrum.to_rgb(), spectrum} }
+}
+
+impl MetalKind{
+    /// Metallic surface constructor.
+    pub fn new(albedo: Vec3, fuzz: f32) -> MetalKind { MetalKind {albedo, spectrum: Spectrum::Rgb(albedo), fuzz} }
+
+    /// Metallic surface with a spectral reflectance.
+    pub fn spectral(spectrum: Spectrum, fuzz: f32) -> MetalKind { MetalKind {albedo: spectrum.to_rgb(), spectrum, fuzz} }
+
+    /// Reflected ray's direction:
+    /// $$\vec v_{out} = \vec v_{in} - 2 v_{norm}\cdot\vec n$$
+    /// # Parameters
+    /// * `v` - Input ray's direction.
+    /// * `n` - Surface's normal direction.
+    ///
+    /// # Returns
+    /// * `Vec3` - Reflected ray's direction.
+    pub fn reflect(v: Vec3, n: Vec3) -> Vec3 { v - 2e0 * n.dot(&v) * n }
+}
+
+impl Dispersion{
+    /// Borosilicate crown glass (Schott N-BK7).
+    pub fn bk7() -> Dispersion {
+        Dispersion::Sellmeier {
+            b: [1.039_612, 0.231_792_3, 1.010_469],
+            c: [6.000_698_7e-3, 2.001_791_4e-2, 1.035_606_5e2]
+        }
+    }
+
+    /// Dense flint glass (Schott SF11).
+    pub fn sf11() -> Dispersion {
+        Dispersion::Sellmeier {
+            b: [1.737_596_9, 0.313_747_35, 1.898_781],
+            c: [1.318_870_7e-2, 6.230_686e-2, 1.551_236_3e2]
+        }
+    }
+
+    /// Fused silica.
+    pub fn fused_silica() -> Dispersion {
+        Dispersion::Sellmeier {
+            b: [0.696_166_3, 0.407_942_6, 0.897_479_4],
+            c: [4.679_148e-3, 1.351_206_3e-2, 9.793_4e1]
+        }
+    }
+
+    /// Water (Cauchy fit on the visible range).
+    pub fn water() -> Dispersion {
+        Dispersion::Cauchy { a: 1.324, b: 3.046e-3 }
+    }
+
+    /// Refractive index at wavelength ``lambda`` (nm), if dispersive.
+    pub fn index(&self, lambda: f32) -> Option<f32> {
+        let l2: f32 = (lambda * 1e-3).powi(2);
+        match self {
+            Dispersion::None => None,
+            Dispersion::Tabulated(spectrum) => Some(spectrum.at(lambda)),
+            Dispersion::Cauchy { a, b } => Some(a + b / l2),
+            Dispersion::Sellmeier { b, c } => {
+                let mut n2: f32 = 1e0;
+                for i in 0..3 {
+                    n2 += b[i] * l2 / (l2 - c[i]);
+                }
+                Some(n2.sqrt())
+            }
+        }
+    }
+}
+
+impl DielectricKind{
+    /// Dielectric surface constructor implementation.
+    pub fn new(n: f32) -> DielectricKind{
+        DielectricKind{n, absorption: Vec3::zeros(), dispersion: Dispersion::None}
+    }
+
+    /// Absorbing (tinted) dielectric constructor.
+    ///
+    /// # Parameters:
+    /// * `n` - Refractive index.
+    /// * `absorption` - RGB absorption coefficient (1 / length).
+    pub fn absorbing(n: f32, absorption: Vec3) -> DielectricKind{
+        DielectricKind{n, absorption, dispersion: Dispersion::None}
+    }
+
+    /// Dispersive dielectric constructor. Rays hitting the
+    /// material are split into their wavelengths.
+    ///
+    /// # Parameters:
+    /// * `dispersion` - Refractive index wavelength dependence.
+    /// * `absorption` - RGB absorption coefficient (1 / length).
+    pub fn dispersive(dispersion: Dispersion, absorption: Vec3) -> DielectricKind{
+        let n: f32 = dispersion.index(spectrum::LAMBDA_D).unwrap_or(1e0);
+        DielectricKind{n, absorption, dispersion}
+    }
+
+    /// Refractive index seen by ``ray_in``. White rays hitting a dispersive
+    /// material get a sampled wavelength, which is stored on ``scattered``,
+    /// and ``tint`` is weighted by the wavelength's color.
+    fn index(&self, ray_in: &Ray, scattered: &mut Ray, tint: &mut Vec3) -> f32 {
+        if let Dispersion::None = self.dispersion {
+            return self.n;
+        }
+        let lambda: f32 = match ray_in.wavelength {
+            Some(lambda) => lambda,
+            None => {
+                let (lambda, _pdf) = spectrum::sample_wavelength(random::<f32>());
+                *tint *= spectrum::wavelength_to_rgb(lambda);
+                lambda
+            }
+        };
+        scattered.wavelength = Some(lambda);
+        self.dispersion.index(lambda).unwrap_or(self.n)
+    }
+
+    /// Beer-Lambert transmittance after travelling ``length`` inside the material.
+    pub fn transmittance(&self, length: f32) -> Vec3 {
+        if self.absorption.max() <= 0e0 {
+            return Vec3::ones();
+        }
+        Vec3::new(
+            (-self.absorption.x() * length).exp(),
+            (-self.absorption.y() * length).exp(),
+            (-self.absorption.z() * length).exp()
+        )
+    }
+
+    /// Dielectric refraction:
+    ///
+    /// $$\eta'\sin\theta'=\eta\sin\theta$$
+    /// $$\cos\theta=-\vec v_{in}\cdot\vec n$$
+    /// $$\vec v_{out} = \frac{\eta}{\eta'}\left(\vec n\times\left(-\vec n\times\vec v_{in}\right)\right)-\left[1-\left(\frac{\eta}{\eta'}\right)^2\left|\vec n\times\vec v_{in}\right|^2\right]^{\frac 1 2}\vec n$$
+    ///
+    /// # Parameters:
+    /// * `v` - Input ray's direction.
+    /// * `n` - Surface normal.
+    /// * `ni_over_nt` - input over output refractive index fraction.
+    /// * `refracted` - refracted direction.
+    ///
+    /// # Returns:
+    /// * `bool` - Whether the ray was refracted or not.
+    pub fn refract(
+        v: Vec3,
+        n: Vec3,
+        ni_over_nt: f32,
+        refracted: &mut Vec3) -> bool{
+
+        let uv: Vec3 = v.unit_vector();
+        let v_out_perp: Vec3 = ni_over_nt * n.cross(&(-n.cross(&uv)));
+        let v_out_norm: Vec3 = -(1e0 - ni_over_nt.powi(2) * (n.cross(&uv)).square_length()).sqrt() * n;
+        *refracted = v_out_perp + v_out_norm;
+        true
+    }
+
+    /// Polynomial approximation by Christophe Schlick for
+    /// ray reflection value instead of full refraction
+    /// depending on input angle (cosine).
+    ///
+    /// # Parameters:
+    ///
+    /// * `ni_over_nt` - input over output refractive index fraction.
+    /// * `cosine` - input ray and surface normal angle's cosine.
+    ///
+    /// # Returns:
+    ///
+    /// * `f32` - reflectivity.
+    pub fn schlick(ni_over_nt: f32, cosine: f32) -> f32 {
+        let r0: f32 = ((1e0 - ni_over_nt) / (1e0 + ni_over_nt)).powi(2);
+        r0 + (1e0 - r0) * ((1e0 - cosine).powi(5))
+    }
+}
+
+impl EmissiveKind{
+    /// Emissive surface constructor.
+    pub fn new(radiance: Vec3) -> EmissiveKind { EmissiveKind {radiance, spectrum: Spectrum::Rgb(radiance)} }
+
+    /// Emissive surface with a spectral radiance.
+    pub fn spectral(spectrum: Spectrum) -> EmissiveKind { EmissiveKind {radiance: spectrum.to_rgb(), spectrum} }
+
+    /// Black body emitter at ``temperature`` (K) with peak spectral radiance ``scale``.
+    pub fn blackbody(temperature: f32, scale: f32) -> EmissiveKind {
+        EmissiveKind::spectral(Spectrum::blackbody(temperature, scale))
+    }
+}
+
+impl IsotropicKind{
+    /// Isotropic phase function constructor.
+    pub fn new(albedo: Vec3) -> IsotropicKind { IsotropicKind {albedo} }
+}
+
+impl HenyeyGreensteinKind{
+    /// Henyey-Greenstein phase function constructor.
+    pub fn new(albedo: Vec3, g: f32) -> HenyeyGreensteinKind { HenyeyGreensteinKind {albedo, g} }
+
+    /// Phase function value for a scattering angle of cosine ``cosine``.
+    pub fn phase(&self, cosine: f32) -> f32 {
+        let denom: f32 = 1e0 + self.g.powi(2) - 2e0 * self.g * cosine;
+        (1e0 - self.g.powi(2)) / (4e0 * std::f32::consts::PI * denom * denom.sqrt())
+    }
+
+    /// Scattered direction sampled from the phase function,
+    /// by inversion of its cumulative distribution:
+    /// $$\cos\theta=\frac{1}{2g}\left[1+g^2-\left(\frac{1-g^2}{1-g+2g\xi}\right)^2\right]$$
+    ///
+    /// # Parameters:
+    /// * `v` - Incoming ray's direction.
+    ///
+    /// # Returns:
+    /// * `Vec3` - Scattered unit direction.
+    pub fn sample(&self, v: Vec3) -> Vec3 {
+        let xi: f32 = random::<f32>();
+        let cosine: f32 = match self.g.abs() < 1e-3 {
+            true => 1e0 - 2e0 * xi,
+            false => {
+                let sq: f32 = (1e0 - self.g.powi(2)) / (1e0 - self.g + 2e0 * self.g * xi);
+                (1e0 + self.g.powi(2) - sq.powi(2)) / (2e0 * self.g)
+            }
+        };
+        let sine: f32 = (1e0 - cosine.powi(2)).max(0e0).sqrt();
+        let phi: f32 = 2e0 * std::f32::consts::PI * random::<f32>();
+        let w: Vec3 = v.unit_vector();
+        let (u, v) = w.orthonormal_basis();
+        u * (sine * phi.cos()) + v * (sine * phi.sin()) + w * cosine
+    }
+}
+
+/// MaterialScatter implementation for all material kinds.
+impl MaterialScatter for Material{
+    fn scatter(
+        &self,
+        ray_in: &Ray,
+        hit_rec: &HitRecord,
+        attenuation: &mut Vec3,
+        scattered: &mut Ray) -> bool{
+        match self {
+            // Lambertian.
+            Material::Lambertian(lambertian) => {
+
+                // New ray origin (hit point).
+                scattered.a = hit_rec.p;
+                // New ray direction (lambertian, cosine weighted), the
+                // normal itself if the sum (almost) cancels out.
+                let direction: Vec3 = hit_rec.normal + random_unit_vector();
+                scattered.b = match direction.square_length() < 1e-8 {
+                    true => hit_rec.normal,
+                    false => direction,
+                };
+                // New attenuation/color.
+                attenuation.e = lambertian.spectrum.value(ray_in.wavelength).e;
+                // New scattered ray always exists.
+                true
+            }
+            // Metallic.
+            Material::Metal(metal) => {
+                // Reflected ray direction.
+                let reflected = MetalKind::reflect(ray_in.direction().unit_vector(), hit_rec.normal);
+                // New ray origin (hit point).
+                scattered.a = hit_rec.p;
+                // New ray direction (reflected + fuzz).
+                scattered.b = reflected + random_in_unit_sphere() * metal.fuzz;
+                // New attenuation/color.
+                attenuation.e = metal.spectrum.value(ray_in.wavelength).e;
+                // Ray exists if input ray came toward the surface.
+                scattered.direction().dot(&hit_rec.normal) > 0e0
+            }
+            // Dielectric.
+            Material::Dielectric(dielectric) => {
+                // Reflection case.
+                let reflected: Vec3 = MetalKind::reflect(ray_in.direction(), hit_rec.normal);
+                // Refraction case. Random initialization, refraction function call sets the real value.
+                let mut refracted: Vec3 = Vec3::random();
+
+                let mut tint: Vec3 = Vec3::ones();
+                let n: f32 = dielectric.index(ray_in, scattered, &mut tint);
+                // Check from and to which ambient the ray is crossing.
+                let (outward_normal, ni_over_nt): (Vec3, f32) = match ray_in.direction().dot(&hit_rec.normal) > 0e0 {
+                    // Ray crossed the material and tries to scape through this surface:
+                    // surface normal is inwards, refraction index ratio is kept.
+                    true => {
+                        // Absorption along the path travelled inside the material.
+                        let tau: Vec3 = dielectric.transmittance(hit_rec.t * ray_in.direction().length());
+                        tint *= Spectrum::Rgb(tau).value(scattered.wavelength);
+                        (-hit_rec.normal, n)
+                    }
+                    // Surface normal is outward (as it is), dielectric to air
+                    // transition, use reciprocal value.
+                    false => (hit_rec.normal, 1e0 / n),
+                };
+                attenuation.e = tint.e;
+
+                // Angle cosine an sine.
+                let cosine: f32 = -ray_in.direction().unit_vector().dot(&outward_normal);
+                let sine: f32 = (1e0 - cosine.powi(2)).sqrt();
+
+                // Pure reflection case.
+                if ni_over_nt * sine > 1e0 {
+                    scattered.a = hit_rec.p;
+                    scattered.b = reflected;
+                    return true;
+                }
+
+                // Reflection probability.
+                let reflect_prob: f32 = DielectricKind::schlick(ni_over_nt, cosine);
+
+                // Refraction occurs.
+                if DielectricKind::refract(
+                    ray_in.direction().unit_vector(),
+                    outward_normal,
+                    ni_over_nt,
+                    &mut refracted) {
+                    // Random reflection.
+                    // if rng.gen::<f32>() < reflect_prob {
+                    if random::<f32>() < reflect_prob {
+                        scattered.a = hit_rec.p;
+                        scattered.b = reflected;
+                        true
+                    }
+                    // Refraction.
+                    else {
+                        scattered.a = hit_rec.p;
+                        scattered.b = refracted;
+                        true
+                    }
+                }
+                // No refraction (never occurs).
+                else {
+                    false
+                }
+            }
+            // Rough conductor.
+            Material::MicrofacetConductor(conductor) => conductor.scatter(ray_in, hit_rec, attenuation, scattered),
+            // Rough dielectric.
+            Material::RoughDielectric(dielectric) => {
+                dielectric.scatter(ray_in, hit_rec, attenuation, scattered)
+            }
+            // Clear coated diffuse.
+            Material::CoatedDiffuse(coated) => {
+                coated.scatter(ray_in, hit_rec, attenuation, scattered)
+            }
+            // Blend, scatter as one of the component materials.
+            Material::Mix(mix) => {
+                mix.choose(hit_rec.p).scatter(ray_in, hit_rec, attenuation, scattered)
+            }
+            // Isotropic volume.
+            Material::Isotropic(isotropic) => {
+                // New ray origin (scattering point inside the medium).
+                scattered.a = hit_rec.p;
+                // New ray direction (uniform over the sphere).
+                scattered.b = random_unit_vector();
+                // New attenuation/color.
+                attenuation.e = Spectrum::Rgb(isotropic.albedo).value(ray_in.wavelength).e;
+                true
+            }
+            // Henyey-Greenstein volume.
+            Material::HenyeyGreenstein(hg) => {
+                // New ray origin (scattering point inside the medium).
+                scattered.a = hit_rec.p;
+                // New ray direction (around the incoming direction).
+                scattered.b = hg.sample(ray_in.direction());
+                // New attenuation/color.
+                attenuation.e = Spectrum::Rgb(hg.albedo).value(ray_in.wavelength).e;
+                true
+            }
+            // Light source, every ray is absorbed.
+            Material::Emissive(_) => false,
+            // Rendered as the visible material.
+            Material::ThermoOptical(thermal) => {
+                thermal.visible().scatter(ray_in, hit_rec, attenuation, scattered)
+            }
+        }
+    }
+}
+
+/// MaterialPdf implementation for all material kinds.
+impl MaterialPdf for Material{
+    fn is_specular(&self) -> bool {
+        match self {
+            Material::Mix(mix) => {
+                let (first, second) = mix.materials();
+                first.is_specular() && second.is_specular()
+            }
+            Material::ThermoOptical(thermal) => thermal.visible().is_specular(),
+            _ => matches!(self, Material::Metal(_) | Material::Dielectric(_)),
+        }
+    }
+
+    fn eval(&self, ray_in: &Ray, hit_rec: &HitRecord, direction: Vec3) -> Vec3 {
+        let pdf: f32 = self.pdf(ray_in, hit_rec, direction);
+        let rgb: Vec3 = match self {
+            Material::Lambertian(lambertian) => {
+                return lambertian.spectrum.value(ray_in.wavelength) * pdf;
+            }
+            Material::MicrofacetConductor(conductor) => return conductor.eval(ray_in, hit_rec, direction),
+            Material::RoughDielectric(dielectric) => dielectric.eval(ray_in, hit_rec, direction),
+            Material::CoatedDiffuse(coated) => return coated.eval(ray_in, hit_rec, direction),
+            Material::Mix(mix) => {
+                let w: f32 = mix.weight(hit_rec.p);
+                let (first, second) = mix.materials();
+                return first.eval(ray_in, hit_rec, direction) * w
+                    + second.eval(ray_in, hit_rec, direction) * (1e0 - w);
+            }
+            Material::ThermoOptical(thermal) => return thermal.visible().eval(ray_in, hit_rec, direction),
+            Material::Isotropic(isotropic) => isotropic.albedo * pdf,
+            Material::HenyeyGreenstein(hg) => hg.albedo * pdf,
+            Material::Metal(_) | Material::Dielectric(_) | Material::Emissive(_) => Vec3::zeros(),
+        };
+        Spectrum::Rgb(rgb).value(ray_in.wavelength)
+    }
+
+    fn pdf(&self, ray_in: &Ray, hit_rec: &HitRecord, direction: Vec3) -> f32 {
+        match self {
+            // Cosine weighted hemisphere.
+            Material::Lambertian(_) => {
+                (direction.unit_vector().dot(&hit_rec.normal)).max(0e0) / std::f32::consts::PI
+            }
+            Material::MicrofacetConductor(conductor) => conductor.pdf(ray_in, hit_rec, direction),
+            Material::RoughDielectric(dielectric) => dielectric.pdf(ray_in, hit_rec, direction),
+            Material::CoatedDiffuse(coated) => coated.pdf(ray_in, hit_rec, direction),
+            Material::Mix(mix) => {
+                let w: f32 = mix.weight(hit_rec.p);
+                let (first, second) = mix.materials();
+                first.pdf(ray_in, hit_rec, direction) * w + second.pdf(ray_in, hit_rec, direction) * (1e0 - w)
+            }
+            Material::ThermoOptical(thermal) => thermal.visible().pdf(ray_in, hit_rec, direction),
+            Material::Isotropic(_) => 1e0 / (4e0 * std::f32::consts::PI),
+            Material::HenyeyGreenstein(hg) => {
+                hg.phase(ray_in.direction().unit_vector().dot(&direction.unit_vector()))
+            }
+            Material::Metal(_) | Material::Dielectric(_) | Material::Emissive(_) => 0e0,
+        }
+    }
+}
+
+/// MaterialEmit implementation for all material kinds.
+impl MaterialEmit for Material{
+    fn emitted(&self, ray_in: &Ray, hit_rec: &HitRecord) -> Vec3 {
+        match self {
+            Material::Emissive(emissive) => emissive.spectrum.value(ray_in.wavelength),
+            Material::Mix(mix) => {
+                let w: f32 = mix.weight(hit_rec.p);
+                let (first, second) = mix.materials();
+                first.emitted(ray_in, hit_rec) * w + second.emitted(ray_in, hit_rec) * (1e0 - w)
+            }
+            Material::ThermoOptical(thermal) => thermal.visible().emitted(ray_in, hit_rec),
+            _ => Vec3::zeros(),
+        }
+    }
+}
+
+/// MaterialAlbedo implementation for all material kinds.
+impl MaterialAlbedo for Material{
+    fn albedo(&self, hit_rec: &HitRecord) -> Vec3 {
+        match self {
+            Material::Lambertian(lambertian) => lambertian.albedo,
+            Material::Metal(metal) => metal.albedo,
+            Material::Dielectric(_) | Material::RoughDielectric(_) => Vec3::ones(),
+            Material::MicrofacetConductor(conductor) => conductor.fresnel(1e0, None),
+            Material::CoatedDiffuse(coated) => coated.albedo(),
+            Material::Mix(mix) => {
+                let w: f32 = mix.weight(hit_rec.p);
+                let (first, second) = mix.materials();
+                first.albedo(hit_rec) * w + second.albedo(hit_rec) * (1e0 - w)
+            }
+            Material::Isotropic(isotropic) => isotropic.albedo,
+            Material::HenyeyGreenstein(hg) => hg.albedo,
+            Material::Emissive(emissive) => Vec3::new(
+                emissive.radiance.x().min(1e0),
+                emissive.radiance.y().min(1e0),
+                emissive.radiance.z().min(1e0)
+            ),
+            Material::ThermoOptical(thermal) => thermal.visible().albedo(hit_rec),
+        }
+    }
+}
+
+/// MaterialThermoOptics implementation for all material kinds.
+impl MaterialThermoOptics for Material{
+    fn thermo_optics(&self, p: Vec3) -> ThermoOptics {
+        let mean = |rgb: Vec3| -> f32 { (rgb.x() + rgb.y() + rgb.z()) / 3e0 };
+        match self {
+            Material::Lambertian(lambertian) => ThermoOptics::solar(mean(lambertian.albedo), 0e0),
+            Material::Metal(metal) => ThermoOptics::solar(mean(metal.albedo), 1e0),
+            Material::MicrofacetConductor(conductor) => ThermoOptics::solar(mean(conductor.fresnel(1e0, None)), 1e0),
+            Material::Dielectric(_) | Material::RoughDielectric(_) => ThermoOptics::solar(1e0, 1e0),
+            Material::CoatedDiffuse(coated) => ThermoOptics::solar(mean(coated.albedo()), 0e0),
+            Material::Mix(mix) => {
+                let w: f32 = mix.weight(p);
+                let (first, second) = mix.materials();
+                let (a, b): (ThermoOptics, ThermoOptics) = (first.thermo_optics(p), second.thermo_optics(p));
+                ThermoOptics::new(
+                    a.emissivity * w + b.emissivity * (1e0 - w),
+                    a.absorptivity * w + b.absorptivity * (1e0 - w),
+                    a.specularity * w + b.specularity * (1e0 - w)
+                )
+            }
+            Material::Isotropic(isotropic) => ThermoOptics::solar(mean(isotropic.albedo), 0e0),
+            Material::HenyeyGreenstein(hg) => ThermoOptics::solar(mean(hg.albedo), 0e0),
+            Material::Emissive(_) => ThermoOptics::grey(0e0, 0e0),
+            Material::ThermoOptical(thermal) => thermal.optics(),
+        }
+    }
+}
+
+/// Random 3D point inside unit sphere.
+fn random_in_unit_sphere() -> Vec3{
+    // Initialization.
+    let mut p: Vec3 = Vec3::new(2e0,2e0,2e0);
+
+    // Loop until ``p`` inside sphere.
+    while p.square_length() >= 1e0 {
+        p = Vec3::random() * 2e0 - Vec3::ones();
+    }
+    p
+}
+
+/// Random 3D unit vector (uniform over the unit sphere).
+pub(crate) fn random_unit_vector() -> Vec3{
+    let z: f32 = 1e0 - 2e0 * random::<f32>();
+    let r: f32 = (1e0 - z.powi(2)).max(0e0).sqrt();
+    let phi: f32 = 2e0 * std::f32::consts::PI * random::<f32>();
+    Vec3::new(r * phi.cos(), r * phi.sin(), z)
+}
@@ -0,0 +1,38 @@
+//! Scalar textures, used for driving the blend weight of mixed materials.
+
+use crate::vectors::{Vec3, Vec3Methods};
+
+/// Scalar texture defined on world space.
+#[derive(Copy, Clone)]
+pub enum Texture {
+    /// Same value everywhere.
+    Constant(f32),
+    /// 3D checkerboard of cells of size ``scale``, alternating
+    /// between the ``odd`` and ``even`` values.
+    Checker { scale: f32, odd: f32, even: f32 },
+    /// Linear ramp from ``start`` at ``origin`` to ``end`` at ``origin + axis``,
+    /// clamped outside.
+    Gradient { origin: Vec3, axis: Vec3, start: f32, end: f32 },
+}
+
+impl Texture {
+    /// Texture value at world point ``p``.
+    pub fn value(&self, p: Vec3) -> f32 {
+        match self {
+            Texture::Constant(value) => *value,
+            Texture::Checker { scale, odd, even } => {
+                let cells: i64 = (p.x() / scale).floor() as i64
+                    + (p.y() / scale).floor() as i64
+                    + (p.z() / scale).floor() as i64;
+                match cells.rem_euclid(2) == 0 {
+                    true => *even,
+                    false => *odd,
+                }
+            }
+            Texture::Gradient { origin, axis, start, end } => {
+                let f: f32 = ((p - *origin).dot(axis) / axis.square_length()).clamp(0e0, 1e0);
+                start + (end - start) * f
+            }
+        }
+    }
+}
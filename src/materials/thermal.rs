@@ -0,0 +1,113 @@
+//! Thermo-optical surface properties.
+
+use std::sync::Arc;
+
+use crate::materials::Material;
+
+/// Thermo-optical properties of a grey opaque surface in the two bands
+/// of spacecraft thermal analysis: the infrared, where surfaces emit,
+/// and the solar spectrum, where they absorb sunlight.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct ThermoOptics {
+    /// Infrared hemispherical emissivity $\varepsilon$.
+    pub emissivity: f32,
+    /// Solar absorptivity $\alpha$.
+    pub absorptivity: f32,
+    /// Specular fraction of the reflectivity, from 0 (diffuse) to 1 (mirror).
+    pub specularity: f32,
+}
+
+/// Infrared emissivity of materials without explicit thermo-optical
+/// data (see `MaterialThermoOptics`), typical of paints and most
+/// non-metallic surfaces. The visible color tells nothing about the
+/// infrared, so metals should be given their measured values with a
+/// `ThermoOpticalKind`.
+pub const DEFAULT_EMISSIVITY: f32 = 0.85;
+
+/// Coating library, beginning of life values.
+const COATINGS: [(&str, ThermoOptics); 8] = [
+    ("white paint", ThermoOptics { emissivity: 0.88, absorptivity: 0.20, specularity: 0e0 }),
+    ("black paint", ThermoOptics { emissivity: 0.88, absorptivity: 0.96, specularity: 0e0 }),
+    ("aluminised kapton", ThermoOptics { emissivity: 0.67, absorptivity: 0.38, specularity: 0.8 }),
+    ("osr", ThermoOptics { emissivity: 0.80, absorptivity: 0.07, specularity: 1e0 }),
+    ("silver teflon", ThermoOptics { emissivity: 0.78, absorptivity: 0.08, specularity: 1e0 }),
+    ("polished aluminium", ThermoOptics { emissivity: 0.05, absorptivity: 0.15, specularity: 0.9 }),
+    ("gold", ThermoOptics { emissivity: 0.03, absorptivity: 0.25, specularity: 0.9 }),
+    ("solar cell", ThermoOptics { emissivity: 0.85, absorptivity: 0.92, specularity: 0.5 }),
+];
+
+impl ThermoOptics {
+    /// ThermoOptics constructor, values clamped from 0 to 1.
+    ///
+    /// # Parameters:
+    /// * `emissivity` - Infrared emissivity.
+    /// * `absorptivity` - Solar absorptivity.
+    /// * `specularity` - Specular fraction of the reflectivity.
+    pub fn new(emissivity: f32, absorptivity: f32, specularity: f32) -> ThermoOptics {
+        ThermoOptics {
+            emissivity: emissivity.clamp(0e0, 1e0),
+            absorptivity: absorptivity.clamp(0e0, 1e0),
+            specularity: specularity.clamp(0e0, 1e0),
+        }
+    }
+
+    /// Grey surface of the given reflectivity in both bands.
+    pub fn grey(reflectivity: f32, specularity: f32) -> ThermoOptics {
+        let absorptivity: f32 = 1e0 - reflectivity.clamp(0e0, 1e0);
+        ThermoOptics::new(absorptivity, absorptivity, specularity)
+    }
+
+    /// Surface of the given solar reflectivity, with the default
+    /// infrared emissivity (see `DEFAULT_EMISSIVITY`).
+    pub fn solar(reflectivity: f32, specularity: f32) -> ThermoOptics {
+        ThermoOptics::new(DEFAULT_EMISSIVITY, 1e0 - reflectivity.clamp(0e0, 1e0), specularity)
+    }
+
+    /// Coating of the library by name (case insensitive): ``white paint``,
+    /// ``black paint``, ``aluminised kapton``, ``osr`` (optical solar
+    /// reflector), ``silver teflon``, ``polished aluminium``, ``gold``
+    /// and ``solar cell``.
+    pub fn coating(name: &str) -> Option<ThermoOptics> {
+        let name: String = name.trim().to_lowercase();
+        COATINGS.iter().find(|(coating, _)| *coating == name).map(|(_, optics)| *optics)
+    }
+
+    /// Names of the library coatings.
+    pub fn coatings() -> Vec<&'static str> {
+        COATINGS.iter().map(|(name, _)| *name).collect()
+    }
+}
+
+/// Material with explicit thermo-optical properties.
+/// Rendering uses the ``visible`` material, while the radiation
+/// (view factor, exchange and heat load) computations use ``optics``.
+#[derive(Clone)]
+pub struct ThermoOpticalKind{
+    /// Material seen by the renderer.
+    visible: Arc<Material>,
+    /// Thermo-optical properties.
+    optics: ThermoOptics
+}
+
+impl ThermoOpticalKind{
+    /// ThermoOpticalKind constructor.
+    ///
+    /// # Parameters:
+    /// * `visible` - Material seen by the renderer.
+    /// * `optics` - Thermo-optical properties.
+    pub fn new(visible: Material, optics: ThermoOptics) -> ThermoOpticalKind {
+        ThermoOpticalKind { visible: Arc::new(visible), optics }
+    }
+
+    /// Material with a coating of the library (see `ThermoOptics::coating`),
+    /// none if the name is unknown.
+    pub fn coating(visible: Material, name: &str) -> Option<ThermoOpticalKind> {
+        ThermoOptics::coating(name).map(|optics| ThermoOpticalKind::new(visible, optics))
+    }
+
+    /// Material seen by the renderer.
+    pub fn visible(&self) -> &Material { &self.visible }
+
+    /// Thermo-optical properties.
+    pub fn optics(&self) -> ThermoOptics { self.optics }
+}
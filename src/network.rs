@@ -0,0 +1,395 @@
+//! Thermal network module. Lumped parameter model in which forms (or
+//! patches) are nodes with a thermal capacitance, joined by conductive
+//! and radiative links, solved for the steady state or in time.
+
+use std::fmt::{Display, Formatter, Error};
+
+extern crate nalgebra;
+use self::nalgebra::{DMatrix, DVector};
+
+use crate::objects::{SurfaceFunctions, HittableList};
+use crate::radiation::Vfs;
+use crate::exchange::SurfaceOptics;
+
+/// Stefan-Boltzmann constant (W / m² K⁴).
+pub const STEFAN_BOLTZMANN: f32 = 5.670_374e-8;
+
+/// Newton iterations convergence tolerance (K).
+const NEWTON_TOL: f32 = 1e-3;
+
+/// Maximum number of Newton iterations per solve.
+const NEWTON_MAX_ITER: usize = 100;
+
+/// Thermal node.
+#[derive(Copy, Clone, Debug)]
+pub struct Node {
+    /// Thermal capacitance (J / K), zero for arithmetic nodes.
+    pub capacitance: f32,
+    /// Temperature (K), initial or fixed.
+    pub temperature: f32,
+    /// Heat load (W).
+    pub load: f32,
+    /// Whether the temperature is fixed (boundary node).
+    pub boundary: bool,
+}
+
+/// Link between two nodes.
+#[derive(Copy, Clone, Debug)]
+pub struct Link {
+    /// First node.
+    pub i: usize,
+    /// Second node.
+    pub j: usize,
+    /// Conductance: $G$ (W / K) for conductive links, $GR$ (m²) for
+    /// radiative ones.
+    pub value: f32,
+}
+
+/// Time integration schemes.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum TimeScheme {
+    /// Implicit (backward) Euler, first order, unconditionally stable.
+    ImplicitEuler,
+    /// Crank-Nicolson, second order, may oscillate with large steps.
+    CrankNicolson,
+}
+
+impl TimeScheme {
+    /// Weight $\theta$ of the end of step rates.
+    fn theta(&self) -> f32 {
+        match self {
+            TimeScheme::ImplicitEuler => 1e0,
+            TimeScheme::CrankNicolson => 5e-1,
+        }
+    }
+}
+
+/// Lumped parameter thermal network. The net power into node $i$ is
+/// $$R_i=Q_i+\sum_jG_{ij}\left(T_j-T_i\right)+\sigma\sum_jGR_{ij}\left(T_j^4-T_i^4\right)$$
+/// and its temperature evolves as $C_i\dot T_i=R_i$.
+pub struct ThermalNetwork {
+    /// Nodes.
+    pub nodes: Vec<Node>,
+    /// Conductive links.
+    pub conductors: Vec<Link>,
+    /// Radiative links.
+    pub radiative: Vec<Link>,
+}
+
+/// Temperatures over time.
+pub struct Transient {
+    /// Instants (s), starting with the initial one.
+    pub times: Vec<f32>,
+    /// ``temperatures[k][i]`` is the temperature of node $i$ at ``times[k]`` (K).
+    pub temperatures: Vec<Vec<f32>>,
+}
+
+/// Failure of the Newton iterations of a solve.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum NewtonError {
+    /// Singular Jacobian, e.g. a non boundary node with neither
+    /// capacitance nor links.
+    Singular,
+    /// No convergence in `NEWTON_MAX_ITER` iterations, with the last
+    /// temperature change (K).
+    NotConverged(f32),
+}
+
+/// Display trait implementation on NewtonError.
+impl Display for NewtonError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        match self {
+            NewtonError::Singular => write!(f, "singular thermal network Jacobian"),
+            NewtonError::NotConverged(change) => write!(
+                f, "no convergence in {} Newton iterations (last change {} K)", NEWTON_MAX_ITER, change
+            ),
+        }
+    }
+}
+
+/// Display trait implementation on Transient, one line per instant:
+/// the time followed by the node temperatures.
+impl Display for Transient {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        for (time, temps) in self.times.iter().zip(self.temperatures.iter()) {
+            write!(f, "{:.4}", time)?;
+            for temp in temps.iter() {
+                write!(f, ";{:.4}", temp)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+impl Default for ThermalNetwork {
+    fn default() -> ThermalNetwork {
+        ThermalNetwork::new()
+    }
+}
+
+impl ThermalNetwork {
+    /// Empty network.
+    pub fn new() -> ThermalNetwork {
+        ThermalNetwork { nodes: Vec::new(), conductors: Vec::new(), radiative: Vec::new() }
+    }
+
+    /// Network with a node per world form, radiatively linked by the
+    /// given conductors, and a boundary node for the deep space, which
+    /// absorbs what leaves the world: $GR_{i,space}=\varepsilon_iA_i-\sum_jGR_{ij}$.
+    ///
+    /// # Parameters:
+    /// * `world` - Forms, node $i$ being form $i$.
+    /// * `optics` - Infrared properties of each form.
+    /// * `conductors` - Full radiative conductor matrix (see
+    ///   `Gebhart::radiative_conductors` and `view_factor_conductors`),
+    ///   averaged with its transpose.
+    /// * `capacitances` - Thermal capacitance of each form (J / K).
+    /// * `temperature` - Initial temperature of every form (K).
+    /// * `space_temperature` - Deep space temperature (K).
+    ///
+    /// # Returns:
+    /// * `ThermalNetwork` - Network, the space being the last node.
+    ///
+    /// # Panics:
+    /// If ``optics``, ``conductors`` (or any of its rows) or
+    /// ``capacitances`` do not have one entry per form.
+    pub fn from_world(
+        world: &HittableList,
+        optics: &[SurfaceOptics],
+        conductors: &[Vec<f32>],
+        capacitances: &[f32],
+        temperature: f32,
+        space_temperature: f32) -> ThermalNetwork
+    {
+        let n: usize = world.forms.len();
+        assert_eq!(optics.len(), n, "one surface optics per form expected");
+        assert_eq!(capacitances.len(), n, "one capacitance per form expected");
+        assert!(
+            conductors.len() == n && conductors.iter().all(|row| row.len() == n),
+            "{}x{} radiative conductor matrix expected", n, n
+        );
+        let mut network: ThermalNetwork = ThermalNetwork::new();
+        for capacitance in capacitances.iter() {
+            network.add_node(*capacitance, temperature);
+        }
+        let space: usize = network.add_boundary(space_temperature);
+        for i in 0..n {
+            for (j, row) in conductors.iter().enumerate().skip(i + 1) {
+                let gr: f32 = 5e-1 * (conductors[i][j] + row[i]);
+                if gr > 0e0 {
+                    network.add_radiative(i, j, gr);
+                }
+            }
+            let emitted: f32 = optics[i].emissivity * world.forms[i].area();
+            let exchanged: f32 = conductors[i].iter().sum();
+            if emitted > exchanged {
+                network.add_radiative(i, space, emitted - exchanged);
+            }
+        }
+        network
+    }
+
+    /// Adds a diffusion (or arithmetic, if ``capacitance`` is 0) node.
+    ///
+    /// # Returns:
+    /// * `usize` - Node index.
+    pub fn add_node(&mut self, capacitance: f32, temperature: f32) -> usize {
+        self.nodes.push(Node { capacitance, temperature, load: 0e0, boundary: false });
+        self.nodes.len() - 1
+    }
+
+    /// Adds a boundary node of fixed ``temperature``.
+    ///
+    /// # Returns:
+    /// * `usize` - Node index.
+    pub fn add_boundary(&mut self, temperature: f32) -> usize {
+        self.nodes.push(Node { capacitance: 0e0, temperature, load: 0e0, boundary: true });
+        self.nodes.len() - 1
+    }
+
+    /// Adds a conductive link of conductance ``g`` (W / K).
+    pub fn add_conductor(&mut self, i: usize, j: usize, g: f32) {
+        self.conductors.push(Link { i, j, value: g });
+    }
+
+    /// Adds a radiative link of conductor ``gr`` (m²).
+    pub fn add_radiative(&mut self, i: usize, j: usize, gr: f32) {
+        self.radiative.push(Link { i, j, value: gr });
+    }
+
+    /// Sets the heat loads of the first nodes (W).
+    pub fn set_loads(&mut self, loads: &[f32]) {
+        for (node, load) in self.nodes.iter_mut().zip(loads.iter()) {
+            node.load = *load;
+        }
+    }
+
+    /// Current node temperatures (K).
+    pub fn temperatures(&self) -> Vec<f32> {
+        self.nodes.iter().map(|node| node.temperature).collect()
+    }
+
+    /// Net power into each node (W), $R_i$.
+    ///
+    /// # Parameters:
+    /// * `temps` - Node temperatures (K).
+    /// * `loads` - Node heat loads (W).
+    pub fn net_power(&self, temps: &[f32], loads: &[f32]) -> Vec<f32> {
+        let mut power: Vec<f32> = loads.to_vec();
+        for link in self.conductors.iter() {
+            let q: f32 = link.value * (temps[link.j] - temps[link.i]);
+            power[link.i] += q;
+            power[link.j] -= q;
+        }
+        for link in self.radiative.iter() {
+            let q: f32 = STEFAN_BOLTZMANN * link.value * (temps[link.j].powi(4) - temps[link.i].powi(4));
+            power[link.i] += q;
+            power[link.j] -= q;
+        }
+        power
+    }
+
+    /// Steady state temperatures, $R_i=0$ on every non boundary node,
+    /// by Newton iterations from the current temperatures.
+    ///
+    /// # Returns:
+    /// * `Result<Vec<f32>, NewtonError>` - Node temperatures (K), or
+    ///   the failure of the iterations.
+    pub fn steady_state(&self) -> Result<Vec<f32>, NewtonError> {
+        let loads: Vec<f32> = self.nodes.iter().map(|node| node.load).collect();
+        let temps: Vec<f32> = self.temperatures();
+        self.solve_step(&temps, &vec![0e0; temps.len()], &loads, 0e0, 1e0)
+    }
+
+    /// Temperatures over time with constant heat loads, from the
+    /// current temperatures.
+    ///
+    /// # Parameters:
+    /// * `dt` - Time step (s).
+    /// * `steps` - Number of steps.
+    /// * `scheme` - Time integration scheme.
+    ///
+    /// # Returns:
+    /// * `Result<Transient, NewtonError>` - Temperatures over time, or
+    ///   the failure of the iterations of the first step not solved.
+    pub fn transient(&self, dt: f32, steps: usize, scheme: TimeScheme) -> Result<Transient, NewtonError> {
+        let loads: Vec<f32> = self.nodes.iter().map(|node| node.load).collect();
+        self.transient_with_loads(dt, steps, scheme, |_| loads.clone())
+    }
+
+    /// Same as `transient`, with heat loads depending on time, e.g.
+    /// along an orbit (see `OrbitalHeatLoads`).
+    ///
+    /// # Parameters:
+    /// * `dt` - Time step (s).
+    /// * `steps` - Number of steps.
+    /// * `scheme` - Time integration scheme.
+    /// * `loads` - Heat loads of every node at a given time (W).
+    pub fn transient_with_loads<F: Fn(f32) -> Vec<f32>>(&self, dt: f32, steps: usize, scheme: TimeScheme, loads: F) -> Result<Transient, NewtonError> {
+        let theta: f32 = scheme.theta();
+        let mut temps: Vec<f32> = self.temperatures();
+        let mut result: Transient = Transient { times: vec![0e0], temperatures: vec![temps.clone()] };
+        for k in 0..steps {
+            let (t0, t1): (f32, f32) = (k as f32 * dt, (k + 1) as f32 * dt);
+            // Explicit part of the rates, weighted by 1 - theta.
+            let rates: Vec<f32> = self.net_power(&temps, &loads(t0));
+            let explicit: Vec<f32> = rates.iter().map(|r| (1e0 - theta) * r).collect();
+            temps = self.solve_step(&temps, &explicit, &loads(t1), 1e0 / dt, theta)?;
+            result.times.push(t1);
+            result.temperatures.push(temps.clone());
+        }
+        Ok(result)
+    }
+
+    /// Solves, by Newton iterations, the temperatures $T$ of the non
+    /// boundary nodes such that
+    /// $$\frac{C_i}{\Delta t}\left(T_i-T_i^0\right)=\theta R_i(T)+E_i$$
+    /// where $E$ are the ``explicit`` rates (the steady state being
+    /// $\frac{1}{\Delta t}=0$). Fails on a singular Jacobian or when
+    /// the change is still above `NEWTON_TOL` after `NEWTON_MAX_ITER`
+    /// iterations.
+    fn solve_step(&self, temps_0: &[f32], explicit: &[f32], loads: &[f32], inv_dt: f32, theta: f32) -> Result<Vec<f32>, NewtonError> {
+        let free: Vec<usize> = (0..self.nodes.len()).filter(|i| !self.nodes[*i].boundary).collect();
+        let mut index: Vec<Option<usize>> = vec![None; self.nodes.len()];
+        for (k, i) in free.iter().enumerate() {
+            index[*i] = Some(k);
+        }
+        let mut temps: Vec<f32> = temps_0.to_vec();
+        let mut change: f32 = 0e0;
+        for _ in 0..NEWTON_MAX_ITER {
+            let rates: Vec<f32> = self.net_power(&temps, loads);
+            let mut jacobian: DMatrix<f32> = DMatrix::from_element(free.len(), free.len(), 0e0);
+            let mut residual: DVector<f32> = DVector::from_element(free.len(), 0e0);
+            for (k, i) in free.iter().enumerate() {
+                let c: f32 = self.nodes[*i].capacitance * inv_dt;
+                residual[k] = c * (temps[*i] - temps_0[*i]) - theta * rates[*i] - explicit[*i];
+                jacobian[(k, k)] += c;
+            }
+            // Derivatives of -theta R.
+            let mut add = |a: usize, b: usize, dr_da: f32, dr_db: f32| {
+                if let Some(ka) = index[a] {
+                    jacobian[(ka, ka)] -= theta * dr_da;
+                    if let Some(kb) = index[b] {
+                        jacobian[(ka, kb)] -= theta * dr_db;
+                    }
+                }
+            };
+            for link in self.conductors.iter() {
+                add(link.i, link.j, -link.value, link.value);
+                add(link.j, link.i, -link.value, link.value);
+            }
+            for link in self.radiative.iter() {
+                let g_i: f32 = 4e0 * STEFAN_BOLTZMANN * link.value * temps[link.i].powi(3);
+                let g_j: f32 = 4e0 * STEFAN_BOLTZMANN * link.value * temps[link.j].powi(3);
+                add(link.i, link.j, -g_i, g_j);
+                add(link.j, link.i, -g_j, g_i);
+            }
+            let delta: DVector<f32> = match jacobian.lu().solve(&(-residual)) {
+                Some(delta) => delta,
+                None => return Err(NewtonError::Singular),
+            };
+            change = 0e0;
+            for (k, i) in free.iter().enumerate() {
+                // Damped, temperatures stay positive.
+                let step: f32 = delta[k].max(-5e-1 * temps[*i]);
+                temps[*i] += step;
+                change = change.max(step.abs());
+            }
+            if change < NEWTON_TOL {
+                return Ok(temps);
+            }
+        }
+        Err(NewtonError::NotConverged(change))
+    }
+}
+
+/// Radiative conductors from geometric view factors, assuming grey
+/// diffuse surfaces exchanging only directly:
+/// $GR_{ij}=\varepsilon_i\varepsilon_jA_iF_{ij}$ (use the Gebhart
+/// factors for multiple reflections). The diagonal holds the self
+/// exchange of concave forms, $GR_{ii}=\varepsilon_i^2A_iF_{ii}$, which
+/// does not reach space.
+///
+/// # Parameters:
+/// * `world` - Forms.
+/// * `vfs` - View factors of the forms (see `ViewFactors::view_factors`).
+/// * `optics` - Infrared properties of each form.
+///
+/// # Returns:
+/// * `Vec<Vec<f32>>` - Full, symmetric, conductor matrix (m²).
+pub fn view_factor_conductors(world: &HittableList, vfs: &Vfs, optics: &[SurfaceOptics]) -> Vec<Vec<f32>> {
+    let n: usize = world.forms.len();
+    let mut conductors: Vec<Vec<f32>> = vec![vec![0e0; n]; n];
+    for (i, row) in vfs.vfs.iter().enumerate() {
+        let f_ii: f32 = vfs.self_vfs.get(i).copied().unwrap_or(0e0);
+        conductors[i][i] = optics[i].emissivity.powi(2) * world.forms[i].area() * f_ii;
+        for (k, f_ij) in row.iter().enumerate() {
+            let j: usize = i + k + 1;
+            let gr: f32 = optics[i].emissivity * optics[j].emissivity * world.forms[i].area() * f_ij;
+            conductors[i][j] = gr;
+            conductors[j][i] = gr;
+        }
+    }
+    conductors
+}
@@ -7,6 +7,7 @@ use crate::materials::Material;
 use crate::objects::square::Square;
 use crate::objects::sphere::Sphere;
 use crate::objects::traits::{SurfaceFunctions, ObjectGetters};
+use crate::objects::motion::Motion;
 
 extern crate nalgebra;
 use nalgebra::{Matrix3, Vector3};
@@ -31,6 +32,8 @@ pub struct Cube{
     v: Vec3,
     /// Cube's Z-axis direction on world axes (note that $\vec w=\vec u\times\vec v$).
     w: Vec3,
+    /// Motion of the cube's center and frame.
+    motion: Motion,
 }
 
 /// Cube surface identifier.
@@ -51,11 +54,41 @@ impl Cube{
         let ang: f32 = random::<f32>() * 8e0 * 1_f32.atan();
         let u: Vec3 = Vec3::new(ang.cos(), 0e0, ang.sin());
         let v: Vec3 = w.cross(&u);
-        Cube {center, length, material, u, v, w}
+        Cube {center, length, material, u, v, w, motion: Motion::Static}
+    }
+
+    /// Moving cube constructor, same orientation as `Cube::new`.
+    ///
+    /// # Parameters:
+    /// * `center` - Cube center at rest.
+    /// * `length` - Cube edge size.
+    /// * `material` - Cube material.
+    /// * `motion` - Motion of the center and rotation of the frame about it.
+    pub fn moving(center: Vec3, length: f32, material: Material, motion: Motion) -> Cube {
+        Cube { motion, ..Cube::new(center, length, material) }
+    }
+
+    /// Cube center and $\vec u$, $\vec v$, $\vec w$ axes at ``time``.
+    pub fn frame_at(&self, time: f32) -> (Vec3, Vec3, Vec3, Vec3) {
+        match self.motion.is_static() {
+            true => (self.center, self.u, self.v, self.w),
+            false => (
+                self.center + self.motion.offset(time),
+                self.motion.rotate(self.u, time),
+                self.motion.rotate(self.v, time),
+                self.motion.rotate(self.w, time)
+            ),
+        }
+    }
+
+    /// Static copy of the cube at ``time``.
+    pub fn snapshot(&self, time: f32) -> Cube {
+        let (center, u, v, w) = self.frame_at(time);
+        Cube {center, length: self.length, material: self.material.clone(), u, v, w, motion: Motion::Static}
     }
 
     pub fn normal(surface_identifier: &CubeSurface) -> Vec3 {
-        return match surface_identifier {
+        match surface_identifier {
             CubeSurface::XN => Vec3::new(-1e0,  0e0,  0e0),
             CubeSurface::XP => Vec3::new( 1e0,  0e0,  0e0),
             CubeSurface::YN => Vec3::new( 0e0, -1e0,  0e0),
@@ -69,7 +102,7 @@ impl Cube{
         let w: Vec3 = Cube::normal(surface_identifier);
         let center: Vec3 = self.center + self.length / 2e0 * w;
         let length: f32 = self.length;
-        let material: Material = self.material;
+        let material: Material = self.material.clone();
         let u: Vec3;
         let v: Vec3;
         match surface_identifier {
@@ -99,14 +132,14 @@ impl Cube{
             },
         }
 
-        return Square::new(
+        Square::new(
             center,
             length,
             material,
             u,
             v,
             w
-        );
+        )
     }
 
     /// Cube's surface point from input adimensional parameters
@@ -116,7 +149,7 @@ impl Cube{
     /// * `t` - Second adimensional parameter from 0 to 1.
     /// * `sid` - Cube's surface identifier (X, Y or Z, plus or minus surface).
     pub fn point(&self, s: f32, t: f32, sid: &CubeSurface) -> Vec3 {
-        return self.get_square(sid).point(s, t);
+        self.get_square(sid).point(s, t)
         // let surf_center: Vec3 = self.center + self.length / 2e0 * self::normal(sid);
         // let point_on_surf_s: Vec3 = self.length * self::normal((sid + 2) % 6) * (s - 5e-1);
         // let point_on_surf_t: Vec3 = self.length * self::normal((sid + 4) % 6) * (t - 5e-1);
@@ -126,7 +159,7 @@ impl Cube{
 }
 
 impl ObjectGetters for Cube {
-    fn get_material(&self) -> Material { self.material }
+    fn get_material(&self) -> Material { self.material.clone() }
 
     fn get_center(&self) -> Vec3 { self.center }
 }
@@ -135,7 +168,8 @@ impl Hittable for Cube{
     fn hit(&self, ray: &Ray, t_min: f32, t_max: f32, rec: &mut Option<HitRecord>) -> bool{
         let mut do_hit: bool = false;
         let mut t_closest: f32 = t_max;
-        let sphere: Sphere = Sphere::new(self.center, self.length * 3e0f32.sqrt() / 2e0, self.material);
+        let (center, u, v, w) = self.frame_at(ray.time);
+        let sphere: Sphere = Sphere::new(center, self.length * 3e0f32.sqrt() / 2e0, self.material.clone());
         if sphere.hit(ray, t_min, t_max, rec) {
             let signs = [-1e0f32, 1e0f32];
             for i in 0..3 {
@@ -147,19 +181,19 @@ impl Hittable for Cube{
                     let minus_b: V3 = -V3::new(ray.direction().x(), ray.direction().y(), ray.direction().z());
                     match i {
                         0 => {
-                            ei = V3::new(self.u.e[0], self.u.e[1], self.u.e[2]);
-                            ej = V3::new(self.v.e[0], self.v.e[1], self.v.e[2]);
-                            ek = V3::new(self.w.e[0], self.w.e[1], self.w.e[2]);
+                            ei = V3::new(u.e[0], u.e[1], u.e[2]);
+                            ej = V3::new(v.e[0], v.e[1], v.e[2]);
+                            ek = V3::new(w.e[0], w.e[1], w.e[2]);
                         },
                         1 => {
-                            ek = V3::new(self.u.e[0], self.u.e[1], self.u.e[2]);
-                            ei = V3::new(self.v.e[0], self.v.e[1], self.v.e[2]);
-                            ej = V3::new(self.w.e[0], self.w.e[1], self.w.e[2]);
+                            ek = V3::new(u.e[0], u.e[1], u.e[2]);
+                            ei = V3::new(v.e[0], v.e[1], v.e[2]);
+                            ej = V3::new(w.e[0], w.e[1], w.e[2]);
                         },
                         2 => {
-                            ej = V3::new(self.u.e[0], self.u.e[1], self.u.e[2]);
-                            ek = V3::new(self.v.e[0], self.v.e[1], self.v.e[2]);
-                            ei = V3::new(self.w.e[0], self.w.e[1], self.w.e[2]);
+                            ej = V3::new(u.e[0], u.e[1], u.e[2]);
+                            ek = V3::new(v.e[0], v.e[1], v.e[2]);
+                            ei = V3::new(w.e[0], w.e[1], w.e[2]);
                         },
                         _ => {}
                     }
@@ -172,7 +206,7 @@ impl Hittable for Cube{
 
                     if det.abs() > 1e-4 {
                         let mut k: V3 = V3::new(ray.origin().x(), ray.origin().y(), ray.origin().z());
-                        k -= V3::new(self.center.x(), self.center.y(), self.center.z());
+                        k -= V3::new(center.x(), center.y(), center.z());
                         k -= ei.scale(self.length / 2e0);
                         let mut x: V3 = V3::zeros();
                         m.pseudo_inverse(1e-5).unwrap().mul_to(&k, &mut x);
@@ -186,7 +220,7 @@ impl Hittable for Cube{
                                 t: t_closest,
                                 p: ray.point_at_parameter(t_closest),
                                 normal: Vec3::new(ei[0], ei[1], ei[2]),
-                                material: self.material,
+                                material: self.material.clone(),
                                 hit_elem: 0
                             };
                             *rec = Some(rec2);
@@ -195,7 +229,7 @@ impl Hittable for Cube{
                 }
             }
         }
-        return do_hit;
+        do_hit
     }
 }
 
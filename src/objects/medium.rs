@@ -0,0 +1,153 @@
+//! Constant-density participating medium (fog, smoke, absorbing gas).
+
+use crate::vectors::{Vec3, Vec3Methods};
+use crate::rays::Ray;
+use crate::hittable::{HitRecord, Hittable};
+use crate::materials::{Material, IsotropicKind, HenyeyGreensteinKind};
+use crate::objects::Form;
+use crate::objects::traits::{SurfaceFunctions, ObjectGetters};
+
+extern crate rand;
+use self::rand::random;
+
+
+/// Constant density medium structure.
+/// The medium fills the volume enclosed by a closed ``boundary``
+/// form (such as a `Sphere` or a `Cube`). Rays crossing the medium
+/// travel a random free-flight distance before scattering, so that
+/// the probability of crossing a length $L$ unscattered is
+/// $$T=e^{-\sigma L}$$
+pub struct ConstantMedium {
+    /// Closed form enclosing the medium.
+    boundary: Box<Form>,
+    /// Extinction coefficient $\sigma$ (inverse of the mean free path).
+    density: f32,
+    /// Phase function material (isotropic or Henyey-Greenstein).
+    material: Material,
+}
+
+/// ConstantMedium function members.
+impl ConstantMedium {
+    /// ConstantMedium constructor.
+    ///
+    /// # Parameters:
+    ///
+    /// * `boundary` - Closed form enclosing the medium.
+    /// * `density` - Extinction coefficient (1 / length).
+    /// * `material` - Phase function material.
+    pub fn new(boundary: Form, density: f32, material: Material) -> ConstantMedium {
+        ConstantMedium { boundary: Box::new(boundary), density, material }
+    }
+
+    /// Medium with an isotropic phase function.
+    pub fn isotropic(boundary: Form, density: f32, albedo: Vec3) -> ConstantMedium {
+        ConstantMedium::new(boundary, density, Material::Isotropic(IsotropicKind::new(albedo)))
+    }
+
+    /// Medium with a Henyey-Greenstein phase function of asymmetry
+    /// parameter ``g`` (-1 backward, 0 isotropic, 1 forward scattering).
+    pub fn henyey_greenstein(boundary: Form, density: f32, albedo: Vec3, g: f32) -> ConstantMedium {
+        ConstantMedium::new(
+            boundary,
+            density,
+            Material::HenyeyGreenstein(HenyeyGreensteinKind::new(albedo, g))
+        )
+    }
+
+    /// Static copy of the medium at ``time``.
+    pub fn snapshot(&self, time: f32) -> ConstantMedium {
+        ConstantMedium::new(self.boundary.snapshot(time), self.density, self.material.clone())
+    }
+
+    /// Getter for the extinction coefficient.
+    pub fn density(&self) -> f32 { self.density }
+
+    /// Getter for the boundary form.
+    pub fn boundary(&self) -> &Form { &self.boundary }
+
+    /// Ray parameters at which the ray enters and leaves the medium,
+    /// clipped to the $[t_{min}, t_{max}]$ interval.
+    ///
+    /// # Returns:
+    ///
+    /// * `Option<(f32, f32)>` - Entry and exit parameters, if the ray crosses the medium.
+    pub fn interval(&self, ray: &Ray, t_min: f32, t_max: f32) -> Option<(f32, f32)> {
+        let mut rec_1: Option<HitRecord> = None;
+        let mut rec_2: Option<HitRecord> = None;
+
+        // Boundary crossings along the whole ray line (the origin may be inside).
+        if !self.boundary.hit(ray, -f32::MAX, f32::MAX, &mut rec_1) {
+            return None;
+        }
+        let t_1: f32 = rec_1.unwrap().t;
+        if !self.boundary.hit(ray, t_1 + 1e-4, f32::MAX, &mut rec_2) {
+            return None;
+        }
+        let t_2: f32 = rec_2.unwrap().t;
+
+        let t_in: f32 = t_1.max(t_min);
+        let t_out: f32 = t_2.min(t_max);
+        match t_in < t_out {
+            true => Some((t_in, t_out)),
+            false => None,
+        }
+    }
+
+    /// Transmittance along the ray between $t_{min}$ and $t_{max}$
+    /// (Beer-Lambert law).
+    pub fn transmittance(&self, ray: &Ray, t_min: f32, t_max: f32) -> f32 {
+        match self.interval(ray, t_min, t_max) {
+            Some((t_in, t_out)) => (-self.density * (t_out - t_in) * ray.direction().length()).exp(),
+            None => 1e0,
+        }
+    }
+}
+
+impl SurfaceFunctions for ConstantMedium {
+    fn point(&self, s: f32, t: f32) -> Vec3 { self.boundary.point(s, t) }
+
+    fn normal(&self, s: f32, t: f32) -> Vec3 { self.boundary.normal(s, t) }
+
+    fn area(&self) -> f32 { self.boundary.area() }
+
+    fn diff_a(&self, s: f32, t: f32) -> f32 { self.boundary.diff_a(s, t) }
+
+    fn parameters(&self, p: Vec3) -> (f32, f32) { self.boundary.parameters(p) }
+}
+
+impl ObjectGetters for ConstantMedium {
+    fn get_material(&self) -> Material { self.material.clone() }
+
+    fn get_center(&self) -> Vec3 { self.boundary.get_center() }
+}
+
+/// Hittable trait on ConstantMedium. The ray "hits" the medium
+/// at the sampled free-flight distance, if it lies inside the boundary.
+impl Hittable for ConstantMedium {
+    fn hit(&self, ray: &Ray, t_min: f32, t_max: f32, rec: &mut Option<HitRecord>) -> bool {
+        let (t_in, t_out) = match self.interval(ray, t_min, t_max) {
+            Some(interval) => interval,
+            None => return false,
+        };
+
+        let ray_length: f32 = ray.direction().length();
+        let distance_inside: f32 = (t_out - t_in) * ray_length;
+        let hit_distance: f32 = -(1e0 - random::<f32>()).ln() / self.density;
+
+        // Ray leaves the medium unscattered.
+        if hit_distance > distance_inside {
+            return false;
+        }
+
+        let t: f32 = t_in + hit_distance / ray_length;
+        *rec = Some(HitRecord {
+            t,
+            p: ray.point_at_parameter(t),
+            // Arbitrary, phase functions do not use the normal.
+            normal: -ray.direction().unit_vector(),
+            material: self.material.clone(),
+            hit_elem: 0
+        });
+        true
+    }
+}
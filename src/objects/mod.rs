@@ -5,9 +5,11 @@ mod cube;
 mod square;
 mod rectangle;
 mod sphere;
+mod medium;
+mod motion;
 mod traits;
 
-use crate::vectors::{Vec3};
+use crate::vectors::{Vec3, Vec3Methods};
 use crate::rays::Ray;
 use crate::hittable::{HitRecord, Hittable};
 use crate::materials::Material;
@@ -15,8 +17,11 @@ pub use crate::objects::cube::Cube;
 pub use crate::objects::square::Square;
 pub use crate::objects::sphere::Sphere;
 pub use rectangle::Rectangle;
+pub use medium::ConstantMedium;
+pub use motion::{Motion, Keyframe};
 pub use crate::objects::traits::{ObjectGetters, SurfaceFunctions};
 use std::borrow::{Borrow};
+use std::collections::BTreeMap;
 
 extern crate nalgebra;
 extern crate rand;
@@ -28,93 +33,261 @@ pub enum Form{
     Cube(Cube),
     Square(Square),
     Rectangle(Rectangle),
+    Medium(ConstantMedium),
 }
 
 /// List of available hittable objects on the world.
 pub struct HittableList{
     /// Object vector.
-    pub forms: Vec<Form>
+    pub forms: Vec<Form>,
+    /// Names given to forms, by world index (see `HittableList::name`).
+    /// Forms pushed straight onto ``forms`` are unnamed.
+    names: BTreeMap<usize, String>
 }
 
 /// Object function members.
 impl Form{
     /// Getter for material data.
     pub fn material(&self) -> Material {
-        return self.get_material();
+        self.get_material()
+    }
+
+    /// Static copy of the form, at its position and orientation at ``time``.
+    pub fn snapshot(&self, time: f32) -> Form {
+        match self {
+            Form::Sphere(sphere) => Form::Sphere(sphere.snapshot(time)),
+            Form::Cube(cube) => Form::Cube(cube.snapshot(time)),
+            Form::Square(square) => Form::Square(square.clone()),
+            Form::Rectangle(rec) => Form::Rectangle(rec.clone()),
+            Form::Medium(medium) => Form::Medium(medium.snapshot(time)),
+        }
+    }
+
+    /// Whether the form is concave, so that it sees itself ($F_{ii}>0$).
+    /// Only hollow spheres are; flat and convex forms have $F_{ii}=0$.
+    pub fn sees_itself(&self) -> bool {
+        match self {
+            Form::Sphere(sphere) => sphere.is_hollow(),
+            _ => false,
+        }
+    }
+
+    /// Outward (front side) normal of the form at its surface point
+    /// ``p``, at rest pose. Flat forms face along their normal $\vec w$,
+    /// hollow spheres face inward; participating media have no surface
+    /// (zero vector).
+    pub fn outward_normal(&self, p: Vec3) -> Vec3 {
+        match self {
+            Form::Sphere(sphere) => match sphere.is_hollow() {
+                true => (sphere.center_at(0e0) - p).unit_vector(),
+                false => (p - sphere.center_at(0e0)).unit_vector(),
+            },
+            Form::Cube(cube) => {
+                let (center, u, v, w) = cube.frame_at(0e0);
+                let r: Vec3 = p - center;
+                let (ru, rv, rw) = (r.dot(&u), r.dot(&v), r.dot(&w));
+                match (ru.abs() >= rv.abs(), ru.abs() >= rw.abs(), rv.abs() >= rw.abs()) {
+                    (true, true, _) => u * ru.signum(),
+                    (false, _, true) => v * rv.signum(),
+                    _ => w * rw.signum(),
+                }
+            }
+            Form::Square(square) => square.normal(0e0, 0e0),
+            Form::Rectangle(rec) => rec.normal(0e0, 0e0),
+            Form::Medium(_) => Vec3::zeros(),
+        }
     }
 }
 
 impl ObjectGetters for Form {
     fn get_material(&self) -> Material {
-        return match self {
+        match self {
             Form::Sphere(sphere) => sphere.get_material(),
             Form::Cube(cube) => cube.get_material(),
             Form::Square(square) => square.get_material(),
             Form::Rectangle(rec) => rec.get_material(),
+            Form::Medium(medium) => medium.get_material(),
         }
     }
 
     fn get_center(&self) -> Vec3 {
-        return match self {
+        match self {
             Form::Sphere(sphere) => sphere.get_center(),
             Form::Cube(cube) => cube.get_center(),
             Form::Square(square) => square.get_center(),
             Form::Rectangle(rec) => rec.get_center(),
+            Form::Medium(medium) => medium.get_center(),
         }
     }
 }
 
 impl SurfaceFunctions for Form {
     fn point(&self, s: f32, t: f32) -> Vec3 {
-        return match self {
+        match self {
             Form::Rectangle(rec) => rec.point(s, t),
             Form::Square(sq) => sq.point(s, t),
             Form::Sphere(sp) => sp.point(s, t),
+            Form::Medium(medium) => medium.point(s, t),
             Form::Cube(_c) => Vec3::zeros(),
         }
     }
 
     fn normal(&self, s: f32, t: f32) -> Vec3 {
-        return match self {
+        match self {
             Form::Rectangle(rec) => rec.normal(s, t),
             Form::Square(sq) => sq.normal(s, t),
             Form::Sphere(sp) => sp.normal(s, t),
+            Form::Medium(medium) => medium.normal(s, t),
             Form::Cube(_c) => Vec3::zeros(),
         }
     }
 
     fn area(&self) -> f32 {
-        return match self {
+        match self {
             Form::Rectangle(rec) => rec.area(),
             Form::Square(sq) => sq.area(),
             Form::Sphere(sp) => sp.area(),
+            Form::Medium(medium) => medium.area(),
             Form::Cube(_c) => 0e0,
         }
     }
 
     fn diff_a(&self, s: f32, t: f32) -> f32 {
-        return match self {
+        match self {
             Form::Rectangle(rec) => rec.diff_a(s, t),
             Form::Square(sq) => sq.diff_a(s, t),
             Form::Sphere(sp) => sp.diff_a(s, t),
+            Form::Medium(medium) => medium.diff_a(s, t),
             Form::Cube(_c) => 0e0,
         }
     }
+
+    fn parameters(&self, p: Vec3) -> (f32, f32) {
+        match self {
+            Form::Rectangle(rec) => rec.parameters(p),
+            Form::Square(sq) => sq.parameters(p),
+            Form::Sphere(sp) => sp.parameters(p),
+            Form::Medium(medium) => medium.parameters(p),
+            Form::Cube(_c) => (0e0, 0e0),
+        }
+    }
+}
+
+impl Default for HittableList {
+    fn default() -> HittableList {
+        HittableList::new()
+    }
 }
 
 /// HittableList function members.
 impl HittableList{
     /// Constructor (empty vector).
-    pub fn new() -> HittableList { HittableList {forms: Vec::new()} }
+    pub fn new() -> HittableList { HittableList {forms: Vec::new(), names: BTreeMap::new()} }
+
+    /// Adds a named form to the world.
+    pub fn push_named(&mut self, form: Form, name: &str) {
+        self.forms.push(form);
+        self.set_name(self.forms.len() - 1, name);
+    }
+
+    /// Removes and returns the form of index ``idx`` with its name,
+    /// shifting the following forms and their names (removing it
+    /// straight from ``forms`` would leave the names behind).
+    ///
+    /// # Panics:
+    /// If ``idx`` is out of bounds.
+    pub fn remove(&mut self, idx: usize) -> (Form, Option<String>) {
+        let form: Form = self.forms.remove(idx);
+        let name: Option<String> = self.names.remove(&idx);
+        let moved: BTreeMap<usize, String> = self.names.split_off(&idx);
+        self.names.extend(moved.into_iter().map(|(k, name)| (k - 1, name)));
+        (form, name)
+    }
+
+    /// Names the form of index ``idx``, e.g. after the thermal node it
+    /// stands for.
+    pub fn set_name(&mut self, idx: usize, name: &str) {
+        self.names.insert(idx, name.to_string());
+    }
+
+    /// Name of the form of index ``idx``. Unnamed forms are called after
+    /// their type and index, e.g. ``sphere_3``.
+    pub fn name(&self, idx: usize) -> String {
+        match self.names.get(&idx) {
+            Some(name) => name.clone(),
+            None => {
+                let kind: &str = match self.forms.get(idx) {
+                    Some(Form::Sphere(_)) => "sphere",
+                    Some(Form::Cube(_)) => "cube",
+                    Some(Form::Square(_)) => "square",
+                    Some(Form::Rectangle(_)) => "rectangle",
+                    Some(Form::Medium(_)) => "medium",
+                    None => "form",
+                };
+                format!("{}_{}", kind, idx)
+            }
+        }
+    }
+
+    /// Names of every form on the world.
+    pub fn names(&self) -> Vec<String> {
+        (0..self.forms.len()).map(|idx| self.name(idx)).collect()
+    }
+
+    /// Static copy of the world at ``time``, e.g. for computing the
+    /// view factors of a moving assembly at a given instant.
+    pub fn snapshot(&self, time: f32) -> HittableList {
+        HittableList { forms: self.forms.iter().map(|form| form.snapshot(time)).collect(), names: self.names.clone() }
+    }
+
+    /// Nearest hit against the world surfaces only, participating
+    /// media are ignored (see `HittableList::transmittance`).
+    pub fn hit_surfaces(&self, ray: &Ray, t_min: f32, t_max: f32, rec: &mut Option<HitRecord>) -> bool {
+        let mut temp_rec: Option<HitRecord> = None;
+        let mut hit_any: bool = false;
+        let mut t_closest: f32 = t_max;
+        for (idx, form) in self.forms.iter().enumerate() {
+            if let Form::Medium(_) = form {
+                continue;
+            }
+            if form.hit(ray, t_min, t_closest, &mut temp_rec) {
+                let rr: &HitRecord = temp_rec.borrow().as_ref().unwrap();
+                hit_any = true;
+                t_closest = rr.t;
+                *rec = Some(HitRecord {
+                    t: t_closest,
+                    p: rr.p,
+                    normal: rr.normal,
+                    material: form.material(),
+                    hit_elem: idx
+                } );
+            }
+        }
+        hit_any
+    }
+
+    /// Fraction of the radiation transmitted along the ray between
+    /// $t_{min}$ and $t_{max}$ through every participating medium
+    /// on the world.
+    pub fn transmittance(&self, ray: &Ray, t_min: f32, t_max: f32) -> f32 {
+        let mut tau: f32 = 1e0;
+        for form in self.forms.iter() {
+            if let Form::Medium(medium) = form {
+                tau *= medium.transmittance(ray, t_min, t_max);
+            }
+        }
+        tau
+    }
 }
 
 impl Hittable for Form {
     fn hit(&self, ray: &Ray, t_min: f32, t_max: f32, rec: &mut Option<HitRecord>) -> bool{
-        return match self {
+        match self {
             Form::Sphere(sphere) => sphere.hit(ray, t_min, t_max, rec),
             Form::Cube(cube) => cube.hit(ray, t_min, t_max, rec),
             Form::Square(square) => square.hit(ray, t_min, t_max, rec),
             Form::Rectangle(rectangle) => rectangle.hit(ray, t_min, t_max, rec),
+            Form::Medium(medium) => medium.hit(ray, t_min, t_max, rec),
         }
     }
 }
@@ -144,6 +317,6 @@ impl Hittable for HittableList {
                 //rec.normal = temp_rec.normal;
             }
         }
-        return hit_any;
+        hit_any
     }
 }
@@ -0,0 +1,106 @@
+//! Rigid body motion of the forms over time.
+
+use crate::vectors::{Vec3, Vec3Methods};
+
+/// Pose of a form at a given time.
+#[derive(Copy, Clone)]
+pub struct Keyframe {
+    /// Keyframe time.
+    pub time: f32,
+    /// Displacement from the form's rest position.
+    pub offset: Vec3,
+    /// Rotation about the form's center, as a rotation vector
+    /// (axis times angle in radians).
+    pub rotation: Vec3,
+}
+
+/// Rigid body motion: displacement of the form's center plus
+/// a rotation about it, both functions of time.
+pub enum Motion {
+    /// Form at rest.
+    Static,
+    /// Constant linear and angular velocities:
+    /// $$\vec d(t)=\vec v t,\quad\vec\theta(t)=\vec\omega t$$
+    Linear { velocity: Vec3, angular_velocity: Vec3 },
+    /// Linear interpolation between keyframes sorted by time,
+    /// the pose is held before the first and after the last one.
+    Keyframed(Vec<Keyframe>),
+}
+
+impl Keyframe {
+    /// Keyframe constructor.
+    ///
+    /// # Parameters:
+    /// * `time` - Keyframe time.
+    /// * `offset` - Displacement from the rest position.
+    /// * `rotation` - Rotation vector (rad).
+    pub fn new(time: f32, offset: Vec3, rotation: Vec3) -> Keyframe {
+        Keyframe { time, offset, rotation }
+    }
+}
+
+impl Motion {
+    /// Translation at constant velocity.
+    pub fn linear(velocity: Vec3) -> Motion {
+        Motion::Linear { velocity, angular_velocity: Vec3::zeros() }
+    }
+
+    /// Rotation at constant angular velocity (rad per time unit).
+    pub fn spinning(angular_velocity: Vec3) -> Motion {
+        Motion::Linear { velocity: Vec3::zeros(), angular_velocity }
+    }
+
+    /// Whether the form moves at all.
+    pub fn is_static(&self) -> bool {
+        matches!(self, Motion::Static)
+    }
+
+    /// Interpolated pose at ``time``.
+    pub fn pose(&self, time: f32) -> Keyframe {
+        match self {
+            Motion::Static => Keyframe::new(time, Vec3::zeros(), Vec3::zeros()),
+            Motion::Linear { velocity, angular_velocity } => {
+                Keyframe::new(time, *velocity * time, *angular_velocity * time)
+            }
+            Motion::Keyframed(keys) => {
+                if keys.is_empty() {
+                    return Keyframe::new(time, Vec3::zeros(), Vec3::zeros());
+                }
+                if time <= keys[0].time {
+                    return Keyframe::new(time, keys[0].offset, keys[0].rotation);
+                }
+                for pair in keys.windows(2) {
+                    let (k0, k1) = (pair[0], pair[1]);
+                    if time <= k1.time {
+                        let f: f32 = (time - k0.time) / (k1.time - k0.time).max(1e-9);
+                        return Keyframe::new(
+                            time,
+                            k0.offset + (k1.offset - k0.offset) * f,
+                            k0.rotation + (k1.rotation - k0.rotation) * f
+                        );
+                    }
+                }
+                let last: Keyframe = keys[keys.len() - 1];
+                Keyframe::new(time, last.offset, last.rotation)
+            }
+        }
+    }
+
+    /// Displacement of the form's center at ``time``.
+    pub fn offset(&self, time: f32) -> Vec3 {
+        self.pose(time).offset
+    }
+
+    /// Vector ``v`` rotated by the form's rotation at ``time``
+    /// (Rodrigues' rotation formula):
+    /// $$\vec v'=\vec v\cos\theta+(\vec k\times\vec v)\sin\theta+\vec k(\vec k\cdot\vec v)(1-\cos\theta)$$
+    pub fn rotate(&self, v: Vec3, time: f32) -> Vec3 {
+        let rotation: Vec3 = self.pose(time).rotation;
+        let theta: f32 = rotation.length();
+        if theta < 1e-9 {
+            return v;
+        }
+        let k: Vec3 = rotation / theta;
+        v * theta.cos() + k.cross(&v) * theta.sin() + k * (k.dot(&v) * (1e0 - theta.cos()))
+    }
+}
@@ -7,35 +7,72 @@ use crate::rays::Ray;
 use crate::hittable::{HitRecord, Hittable};
 use crate::materials::Material;
 use crate::objects::traits::{SurfaceFunctions, ObjectGetters};
+use crate::objects::motion::Motion;
 
 
 /// Sphere structure.
 pub struct Sphere{
     /// Sphere center.
     center: Vec3,
-    /// Sphere radius.
+    /// Sphere radius, negative if hollow (facing inward).
     radius: f32,
     /// Sphere material.
     material: Material,
+    /// Motion of the center.
+    motion: Motion,
 }
 
 /// Sphere function members.
 impl Sphere{
-    /// Sphere constructor.
-    pub fn new(center: Vec3, radius: f32, material: Material) -> Sphere { Sphere {center, radius, material} }
+    /// Sphere constructor. A negative radius makes it hollow, see `Sphere::hollow`.
+    pub fn new(center: Vec3, radius: f32, material: Material) -> Sphere { Sphere::moving(center, radius, material, Motion::Static) }
+
+    /// Hollow sphere constructor: the surface faces inward, e.g. an
+    /// enclosure seen from inside, which sees itself ($F_{ii}>0$).
+    ///
+    /// # Parameters:
+    /// * `center` - Sphere center.
+    /// * `radius` - Sphere radius.
+    /// * `material` - Sphere material.
+    pub fn hollow(center: Vec3, radius: f32, material: Material) -> Sphere { Sphere::new(center, -radius.abs(), material) }
+
+    /// Whether the sphere is hollow, its surface facing inward.
+    pub fn is_hollow(&self) -> bool { self.radius < 0e0 }
+
+    /// Sphere radius.
+    pub fn radius(&self) -> f32 { self.radius.abs() }
+
+    /// Moving sphere constructor.
+    ///
+    /// # Parameters:
+    /// * `center` - Sphere center at rest.
+    /// * `radius` - Sphere radius.
+    /// * `material` - Sphere material.
+    /// * `motion` - Motion of the center (rotations are ignored).
+    pub fn moving(center: Vec3, radius: f32, material: Material, motion: Motion) -> Sphere {
+        Sphere {center, radius, material, motion}
+    }
+
+    /// Sphere center at ``time``.
+    pub fn center_at(&self, time: f32) -> Vec3 { self.center + self.motion.offset(time) }
+
+    /// Static copy of the sphere at ``time``.
+    pub fn snapshot(&self, time: f32) -> Sphere { Sphere::new(self.center_at(time), self.radius, self.material.clone()) }
 }
 
 impl SurfaceFunctions for Sphere {
     /// Point on sphere at longitude $\lambda$ and latitude $\phi$:
     /// $$\vec P(\vec C, r, \lambda,\phi)=\vec C + R_{Z}(\lambda)\cdot R_{Y}(-\phi)\cdot\left\\{r,0,0\right\\}$$
+    /// taken at the rest pose $t=0$ for moving spheres.
     fn point(&self, s: f32, t: f32) -> Vec3{
-        self.center + self.radius * self.normal(s, t)
+        self.center_at(0e0) + self.radius * self.normal(s, t)
     }
 
     /// Surface normal at surface point defined by the adimensional parameters
     /// $s$ and $t$, so that:
     /// $$\lambda=2\pi s$$
     /// $$\phi=\pi\left(t-\frac{1}{2}\right)$$
+    /// pointing inward on hollow spheres.
     ///
     /// # Parameters:
     /// * `s` - Longitude parameter from 0 to 1.
@@ -46,21 +83,32 @@ impl SurfaceFunctions for Sphere {
     fn normal(&self, s: f32, t:f32) -> Vec3 {
         let lambda: f32 = 2e0 * PI * s;
         let phi: f32 = PI * (t - 5e-1);
-        return Vec3::new(lambda.cos()*phi.cos(), lambda.sin()*phi.cos(), phi.sin());
+        Vec3::new(lambda.cos()*phi.cos(), lambda.sin()*phi.cos(), phi.sin()) * self.radius.signum()
     }
 
     fn area(&self) -> f32 {
-        return 4e0 * PI * self.radius.powi(2);
+        4e0 * PI * self.radius.powi(2)
     }
 
     fn diff_a(&self, _s: f32, t: f32) -> f32 {
         let phi: f32 = PI * (t - 5e-1);
-        return self.radius.powi(2) * phi.cos() * 2e0 * PI.powi(2);
+        self.radius.powi(2) * phi.cos() * 2e0 * PI.powi(2)
+    }
+
+    /// Longitude and latitude of the point, $s=\frac{\lambda}{2\pi}$
+    /// wrapped to $[0, 1)$ and $t=\frac{\phi}{\pi}+\frac{1}{2}$,
+    /// relative to the center at rest ($t=0$).
+    fn parameters(&self, p: Vec3) -> (f32, f32) {
+        let r: Vec3 = (p - self.center_at(0e0)).unit_vector();
+        let lambda: f32 = r.y().atan2(r.x());
+        let phi: f32 = r.z().clamp(-1e0, 1e0).asin();
+        let s: f32 = (lambda / (2e0 * PI)).rem_euclid(1e0);
+        (s, phi / PI + 5e-1)
     }
 }
 
 impl ObjectGetters for Sphere {
-    fn get_material(&self) -> Material { self.material }
+    fn get_material(&self) -> Material { self.material.clone() }
 
     fn get_center(&self) -> Vec3 { self.center }
 }
@@ -68,8 +116,9 @@ impl ObjectGetters for Sphere {
 /// Hittable trait on sphere.
 impl Hittable for Sphere{
     fn hit(&self, ray: &Ray, t_min: f32, t_max: f32, rec: &mut Option<HitRecord>) -> bool{
-        // Ray relative to sphere center.
-        let r: Vec3 = ray.origin() - self.center;
+        // Ray relative to sphere center (at the ray's time).
+        let center: Vec3 = self.center_at(ray.time);
+        let r: Vec3 = ray.origin() - center;
         let a: f32 = ray.direction().square_length();
         let b: f32 = 2e0 * r.dot(&ray.direction());
         let c: f32 = r.square_length() - self.radius.powi(2);
@@ -101,15 +150,15 @@ impl Hittable for Sphere{
                 let rec2 = HitRecord{
                     t: t_op.unwrap(),
                     p: ray.point_at_parameter(t_op.unwrap()),
-                    normal: (ray.point_at_parameter(t_op.unwrap()) - self.center) / self.radius,
-                    material: self.material,
+                    normal: (ray.point_at_parameter(t_op.unwrap()) - center) / self.radius,
+                    material: self.material.clone(),
                     hit_elem: 0
                 };
                 *rec = Some(rec2);
             }
             return ans;
         }
-        return false;
+        false
     }
 }
 
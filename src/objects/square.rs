@@ -17,6 +17,7 @@ type M3 = Matrix3<f32>;
 type V3 = Vector3<f32>;
 
 /// Square structure.
+#[derive(Clone)]
 pub struct Square {
     /// Square center.
     center: Vec3,
@@ -59,24 +60,29 @@ impl SurfaceFunctions for Square {
         let surf_center: Vec3 = self.center;
         let point_on_surf_s: Vec3 = self.length * (s - 5e-1) * self.u;
         let point_on_surf_t: Vec3 = self.length * (t - 5e-1) * self.v;
-        return surf_center + point_on_surf_s + point_on_surf_t;
+        surf_center + point_on_surf_s + point_on_surf_t
     }
 
     fn normal(&self, _s: f32, _t: f32) -> Vec3 {
-        return self.w;
+        self.w
     }
 
     fn area(&self) -> f32 {
-        return self.length.powi(2);
+        self.length.powi(2)
     }
 
     fn diff_a(&self, _s: f32, _t: f32) -> f32 {
-        return self.area();
+        self.area()
+    }
+
+    fn parameters(&self, p: Vec3) -> (f32, f32) {
+        let r: Vec3 = p - self.center;
+        (r.dot(&self.u) / self.length + 5e-1, r.dot(&self.v) / self.length + 5e-1)
     }
 }
 
 impl ObjectGetters for Square {
-    fn get_material(&self) -> Material { self.material }
+    fn get_material(&self) -> Material { self.material.clone() }
 
     fn get_center(&self) -> Vec3 { self.center }
 }
@@ -136,11 +142,11 @@ impl Hittable for Square {
             t,
             p,
             normal: -n * n.dot(&ray.direction()) / (n.dot(&ray.direction())).abs(),
-            material: self.material,
+            material: self.material.clone(),
             hit_elem: 0
         };
         *rec = Some(rec2);
 
-        return true;
+        true
     }
 }
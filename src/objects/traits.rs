@@ -22,4 +22,7 @@ pub trait SurfaceFunctions {
     ///
     /// $$\text{d}A=\frac{\text{diff\\_a}(s, t)}{N_sN_t}$$
     fn diff_a(&self, s: f32, t: f32) -> f32;
+    /// Adimensional parameters $(s, t)$ of a surface point, inverse
+    /// of `point`. Points off the surface are projected onto it.
+    fn parameters(&self, p: Vec3) -> (f32, f32);
 }
@@ -0,0 +1,336 @@
+//! Orbital heat loads module. Absorbed solar, Earth albedo and Earth
+//! infrared power on each form of a spacecraft, the world being the
+//! spacecraft in its body frame (meters). Parts shadowing each other
+//! are accounted for by tracing the rays through the world.
+
+use std::f32::consts::PI;
+use std::fmt::{Display, Formatter, Error};
+
+use crate::samplers::{Sampler, SamplerKind};
+use crate::vectors::{Vec3, Vec3Methods};
+use crate::rays::Ray;
+use crate::objects::{Form, SurfaceFunctions, HittableList};
+use crate::hittable::HitRecord;
+use crate::radiation::{cosine_direction, RAY_EPS};
+use crate::exchange::{Band, SurfaceOptics};
+
+/// Mean Earth radius (m).
+pub const EARTH_RADIUS: f32 = 6.371e6;
+
+/// Solar constant at 1 AU (W / m²).
+pub const SOLAR_CONSTANT: f32 = 1361e0;
+
+/// Mean Earth albedo.
+pub const EARTH_ALBEDO: f32 = 0.3;
+
+/// Mean Earth infrared exitance (W / m²).
+pub const EARTH_IR: f32 = 237e0;
+
+/// Obliquity of the ecliptic (rad).
+const OBLIQUITY: f32 = 0.409_1;
+
+/// Circular orbit around the Earth.
+///
+/// Positions and directions are given on the orbit frame: $Z$ along
+/// the orbit normal (angular momentum) and $X$ along the projection
+/// of the sun direction on the orbit plane, so that the sun lies on
+/// the $XZ$ plane at an elevation $\beta$ over the orbit plane.
+#[derive(Copy, Clone, Debug)]
+pub struct Orbit {
+    /// Altitude over the mean Earth radius (m).
+    pub altitude: f32,
+    /// Beta angle, sun elevation over the orbit plane (rad).
+    pub beta: f32,
+    /// Position along the orbit, angle from the noon point (rad).
+    pub anomaly: f32,
+}
+
+impl Orbit {
+    /// Orbit constructor.
+    ///
+    /// # Parameters:
+    /// * `altitude` - Altitude (m).
+    /// * `beta` - Beta angle (rad).
+    /// * `anomaly` - Angle from the noon point along the orbit (rad).
+    pub fn new(altitude: f32, beta: f32, anomaly: f32) -> Orbit {
+        Orbit { altitude, beta, anomaly }
+    }
+
+    /// Orbit from its orientation and the season:
+    /// $$\sin\beta=\cos\Gamma\sin\Omega\sin i-\sin\Gamma\cos\epsilon\cos\Omega\sin i+\sin\Gamma\sin\epsilon\cos i$$
+    ///
+    /// # Parameters:
+    /// * `altitude` - Altitude (m).
+    /// * `inclination` - Orbit inclination $i$ (rad).
+    /// * `raan` - Right ascension of the ascending node $\Omega$ (rad).
+    /// * `sun_longitude` - Ecliptic longitude of the sun $\Gamma$ (rad).
+    /// * `anomaly` - Angle from the noon point along the orbit (rad).
+    pub fn from_inclination(altitude: f32, inclination: f32, raan: f32, sun_longitude: f32, anomaly: f32) -> Orbit {
+        let sin_beta: f32 = sun_longitude.cos() * raan.sin() * inclination.sin()
+            - sun_longitude.sin() * OBLIQUITY.cos() * raan.cos() * inclination.sin()
+            + sun_longitude.sin() * OBLIQUITY.sin() * inclination.cos();
+        Orbit::new(altitude, sin_beta.clamp(-1e0, 1e0).asin(), anomaly)
+    }
+
+    /// Orbit radius (m).
+    pub fn radius(&self) -> f32 { EARTH_RADIUS + self.altitude }
+
+    /// Spacecraft position (m).
+    pub fn position(&self) -> Vec3 {
+        Vec3::new(self.anomaly.cos(), self.anomaly.sin(), 0e0) * self.radius()
+    }
+
+    /// Spacecraft velocity direction.
+    pub fn velocity(&self) -> Vec3 {
+        Vec3::new(-self.anomaly.sin(), self.anomaly.cos(), 0e0)
+    }
+
+    /// Sun direction.
+    pub fn sun(&self) -> Vec3 {
+        Vec3::new(self.beta.cos(), 0e0, self.beta.sin())
+    }
+
+    /// Whether the spacecraft is in the Earth's (cylindrical) shadow.
+    ///
+    /// # Parameters:
+    /// * `sun` - Sun direction on the orbit frame.
+    pub fn in_eclipse(&self, sun: Vec3) -> bool {
+        let r: Vec3 = self.position();
+        let along: f32 = r.dot(&sun);
+        along < 0e0 && (r - sun * along).length() < EARTH_RADIUS
+    }
+}
+
+/// Spacecraft attitude: the directions, on the body (world) frame,
+/// of the local vertical local horizontal (LVLH) axes.
+#[derive(Copy, Clone)]
+pub struct Attitude {
+    /// Direction towards the Earth's center.
+    nadir: Vec3,
+    /// Direction of the orbital velocity.
+    velocity: Vec3,
+}
+
+impl Attitude {
+    /// Attitude constructor. The velocity direction is made
+    /// perpendicular to the nadir one.
+    ///
+    /// # Parameters:
+    /// * `nadir` - Body frame direction towards the Earth.
+    /// * `velocity` - Body frame direction of flight.
+    pub fn new(nadir: Vec3, velocity: Vec3) -> Attitude {
+        let nadir: Vec3 = nadir.unit_vector();
+        let velocity: Vec3 = (velocity - nadir * nadir.dot(&velocity)).unit_vector();
+        Attitude { nadir, velocity }
+    }
+
+    /// Body frame direction towards the Earth.
+    pub fn nadir(&self) -> Vec3 { self.nadir }
+
+    /// Body frame components of an orbit frame direction.
+    ///
+    /// # Parameters:
+    /// * `orbit` - Spacecraft orbit.
+    /// * `v` - Direction on the orbit frame.
+    pub fn to_body(&self, orbit: &Orbit, v: Vec3) -> Vec3 {
+        let nadir: Vec3 = -orbit.position().unit_vector();
+        let velocity: Vec3 = orbit.velocity();
+        self.nadir * v.dot(&nadir)
+            + self.velocity * v.dot(&velocity)
+            + self.nadir.cross(&self.velocity) * v.dot(&nadir.cross(&velocity))
+    }
+}
+
+/// Orbital thermal environment of a spacecraft.
+#[derive(Copy, Clone)]
+pub struct Environment {
+    /// Spacecraft orbit.
+    pub orbit: Orbit,
+    /// Sun direction on the orbit frame.
+    pub sun: Vec3,
+    /// Spacecraft attitude.
+    pub attitude: Attitude,
+    /// Solar flux (W / m²).
+    pub solar_constant: f32,
+    /// Earth albedo.
+    pub albedo: f32,
+    /// Earth infrared exitance (W / m²).
+    pub earth_ir: f32,
+}
+
+impl Environment {
+    /// Environment with the sun direction given by the beta angle of
+    /// the orbit and mean solar and Earth fluxes.
+    pub fn new(orbit: Orbit, attitude: Attitude) -> Environment {
+        Environment {
+            orbit,
+            sun: orbit.sun(),
+            attitude,
+            solar_constant: SOLAR_CONSTANT,
+            albedo: EARTH_ALBEDO,
+            earth_ir: EARTH_IR,
+        }
+    }
+}
+
+/// Power absorbed by a form (W).
+#[derive(Copy, Clone, Debug, Default)]
+pub struct HeatLoad {
+    /// Direct sunlight.
+    pub solar: f32,
+    /// Sunlight reflected by the Earth.
+    pub albedo: f32,
+    /// Earth infrared emission.
+    pub earth_ir: f32,
+}
+
+impl HeatLoad {
+    /// Total absorbed power.
+    pub fn total(&self) -> f32 { self.solar + self.albedo + self.earth_ir }
+}
+
+/// Heat loads of the world forms.
+pub struct HeatLoads {
+    /// ``loads[i]`` is the power absorbed by form $i$.
+    pub loads: Vec<HeatLoad>
+}
+
+/// Display trait implementation on HeatLoads.
+impl Display for HeatLoads {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        for (i, load) in self.loads.iter().enumerate() {
+            writeln!(
+                f,
+                "Q({}) = {:.4} W (solar {:.4}, albedo {:.4}, IR {:.4})",
+                i,
+                load.total(),
+                load.solar,
+                load.albedo,
+                load.earth_ir)?;
+        }
+        Ok(())
+    }
+}
+
+/// Orbital heat loads trait for world.
+pub trait OrbitalHeatLoads {
+    /// Absorbed power of one form, by Monte Carlo integration over its
+    /// surface. Sunlight is absorbed with the solar absorptivity of the
+    /// form material, if a ray towards the sun leaves the world unblocked.
+    /// Earth albedo and infrared are collected by cosine weighted rays
+    /// reaching the (lambertian) Earth unblocked, the latter absorbed
+    /// with the infrared emissivity. Reflections between forms are not
+    /// included.
+    ///
+    /// # Parameters:
+    ///
+    /// * `self`: world of objects (spacecraft body frame, in meters).
+    /// * `n`: number of surface samples.
+    /// * `form_idx`: World index of the form.
+    /// * `env`: Orbital environment.
+    ///
+    /// # Returns:
+    ///
+    /// * `HeatLoad`: Absorbed powers (W).
+    fn heat_load(&self, n: usize, form_idx: usize, env: &Environment) -> HeatLoad;
+
+    /// Same as `heat_load`, for every form.
+    fn heat_loads(&self, n: usize, env: &Environment) -> HeatLoads;
+}
+
+impl OrbitalHeatLoads for HittableList {
+    fn heat_load(&self, n: usize, form_idx: usize, env: &Environment) -> HeatLoad {
+        let form: &Form = self.forms.get(form_idx).unwrap();
+        if matches!(form, Form::Medium(_)) || form.area() <= 0e0 {
+            return HeatLoad::default();
+        }
+        let solar_optics: SurfaceOptics = self.surface_optics(Band::Solar)[form_idx];
+        let ir_optics: SurfaceOptics = self.surface_optics(Band::Infrared)[form_idx];
+        let sun: Vec3 = env.attitude.to_body(&env.orbit, env.sun).unit_vector();
+        let earth: Vec3 = env.attitude.nadir() * env.orbit.radius();
+        let lit: bool = !env.orbit.in_eclipse(env.sun);
+
+        let mut sampler: Sampler = Sampler::new(SamplerKind::Sobol, n);
+        let (mut solar, mut albedo, mut earth_ir): (f32, f32, f32) = (0e0, 0e0, 0e0);
+        for k in 0..n {
+            sampler.start_sample(k);
+            let (s, t) = sampler.next_2d();
+            let direction: (f32, f32) = sampler.next_2d();
+            // Points are uniform on (s, t), weighted by their area.
+            let weight: f32 = form.diff_a(s, t) / form.area();
+            let p: Vec3 = form.point(s, t);
+            let normal: Vec3 = form.normal(s, t);
+
+            // Direct sunlight.
+            let cos_sun: f32 = normal.dot(&sun);
+            if lit && cos_sun > 0e0 {
+                solar += weight * cos_sun * self.unblocked(&Ray::new(p, sun));
+            }
+
+            // Earth, seen through cosine weighted rays.
+            let ray: Ray = Ray::new(p, cosine_direction(normal, direction));
+            if let Some(earth_normal) = earth_hit(&ray, earth) {
+                let tau: f32 = self.unblocked(&ray);
+                earth_ir += weight * tau;
+                albedo += weight * tau * earth_normal.dot(&sun).max(0e0);
+            }
+        }
+        let area: f32 = form.area() / n as f32;
+        HeatLoad {
+            solar: solar_optics.emissivity * env.solar_constant * solar * area,
+            albedo: solar_optics.emissivity * env.albedo * env.solar_constant * albedo * area,
+            earth_ir: ir_optics.emissivity * env.earth_ir * earth_ir * area,
+        }
+    }
+
+    fn heat_loads(&self, n: usize, env: &Environment) -> HeatLoads {
+        HeatLoads { loads: (0..self.forms.len()).map(|i| self.heat_load(n, i, env)).collect() }
+    }
+}
+
+/// Shadowing by the world forms.
+impl HittableList {
+    /// Fraction of the radiation leaving the world along ``ray``: 0 if
+    /// a surface blocks it, the media transmittance otherwise.
+    fn unblocked(&self, ray: &Ray) -> f32 {
+        let mut rec: Option<HitRecord> = None;
+        match self.hit_surfaces(ray, RAY_EPS, f32::MAX, &mut rec) {
+            true => 0e0,
+            false => self.transmittance(ray, 0e0, f32::MAX),
+        }
+    }
+}
+
+/// Earth surface normal where ``ray`` hits the Earth, if it does,
+/// the Earth's center being at ``center`` from the ray's origin.
+/// Computed in double precision, the ray's origin being about the
+/// Earth radius away.
+fn earth_hit(ray: &Ray, center: Vec3) -> Option<Vec3> {
+    let d: Vec3 = ray.direction().unit_vector();
+    let (dx, dy, dz): (f64, f64, f64) = (d.x() as f64, d.y() as f64, d.z() as f64);
+    let (cx, cy, cz): (f64, f64, f64) = (center.x() as f64, center.y() as f64, center.z() as f64);
+    let radius: f64 = EARTH_RADIUS as f64;
+    let distance: f64 = (cx * cx + cy * cy + cz * cz).sqrt();
+    let b: f64 = dx * cx + dy * cy + dz * cz;
+    let disc: f64 = b * b - (distance - radius) * (distance + radius);
+    if b <= 0e0 || disc < 0e0 {
+        return None;
+    }
+    let t: f64 = b - disc.sqrt();
+    let normal: Vec3 = Vec3::new(
+        ((dx * t - cx) / radius) as f32,
+        ((dy * t - cy) / radius) as f32,
+        ((dz * t - cz) / radius) as f32
+    );
+    Some(normal.unit_vector())
+}
+
+/// Orbital period of a circular orbit (s).
+///
+/// # Parameters:
+/// * `altitude` - Altitude (m).
+pub fn orbital_period(altitude: f32) -> f32 {
+    // Earth's gravitational parameter (m³ / s²).
+    let mu: f32 = 3.986_004e14;
+    2e0 * PI * ((EARTH_RADIUS + altitude).powi(3) / mu).sqrt()
+}
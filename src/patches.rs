@@ -0,0 +1,306 @@
+//! Surface patches module. Meshes the world forms into patches over
+//! their $(s, t)$ parameter domain, so that view factors and radiosity
+//! are resolved along each surface instead of one value per form.
+//! Patch results can be aggregated back to their parent forms, or
+//! rendered as a false color map.
+
+use std::ops::Range;
+
+use crate::rays::Ray;
+use crate::objects::{Form, SurfaceFunctions, HittableList};
+use crate::hittable::HitRecord;
+use crate::cameras::{Camera, CameraRay};
+use crate::samplers::{Sampler, SamplerKind};
+use crate::film::Framebuffer;
+use crate::adaptive::ramp_color;
+
+/// Quadrature points per parameter of the patch areas.
+const AREA_QUADRATURE: usize = 8;
+
+/// Radiosity convergence tolerance, relative to the largest radiosity.
+const RADIOSITY_TOL: f32 = 1e-6;
+
+/// Maximum number of radiosity (Gauss-Seidel) sweeps.
+const RADIOSITY_MAX_ITER: usize = 1000;
+
+/// Rectangle of the parameter domain of a form.
+#[derive(Copy, Clone, Debug)]
+pub struct Patch {
+    /// World index of the parent form.
+    pub form: usize,
+    /// Parameter $s$ range.
+    pub s: (f32, f32),
+    /// Parameter $t$ range.
+    pub t: (f32, f32),
+    /// Patch area, $\int\text{diff\\_a}(s, t)\\,\text{d}s\\,\text{d}t$.
+    pub area: f32,
+}
+
+impl Patch {
+    /// Parameters of the form at the patch's local coordinates
+    /// ``(a, b)``, both from 0 to 1.
+    pub fn parameters(&self, a: f32, b: f32) -> (f32, f32) {
+        (self.s.0 + a * (self.s.1 - self.s.0), self.t.0 + b * (self.t.1 - self.t.0))
+    }
+
+    /// Area of the parameter rectangle, $\Delta s\Delta t$.
+    fn parameter_area(&self) -> f32 {
+        (self.s.1 - self.s.0) * (self.t.1 - self.t.0)
+    }
+}
+
+/// Uniform grid of patches of one form.
+#[derive(Copy, Clone, Debug)]
+struct Grid {
+    /// Index of the first patch of the form.
+    first: usize,
+    /// Divisions of $s$.
+    n_s: usize,
+    /// Divisions of $t$.
+    n_t: usize,
+}
+
+/// Patches of every surface form of a world.
+pub struct Mesh {
+    /// Patches, grouped by form, with $s$ varying fastest.
+    pub patches: Vec<Patch>,
+    /// Grid of each form, none for forms without patches.
+    grids: Vec<Option<Grid>>,
+}
+
+impl Mesh {
+    /// Meshes every form into ``n_s`` by ``n_t`` patches of equal
+    /// parameter ranges. Participating media and cubes are not meshed
+    /// (they have no parameterized surface), and rays hitting them are
+    /// lost.
+    ///
+    /// # Parameters:
+    /// * `world` - Forms to mesh.
+    /// * `n_s` - Divisions of the $s$ parameter.
+    /// * `n_t` - Divisions of the $t$ parameter.
+    pub fn new(world: &HittableList, n_s: usize, n_t: usize) -> Mesh {
+        let (n_s, n_t): (usize, usize) = (n_s.max(1), n_t.max(1));
+        let mut patches: Vec<Patch> = Vec::new();
+        let mut grids: Vec<Option<Grid>> = Vec::new();
+        for (idx, form) in world.forms.iter().enumerate() {
+            match form {
+                Form::Medium(_) | Form::Cube(_) => {
+                    grids.push(None);
+                    continue;
+                }
+                _ => grids.push(Some(Grid { first: patches.len(), n_s, n_t })),
+            }
+            for k_t in 0..n_t {
+                for k_s in 0..n_s {
+                    let mut patch: Patch = Patch {
+                        form: idx,
+                        s: (k_s as f32 / n_s as f32, (k_s + 1) as f32 / n_s as f32),
+                        t: (k_t as f32 / n_t as f32, (k_t + 1) as f32 / n_t as f32),
+                        area: 0e0,
+                    };
+                    patch.area = patch_area(form, &patch);
+                    patches.push(patch);
+                }
+            }
+        }
+        Mesh { patches, grids }
+    }
+
+    /// Number of patches.
+    pub fn len(&self) -> usize { self.patches.len() }
+
+    /// Whether there are no patches.
+    pub fn is_empty(&self) -> bool { self.patches.is_empty() }
+
+    /// Indices of the patches of a form, empty if it is not meshed.
+    pub fn form_patches(&self, form_idx: usize) -> Range<usize> {
+        match self.grids.get(form_idx).cloned().flatten() {
+            Some(grid) => grid.first..(grid.first + grid.n_s * grid.n_t),
+            None => 0..0,
+        }
+    }
+
+    /// Patch of a form containing the parameters ``(s, t)``, if the
+    /// form is meshed.
+    pub fn patch_at(&self, form_idx: usize, s: f32, t: f32) -> Option<usize> {
+        let grid: Grid = self.grids.get(form_idx).cloned().flatten()?;
+        let k_s: usize = ((s.clamp(0e0, 1e0) * grid.n_s as f32) as usize).min(grid.n_s - 1);
+        let k_t: usize = ((t.clamp(0e0, 1e0) * grid.n_t as f32) as usize).min(grid.n_t - 1);
+        Some(grid.first + k_t * grid.n_s + k_s)
+    }
+
+    /// Patch containing a hit point, if its form is meshed.
+    pub fn patch_hit(&self, world: &HittableList, rec: &HitRecord) -> Option<usize> {
+        let (s, t) = world.forms[rec.hit_elem].parameters(rec.p);
+        self.patch_at(rec.hit_elem, s, t)
+    }
+
+    /// View factors between every pair of patches, $F_{pq}$, by
+    /// shooting ``n`` cosine weighted rays from each patch (see
+    /// `VfMethod::RayShooting`).
+    ///
+    /// # Parameters:
+    /// * `world` - Meshed world.
+    /// * `n` - Rays per patch.
+    ///
+    /// # Returns:
+    /// * `Vec<Vec<f32>>` - View factors, ``vfs[p][q]`` being $F_{pq}$;
+    ///   $1-\sum_q F_{pq}$ escapes the world or hits unmeshed forms.
+    pub fn view_factors(&self, world: &HittableList, n: usize) -> Vec<Vec<f32>> {
+        (0..self.len()).map(|p| self.view_factor_row(world, n, p)).collect()
+    }
+
+    /// View factors from one patch to every patch, $F_{p*}$.
+    fn view_factor_row(&self, world: &HittableList, n: usize, patch_idx: usize) -> Vec<f32> {
+        let patch: &Patch = &self.patches[patch_idx];
+        let form: &Form = &world.forms[patch.form];
+        let mut sampler: Sampler = Sampler::new(SamplerKind::Sobol, n);
+        let mut row: Vec<f32> = vec![0e0; self.len()];
+        for k in 0..n {
+            sampler.start_sample(k);
+            let (a, b) = sampler.next_2d();
+            let direction: (f32, f32) = sampler.next_2d();
+            let (s, t) = patch.parameters(a, b);
+            if let Some((rec, tau)) = world.shoot_cosine(form.point(s, t), form.normal(s, t), direction) {
+                if let Some(q) = self.patch_hit(world, &rec) {
+                    // Points are uniform on the patch's (s, t) range, weighted by their area.
+                    row[q] += tau * form.diff_a(s, t) * patch.parameter_area() / patch.area;
+                }
+            }
+        }
+        row.iter().map(|f| f / n as f32).collect()
+    }
+
+    /// Form to form view factors from the patch ones:
+    /// $$F_{IJ}=\frac{1}{A_I}\sum_{p\in I}\sum_{q\in J}A_pF_{pq}$$
+    ///
+    /// # Parameters:
+    /// * `vfs` - Patch view factors (see `Mesh::view_factors`).
+    ///
+    /// # Returns:
+    /// * `Vec<Vec<f32>>` - View factors, ``vfs[i][j]`` being $F_{ij}$
+    ///   (zero rows and columns for unmeshed forms).
+    pub fn aggregate(&self, vfs: &[Vec<f32>]) -> Vec<Vec<f32>> {
+        let n_forms: usize = self.grids.len();
+        let mut forms: Vec<Vec<f32>> = vec![vec![0e0; n_forms]; n_forms];
+        for (p, row) in vfs.iter().enumerate() {
+            let patch: &Patch = &self.patches[p];
+            for (q, f_pq) in row.iter().enumerate() {
+                forms[patch.form][self.patches[q].form] += patch.area * f_pq;
+            }
+        }
+        for (i, row) in forms.iter_mut().enumerate() {
+            let area: f32 = self.form_area(i);
+            if area > 0e0 {
+                row.iter_mut().for_each(|f| *f /= area);
+            }
+        }
+        forms
+    }
+
+    /// Area weighted mean of a patch quantity over each form, zero
+    /// for unmeshed forms.
+    pub fn form_means(&self, values: &[f32]) -> Vec<f32> {
+        (0..self.grids.len())
+            .map(|i| {
+                let area: f32 = self.form_area(i);
+                let sum: f32 = self.form_patches(i).map(|p| self.patches[p].area * values[p]).sum();
+                match area > 0e0 {
+                    true => sum / area,
+                    false => 0e0,
+                }
+            })
+            .collect()
+    }
+
+    /// Per patch copy of a form quantity.
+    pub fn per_patch(&self, values: &[f32]) -> Vec<f32> {
+        self.patches.iter().map(|patch| values[patch.form]).collect()
+    }
+
+    /// Meshed area of a form.
+    fn form_area(&self, form_idx: usize) -> f32 {
+        self.form_patches(form_idx).map(|p| self.patches[p].area).sum()
+    }
+
+    /// False color map of a patch quantity seen from a camera, from
+    /// blue (smallest value) to red (largest value), black where no
+    /// patch is seen. One ray through each pixel's center.
+    ///
+    /// # Parameters:
+    /// * `world` - Meshed world.
+    /// * `cam` - Camera.
+    /// * `width_px` - Image width in pixels.
+    /// * `height_px` - Image height in pixels.
+    /// * `values` - Quantity of each patch.
+    ///
+    /// # Returns:
+    /// * `Framebuffer` - Display values, to be written without
+    ///   encoding (`Transfer::Linear`).
+    pub fn false_color(&self, world: &HittableList, cam: &Camera, width_px: u16, height_px: u16, values: &[f32]) -> Framebuffer {
+        let min: f32 = values.iter().cloned().fold(f32::INFINITY, f32::min);
+        let max: f32 = values.iter().cloned().fold(f32::NEG_INFINITY, f32::max);
+        let mut film: Framebuffer = Framebuffer::new(width_px, height_px);
+        for j in 0..height_px {
+            for i in 0..width_px {
+                let u: f32 = (i as f32 + 5e-1) / (width_px as f32);
+                let v: f32 = (j as f32 + 5e-1) / (height_px as f32);
+                let ray: Ray = cam.get_ray_lens(u, v, (5e-1, 5e-1));
+                let mut rec: Option<HitRecord> = None;
+                if !cam.covers(u, v) || !world.hit_surfaces(&ray, 1e-3, f32::MAX, &mut rec) {
+                    continue;
+                }
+                if let Some(p) = rec.as_ref().and_then(|rec| self.patch_hit(world, rec)) {
+                    let x: f32 = match max > min {
+                        true => (values[p] - min) / (max - min),
+                        false => 5e-1,
+                    };
+                    film.set(i, j, ramp_color(x));
+                }
+            }
+        }
+        film
+    }
+}
+
+/// Radiosity of each patch of a grey diffuse enclosure, solution of
+/// $$B_p=E_p+\rho_p\sum_qF_{pq}B_q$$
+/// by Gauss-Seidel iteration.
+///
+/// # Parameters:
+/// * `vfs` - Patch view factors (see `Mesh::view_factors`).
+/// * `emission` - Emitted power per unit area $E_p$ of each patch.
+/// * `reflectivity` - Diffuse reflectivity $\rho_p$ of each patch, below 1.
+///
+/// # Returns:
+/// * `Vec<f32>` - Radiosity (leaving power per unit area) $B_p$.
+pub fn radiosity(vfs: &[Vec<f32>], emission: &[f32], reflectivity: &[f32]) -> Vec<f32> {
+    let mut b: Vec<f32> = emission.to_vec();
+    for _ in 0..RADIOSITY_MAX_ITER {
+        let mut change: f32 = 0e0;
+        for p in 0..b.len() {
+            let irradiance: f32 = vfs[p].iter().zip(b.iter()).map(|(f, b_q)| f * b_q).sum();
+            let b_p: f32 = emission[p] + reflectivity[p] * irradiance;
+            change = change.max((b_p - b[p]).abs());
+            b[p] = b_p;
+        }
+        let scale: f32 = b.iter().cloned().fold(0e0, f32::max);
+        if change <= RADIOSITY_TOL * scale {
+            break;
+        }
+    }
+    b
+}
+
+/// Patch area by midpoint quadrature of the form's area differential.
+fn patch_area(form: &Form, patch: &Patch) -> f32 {
+    let n: usize = AREA_QUADRATURE;
+    let mut sum: f32 = 0e0;
+    for k_a in 0..n {
+        for k_b in 0..n {
+            let (s, t) = patch.parameters((k_a as f32 + 5e-1) / n as f32, (k_b as f32 + 5e-1) / n as f32);
+            sum += form.diff_a(s, t);
+        }
+    }
+    sum * patch.parameter_area() / (n * n) as f32
+}
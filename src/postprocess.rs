@@ -0,0 +1,167 @@
+//! Post-processing module. Converts linear radiance values into
+//! displayable 8-bit colors: exposure, tone mapping, sRGB transfer
+//! function and dithering.
+
+extern crate rand;
+use self::rand::random;
+
+use crate::vectors::Vec3;
+use crate::cameras::CameraFocus;
+
+/// Camera exposure.
+#[derive(Copy, Clone)]
+pub enum Exposure {
+    /// Exposure compensation in stops, radiance is scaled by $2^{EV}$.
+    Ev(f32),
+    /// Physical camera settings. Radiance is scaled by
+    /// $$k=\frac{S}{100}\frac{t}{N^2}\left(\frac{16^2}{1/100}\right)$$
+    /// so that the "sunny 16" settings (ISO 100, 1/100 s, f/16) give $k = 1$.
+    Physical {
+        /// Sensor sensitivity $S$ (ISO).
+        iso: f32,
+        /// Shutter time $t$ (s).
+        shutter: f32,
+        /// Aperture's f-number $N$.
+        f_number: f32,
+    },
+}
+
+/// Tone mapping operators, compressing radiance into the $[0, 1]$ range.
+#[derive(Copy, Clone)]
+pub enum ToneMapping {
+    /// Values above 1 are clipped.
+    Clamp,
+    /// Reinhard operator, $c' = \frac{c}{1 + c}$.
+    Reinhard,
+    /// Extended Reinhard operator, mapping ``white`` to 1:
+    /// $c' = \frac{c\left(1 + c/c_w^2\right)}{1 + c}$.
+    ReinhardExtended { white: f32 },
+    /// ACES filmic curve (Narkowicz fit).
+    AcesFilmic,
+}
+
+/// Transfer function (encoding) from linear values to display values.
+#[derive(Copy, Clone)]
+pub enum Transfer {
+    /// No encoding.
+    Linear,
+    /// Gamma 2 approximation, $c' = \sqrt{c}$.
+    Gamma2,
+    /// Standard sRGB piecewise curve.
+    Srgb,
+}
+
+/// Post-processing pipeline.
+#[derive(Copy, Clone)]
+pub struct PostProcess {
+    /// Exposure applied to the linear radiance.
+    pub exposure: Exposure,
+    /// Tone mapping operator.
+    pub tone_mapping: ToneMapping,
+    /// Display encoding.
+    pub transfer: Transfer,
+    /// Whether to add triangular noise of one quantization step
+    /// before quantizing to 8 bits (avoids banding).
+    pub dither: bool,
+}
+
+impl Exposure {
+    /// Exposure from the aperture of a thin lens camera.
+    ///
+    /// # Parameters:
+    /// * `camera` - camera, its f-number is taken from its aperture.
+    /// * `iso` - Sensor sensitivity (ISO).
+    /// * `shutter` - Shutter time (s).
+    pub fn from_camera(camera: &CameraFocus, iso: f32, shutter: f32) -> Exposure {
+        Exposure::Physical { iso, shutter, f_number: camera.f_number() }
+    }
+
+    /// Linear radiance scale factor.
+    pub fn scale(&self) -> f32 {
+        match self {
+            Exposure::Ev(ev) => 2f32.powf(*ev),
+            Exposure::Physical { iso, shutter, f_number } => {
+                iso / 100e0 * shutter / f_number.powi(2) * 256e0 / 1e-2
+            }
+        }
+    }
+}
+
+impl ToneMapping {
+    /// Tone mapped value of a linear channel value ``c``.
+    pub fn map(&self, c: f32) -> f32 {
+        let c: f32 = c.max(0e0);
+        let mapped: f32 = match self {
+            ToneMapping::Clamp => c,
+            ToneMapping::Reinhard => c / (1e0 + c),
+            ToneMapping::ReinhardExtended { white } => c * (1e0 + c / white.powi(2)) / (1e0 + c),
+            ToneMapping::AcesFilmic => {
+                (c * (2.51 * c + 0.03)) / (c * (2.43 * c + 0.59) + 0.14)
+            }
+        };
+        mapped.min(1e0)
+    }
+}
+
+impl Transfer {
+    /// Encoded value of a linear channel value ``c`` from 0 to 1.
+    pub fn encode(&self, c: f32) -> f32 {
+        match self {
+            Transfer::Linear => c,
+            Transfer::Gamma2 => c.sqrt(),
+            Transfer::Srgb => match c <= 0.003_130_8 {
+                true => 12.92 * c,
+                false => 1.055 * c.powf(1e0 / 2.4) - 0.055,
+            },
+        }
+    }
+}
+
+impl Default for PostProcess {
+    fn default() -> PostProcess {
+        PostProcess::new()
+    }
+}
+
+impl PostProcess {
+    /// Default pipeline: no exposure compensation, clamping and sRGB encoding.
+    pub fn new() -> PostProcess {
+        PostProcess {
+            exposure: Exposure::Ev(0e0),
+            tone_mapping: ToneMapping::Clamp,
+            transfer: Transfer::Srgb,
+            dither: false,
+        }
+    }
+
+    /// Display value from 0 to 1 of a linear radiance value.
+    pub fn display(&self, col: Vec3) -> Vec3 {
+        let scale: f32 = self.exposure.scale();
+        let mut out: Vec3 = Vec3::zeros();
+        for c in 0..3 {
+            // Non finite values (diverging samples) are shown black.
+            let value: f32 = match col[c].is_finite() {
+                true => col[c] * scale,
+                false => 0e0,
+            };
+            out.e[c] = self.transfer.encode(self.tone_mapping.map(value));
+        }
+        out
+    }
+
+    /// 8-bit color of a linear radiance value.
+    pub fn apply(&self, col: Vec3) -> [u8; 3] {
+        let display: Vec3 = self.display(col);
+        let mut rgb: [u8; 3] = [0; 3];
+        for (c, value) in rgb.iter_mut().enumerate() {
+            let level: f32 = 255e0 * display[c];
+            // Saturated values stay exact black or white.
+            let noise: f32 = match self.dither && level > 1e-3 && level < 255e0 - 1e-3 {
+                true => random::<f32>() - random::<f32>(),
+                false => 0e0,
+            };
+            *value = (level + 5e-1 + noise).floor().clamp(0e0, 255e0) as u8;
+        }
+        rgb
+    }
+}
@@ -0,0 +1,46 @@
+//! Progressive rendering module. Settings of renders made of
+//! successive passes over the whole image, accumulated on a float
+//! framebuffer that is checkpointed to disk between passes.
+
+/// Progressive rendering settings.
+#[derive(Clone)]
+pub struct Progressive {
+    /// Samples added to every pixel on each pass.
+    pub pass_samples: u16,
+    /// Samples per pixel at which the render ends.
+    pub target_samples: u32,
+    /// Checkpoint file, ``None`` to keep the accumulation in memory only.
+    pub checkpoint: Option<String>,
+    /// Passes between checkpoints.
+    pub checkpoint_every: usize,
+}
+
+impl Progressive {
+    /// Progressive constructor.
+    ///
+    /// # Parameters:
+    /// * `pass_samples` - Samples added to every pixel on each pass.
+    /// * `target_samples` - Samples per pixel at which the render ends.
+    /// * `checkpoint` - Checkpoint file, if any.
+    /// * `checkpoint_every` - Passes between checkpoints.
+    pub fn new(pass_samples: u16, target_samples: u32, checkpoint: Option<&str>, checkpoint_every: usize) -> Progressive {
+        Progressive {
+            pass_samples: pass_samples.max(1),
+            target_samples,
+            checkpoint: checkpoint.map(String::from),
+            checkpoint_every: checkpoint_every.max(1),
+        }
+    }
+
+    /// Samples to add to a pixel with ``taken`` samples on the next pass.
+    pub fn pass_samples(&self, taken: u32) -> u16 {
+        self.target_samples.saturating_sub(taken).min(self.pass_samples as u32) as u16
+    }
+
+    /// Number of passes left to reach the target from ``taken`` samples.
+    pub fn passes_left(&self, taken: u32) -> usize {
+        let left: u32 = self.target_samples.saturating_sub(taken);
+        let p: u32 = self.pass_samples.max(1) as u32;
+        (left / p + (left % p != 0) as u32) as usize
+    }
+}
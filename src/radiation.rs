@@ -4,35 +4,95 @@
 use std::f32::consts::PI;
 use std::fmt::{Display, Formatter, Error};
 
-use rand::random;
+use crate::samplers::{Sampler, SamplerKind};
+use crate::cameras::sample_unit_disk;
 use crate::vectors::{Vec3, Vec3Methods};
 use crate::rays::Ray;
 use crate::objects::{Form, SurfaceFunctions, HittableList};
-use crate::hittable::{HitRecord, Hittable};
+use crate::hittable::HitRecord;
+
+/// Minimum distance of the first hit of the shot rays, so that
+/// they do not hit the emitting surface itself.
+pub(crate) const RAY_EPS: f32 = 1e-4;
+
+/// View factor estimators.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum VfMethod {
+    /// Double area integration of the kernel
+    /// $\frac{\cos\beta_1\cos\beta_2}{\pi L^2}$ between random point
+    /// pairs, one form pair at a time. Its variance grows without bound
+    /// for nearby or touching surfaces ($L \to 0$).
+    AreaIntegration,
+    /// Cosine weighted rays shot from random points of the first
+    /// form, counting the form each one hits first on its front side.
+    /// Bounded variance, and one pass gives the whole row $F_{i*}$.
+    RayShooting,
+}
 
 
 /// View Factors structure.
 pub struct Vfs {
-    pub vfs: Vec<Vec<f32>>
+    /// ``vfs[i][j - i - 1]`` is $F_{ij}$, $j > i$.
+    pub vfs: Vec<Vec<f32>>,
+    /// Self view factors $F_{ii}$, zero but on concave forms
+    /// (see `Form::sees_itself`).
+    pub self_vfs: Vec<f32>
+}
+
+impl Default for Vfs {
+    fn default() -> Vfs {
+        Vfs::new()
+    }
 }
 
 /// View Factors methods.
 impl Vfs {
     /// Constructor.
     pub fn new() -> Vfs {
-        Vfs { vfs: Vec::new() }
+        Vfs { vfs: Vec::new(), self_vfs: Vec::new() }
     }
 
-    /// From list of view factors Vec<Vec<f32>>.
+    /// From list of view factors Vec<Vec<f32>>, without self view factors.
     pub fn from(vfs: Vec<Vec<f32>>) -> Vfs {
-        Vfs { vfs }
+        let self_vfs: Vec<f32> = vec![0e0; vfs.len()];
+        Vfs { vfs, self_vfs }
+    }
+
+    /// From list of view factors and self view factors.
+    pub fn with_self(vfs: Vec<Vec<f32>>, self_vfs: Vec<f32>) -> Vfs {
+        Vfs { vfs, self_vfs }
+    }
+
+    /// Full square matrix of view factors, $F_{ij}$ at ``[i][j]``. The
+    /// factors below the diagonal follow from reciprocity,
+    /// $F_{ji}=A_iF_{ij}/A_j$, and the diagonal holds the self view factors.
+    ///
+    /// # Parameters:
+    /// * `world` - World the view factors were computed on.
+    pub fn matrix(&self, world: &HittableList) -> Vec<Vec<f32>> {
+        let n: usize = self.vfs.len();
+        let mut matrix: Vec<Vec<f32>> = vec![vec![0e0; n]; n];
+        for (i, row) in self.vfs.iter().enumerate() {
+            matrix[i][i] = self.self_vfs.get(i).copied().unwrap_or(0e0);
+            for (k, f) in row.iter().enumerate() {
+                let j: usize = i + k + 1;
+                matrix[i][j] = *f;
+                let a_j: f32 = world.forms[j].area();
+                matrix[j][i] = match a_j > 0e0 {
+                    true => world.forms[i].area() * f / a_j,
+                    false => 0e0,
+                };
+            }
+        }
+        matrix
     }
 }
 
 /// View Factor trait for world.
 pub trait ViewFactors {
     /// Compute the view factor for two objects on the world by means of
-    /// the Monte Carlo Method.
+    /// the Monte Carlo Method. Participating media do not block the
+    /// line of sight but attenuate it by their transmittance.
     ///
     /// # Parameters:
     ///
@@ -46,8 +106,64 @@ pub trait ViewFactors {
     /// * `f32`: View factor from object 1 to 2, $F_{12}$.
     fn view_factor(&self, n: usize, form_1_idx: usize, form_2_idx: usize) -> f32;
 
+    /// Same as `view_factor`, drawing the surface points
+    /// $(s_1, t_1, s_2, t_2)$ from the given ``sampler``
+    /// (`view_factor` uses a scrambled Sobol sequence).
+    fn view_factor_sampled(&self, n: usize, form_1_idx: usize, form_2_idx: usize, sampler: SamplerKind) -> f32;
+
+    /// Same as `view_factor`, with the given estimator
+    /// (`view_factor` uses `VfMethod::AreaIntegration`).
+    fn view_factor_with(&self, n: usize, form_1_idx: usize, form_2_idx: usize, method: VfMethod) -> f32;
+
+    /// View factors from one form to every form on the world, $F_{i*}$,
+    /// by shooting ``n`` cosine weighted rays from random points of the
+    /// form (`VfMethod::RayShooting`). The ray weight is the
+    /// transmittance of the participating media it crosses.
+    ///
+    /// # Parameters:
+    ///
+    /// * `self`: world of objects.
+    /// * `n`: number of rays.
+    /// * `form_idx`: World index of the emitting form.
+    /// * `sampler`: generator of the surface points and ray directions.
+    ///
+    /// # Returns:
+    ///
+    /// * `Vec<f32>`: View factors $F_{ij}$, indexed by $j$ (including
+    ///   $F_{ii}$); $1-\sum_j F_{ij}$ escapes the world.
+    fn view_factor_row(&self, n: usize, form_idx: usize, sampler: SamplerKind) -> Vec<f32>;
+
+    /// Same as `view_factors`, with the given estimator. Ray shooting
+    /// traces one row per form instead of one integral per form pair.
+    fn view_factors_with(&self, n: usize, method: VfMethod) -> Vfs;
+
+    /// Same as `view_factor_with`, returning the estimate with its
+    /// standard error (see `VfEstimate::confidence_interval`).
+    fn view_factor_estimate(&self, n: usize, form_1_idx: usize, form_2_idx: usize, method: VfMethod) -> VfEstimate;
+
+    /// View factor $F_{12}$ sampled until its standard error meets
+    /// ``target`` (or its maximum number of samples is reached).
+    ///
+    /// # Parameters:
+    ///
+    /// * `self`: world of objects.
+    /// * `form_1_idx`: World index pointing at the first (main) object.
+    /// * `form_2_idx`: World index pointing at the second object.
+    /// * `method`: estimator.
+    /// * `target`: error target and sample cap.
+    ///
+    /// # Returns:
+    ///
+    /// * `VfEstimate`: estimate, error and samples spent.
+    fn view_factor_converged(&self, form_1_idx: usize, form_2_idx: usize, method: VfMethod, target: &VfTarget) -> VfEstimate;
+
+    /// Same as `view_factor_converged`, for every pair of forms. With
+    /// ray shooting, a row is sampled until all its pairs converge.
+    fn view_factors_converged(&self, method: VfMethod, target: &VfTarget) -> VfEstimates;
+
     /// Compute the view factors on the world by means of
-    /// the Monte Carlo Method.
+    /// the Monte Carlo Method. Moving forms are taken at their rest
+    /// pose $t=0$, see `HittableList::snapshot` for any other instant.
     ///
     /// # Parameters:
     ///
@@ -62,93 +178,398 @@ pub trait ViewFactors {
 
 impl ViewFactors for HittableList {
     fn view_factor(&self, n: usize, form_1_idx: usize, form_2_idx: usize) -> f32 {
-        let form_1: &Form = self.forms.get(form_1_idx).unwrap();
-        let form_2: &Form = self.forms.get(form_2_idx).unwrap();
+        self.view_factor_sampled(n, form_1_idx, form_2_idx, SamplerKind::Sobol)
+    }
+
+    fn view_factor_sampled(&self, n: usize, form_1_idx: usize, form_2_idx: usize, sampler: SamplerKind) -> f32 {
+        let mut sampler: Sampler = Sampler::new(sampler, n);
         let mut temp: f32 = 0e0;
-        let mut l;
-        let mut r12: Vec3;
-        let mut n1: Vec3;
-        let mut n2: Vec3;
-        let mut cos_beta1: f32;
-        let mut cos_beta2: f32;
-        let mut s1: f32;
-        let mut t1: f32;
-        let mut s2: f32;
-        let mut t2: f32;
-
-        let mut da1: f32;
-        let mut da2: f32;
-
-        let mut p1: Vec3;
-        let mut p2: Vec3;
+        for k in 0..n {
+            sampler.start_sample(k);
+            temp += self.area_sample(form_1_idx, form_2_idx, &mut sampler);
+        }
+        temp / (n as f32)
+    }
+
+    fn view_factor_with(&self, n: usize, form_1_idx: usize, form_2_idx: usize, method: VfMethod) -> f32 {
+        match method {
+            VfMethod::AreaIntegration => self.view_factor(n, form_1_idx, form_2_idx),
+            VfMethod::RayShooting => self.view_factor_row(n, form_1_idx, SamplerKind::Sobol)[form_2_idx],
+        }
+    }
+
+    fn view_factor_row(&self, n: usize, form_idx: usize, sampler: SamplerKind) -> Vec<f32> {
+        let mut sampler: Sampler = Sampler::new(sampler, n);
+        let mut row: Vec<f32> = vec![0e0; self.forms.len()];
+        for k in 0..n {
+            sampler.start_sample(k);
+            if let Some((j, weight)) = self.shooting_sample(form_idx, &mut sampler) {
+                row[j] += weight;
+            }
+        }
+        row.iter().map(|f| f / n as f32).collect()
+    }
 
+    fn view_factors_with(&self, n: usize, method: VfMethod) -> Vfs {
+        let n_objs: usize = self.forms.len();
+        let mut viewfactors: Vec<Vec<f32>> = Vec::new();
+        let mut self_vfs: Vec<f32> = vec![0e0; n_objs];
+
+        for i in 0..n_objs {
+            let sees_itself: bool = self.forms[i].sees_itself();
+            let views_i: Vec<f32> = match method {
+                VfMethod::AreaIntegration => {
+                    if sees_itself {
+                        self_vfs[i] = self.view_factor(n, i, i);
+                    }
+                    ((i+1)..n_objs).map(|j| self.view_factor(n, i, j)).collect()
+                }
+                VfMethod::RayShooting => match i + 1 < n_objs || sees_itself {
+                    true => {
+                        let mut row: Vec<f32> = self.view_factor_row(n, i, SamplerKind::Sobol);
+                        self_vfs[i] = row[i];
+                        row.split_off(i + 1)
+                    }
+                    false => Vec::new(),
+                },
+            };
+            viewfactors.push(views_i);
+        }
+        Vfs::with_self(viewfactors, self_vfs)
+    }
+
+    fn view_factors(&self, n: usize) -> Vfs {
+        self.view_factors_with(n, VfMethod::AreaIntegration)
+    }
+
+    fn view_factor_estimate(&self, n: usize, form_1_idx: usize, form_2_idx: usize, method: VfMethod) -> VfEstimate {
+        self.estimate_row(form_1_idx, &[form_2_idx], method, &VfTarget::fixed(n)).remove(0)
+    }
+
+    fn view_factor_converged(&self, form_1_idx: usize, form_2_idx: usize, method: VfMethod, target: &VfTarget) -> VfEstimate {
+        self.estimate_row(form_1_idx, &[form_2_idx], method, target).remove(0)
+    }
+
+    fn view_factors_converged(&self, method: VfMethod, target: &VfTarget) -> VfEstimates {
+        let n_objs: usize = self.forms.len();
+        let mut self_estimates: Vec<Option<VfEstimate>> = vec![None; n_objs];
+        let estimates: Vec<Vec<VfEstimate>> = (0..n_objs)
+            .map(|i| {
+                let sees_itself: bool = self.forms[i].sees_itself();
+                // The self view factor, if any, is estimated first.
+                let targets: Vec<usize> = (i..n_objs).filter(|j| *j > i || sees_itself).collect();
+                let mut row: Vec<VfEstimate> = self.estimate_row(i, &targets, method, target);
+                if sees_itself {
+                    self_estimates[i] = Some(row.remove(0));
+                }
+                row
+            })
+            .collect();
+        VfEstimates { estimates, self_estimates }
+    }
+}
+
+/// Single sample view factor estimators.
+impl HittableList {
+    /// One sample of the double area integral of $F_{12}$, drawing the
+    /// points $(s_1, t_1, s_2, t_2)$ from the current sample of ``sampler``.
+    fn area_sample(&self, form_1_idx: usize, form_2_idx: usize, sampler: &mut Sampler) -> f32 {
+        let form_1: &Form = self.forms.get(form_1_idx).unwrap();
+        let form_2: &Form = self.forms.get(form_2_idx).unwrap();
         let mut hit_rec: Option<HitRecord> = None;
-        let mut ray: Ray;
 
-        for _ in 0..n {
-            s1 = random();
-            t1 = random();
-            s2 = random();
-            t2 = random();
+        let (s1, t1) = sampler.next_2d();
+        let (s2, t2) = sampler.next_2d();
+
+        let p1: Vec3 = form_1.point(s1, t1);
+        let n1: Vec3 = form_1.normal(s1, t1);
+
+        let p2: Vec3 = form_2.point(s2, t2);
+        let n2: Vec3 = form_2.normal(s2, t2);
 
-            p1 = form_1.point(s1, t1);
-            n1 = form_1.normal(s1, t1);
+        let r12: Vec3 = p2 - p1;
+        let l: f32 = r12.length();
 
-            p2 = form_2.point(s2, t2);
-            n2 = form_2.normal(s2, t2);
+        let mut cos_beta1: f32 = r12.dot(&n1) / l;
+        let mut cos_beta2: f32 = (-r12).dot(&n2) / l;
 
-            r12 = p2 - p1;
-            l = r12.length();
+        // Both points may lie on the same (concave) form: hits closer
+        // than RAY_EPS are the ray leaving the surface at p1.
+        let ray: Ray = Ray::new(p1, r12.unit_vector());
+        cos_beta2 = match self.hit_surfaces(&ray, RAY_EPS, l, &mut hit_rec) {
+            true => match &hit_rec {
+                Some(rec) => match rec.hit_elem == form_2_idx {
+                    true => cos_beta2,
+                    false => 0e0,
+                }
+                None => 0e0,
+            }
+            false => {
+                cos_beta2
+            }
+        };
+
+        // Gas attenuation along the line of sight.
+        let tau: f32 = self.transmittance(&ray, 0e0, l);
+
+        if cos_beta1 < 0e0 || cos_beta2 < 0e0 {
+            cos_beta1 = 0e0
+        }
+        let da1: f32 = form_1.diff_a(s1, t1);
+        let da2: f32 = form_2.diff_a(s2, t2);
+        tau * cos_beta1 * cos_beta2 / l.powi(2) * da1 * da2 / PI / form_1.area()
+    }
+
+    /// One cosine weighted ray shot from the form, drawing the point
+    /// $(s, t)$ and the direction from the current sample of ``sampler``.
+    /// Returns the form hit first on its front side, if any, and the
+    /// sample of its view factor.
+    fn shooting_sample(&self, form_idx: usize, sampler: &mut Sampler) -> Option<(usize, f32)> {
+        let form: &Form = self.forms.get(form_idx).unwrap();
+        let (s, t) = sampler.next_2d();
+        let direction: (f32, f32) = sampler.next_2d();
+        let (rec, tau) = self.shoot_cosine(form.point(s, t), form.normal(s, t), direction)?;
+        // Points are uniform on (s, t), weighted by their area.
+        Some((rec.hit_elem, tau * form.diff_a(s, t) / form.area()))
+    }
+
+    /// Shoots a cosine weighted ray from ``origin`` around ``normal``,
+    /// the direction drawn from the uniform pair ``(u1, u2)``.
+    /// Returns the first surface hit on its front side, if any, and the
+    /// transmittance of the participating media along the way.
+    pub(crate) fn shoot_cosine(&self, origin: Vec3, normal: Vec3, uv: (f32, f32)) -> Option<(HitRecord, f32)> {
+        self.shoot(&Ray::new(origin, cosine_direction(normal, uv)))
+    }
 
-            cos_beta1 = r12.dot(&n1) / l;
-            cos_beta2 = (-r12).dot(&n2) / l;
+    /// First surface hit by ``ray`` on its front side, if any, and the
+    /// transmittance of the participating media along the way.
+    pub(crate) fn shoot(&self, ray: &Ray) -> Option<(HitRecord, f32)> {
+        let mut hit_rec: Option<HitRecord> = None;
+        if !self.hit_surfaces(ray, RAY_EPS, f32::MAX, &mut hit_rec) {
+            return None;
+        }
+        let rec: HitRecord = hit_rec?;
+        // Hits on the back side of a surface are blocked.
+        match self.forms[rec.hit_elem].outward_normal(rec.p).dot(&ray.direction()) < 0e0 {
+            true => {
+                let tau: f32 = self.transmittance(ray, 0e0, rec.t);
+                Some((rec, tau))
+            }
+            false => None,
+        }
+    }
 
-            ray = Ray::new(p1, r12.unit_vector());
-            cos_beta2 = match self.hit(&ray, 1e-8, l, &mut hit_rec) {
-                true => match &hit_rec {
-                    Some(rec) => match rec.hit_elem == form_2_idx {
-                        true => cos_beta2,
-                        false => 0e0,
+    /// Estimates of $F_{ij}$ for every $j$ in ``targets``, sampled in
+    /// batches until ``target`` is met by all of them. Area integration
+    /// samples each pair on its own, ray shooting samples them at once.
+    pub(crate) fn estimate_row(&self, form_idx: usize, targets: &[usize], method: VfMethod, target: &VfTarget) -> Vec<VfEstimate> {
+        let mut estimates: Vec<VfEstimate> = vec![VfEstimate::new(); targets.len()];
+        match method {
+            VfMethod::AreaIntegration => {
+                for (estimate, j) in estimates.iter_mut().zip(targets.iter()) {
+                    let mut sampler: Sampler = Sampler::new(SamplerKind::Sobol, target.max_samples);
+                    while target.needs_samples(estimate) {
+                        for _ in 0..target.batch(estimate.samples()) {
+                            sampler.start_sample(estimate.samples());
+                            estimate.add(self.area_sample(form_idx, *j, &mut sampler));
+                        }
                     }
-                    None => 0e0,
                 }
-                false => {
-                    cos_beta2
+            }
+            VfMethod::RayShooting => {
+                let mut sampler: Sampler = Sampler::new(SamplerKind::Sobol, target.max_samples);
+                let mut n: usize = 0;
+                while estimates.iter().any(|estimate| target.needs_samples(estimate)) {
+                    for _ in 0..target.batch(n) {
+                        sampler.start_sample(n);
+                        let hit: Option<(usize, f32)> = self.shooting_sample(form_idx, &mut sampler);
+                        for (estimate, j) in estimates.iter_mut().zip(targets.iter()) {
+                            estimate.add(match hit {
+                                Some((k, weight)) if k == *j => weight,
+                                _ => 0e0,
+                            });
+                        }
+                        n += 1;
+                    }
                 }
-            };
-
-            if cos_beta1 < 0e0 || cos_beta2 < 0e0 {
-                cos_beta1 = 0e0
             }
-            da1 = form_1.diff_a(s1, t1);
-            da2 = form_2.diff_a(s2, t2);
-            // println!("C1: {}, C2: {}, L: {}", cos_beta1, cos_beta2, l);
-            temp = temp + cos_beta1 * cos_beta2 / l.powi(2) * da1 * da2;
         }
+        estimates
+    }
+}
+
+/// Cosine weighted direction around ``normal`` (Malley's method),
+/// drawn from the uniform pair ``(u1, u2)``.
+pub(crate) fn cosine_direction(normal: Vec3, (u1, u2): (f32, f32)) -> Vec3 {
+    let (u, v) = normal.orthonormal_basis();
+    let disk: Vec3 = sample_unit_disk(u1, u2);
+    let z: f32 = (1e0 - disk.x().powi(2) - disk.y().powi(2)).max(0e0).sqrt();
+    u * disk.x() + v * disk.y() + normal * z
+}
+
+/// Two sided confidence interval factor of the normal distribution
+/// at a 95% level.
+pub const Z_95: f32 = 1.96;
 
-        return temp / PI / form_1.area() / (n as f32);
+/// Batch size and minimum number of samples of `VfTarget::new`.
+const VF_BATCH: usize = 1024;
+
+/// Monte Carlo estimate of a view factor: running mean and variance
+/// of its samples (Welford's algorithm).
+#[derive(Copy, Clone, Debug)]
+pub struct VfEstimate {
+    /// Number of samples.
+    n: usize,
+    /// Mean of the samples.
+    mean: f32,
+    /// Sum of squared deviations from the mean.
+    m2: f32,
+}
+
+impl Default for VfEstimate {
+    fn default() -> VfEstimate {
+        VfEstimate::new()
     }
+}
 
-    fn view_factors(&self, n: usize) -> Vfs {
-        let n_objs: usize = self.forms.len();
-        let mut viewfactors: Vec<Vec<f32>> = Vec::new();
+impl VfEstimate {
+    /// Empty estimate.
+    pub fn new() -> VfEstimate {
+        VfEstimate { n: 0, mean: 0e0, m2: 0e0 }
+    }
 
-        for i in 0..n_objs {
-            let mut views_i: Vec<f32> = Vec::new();
-
-            for j in (i+1)..n_objs {
-                views_i.push(
-                    self.view_factor(
-                        n,
-                        i,
-                        j
-                    )
-                );
-            }
+    /// Adds a sample.
+    pub fn add(&mut self, x: f32) {
+        self.n += 1;
+        let delta: f32 = x - self.mean;
+        self.mean += delta / self.n as f32;
+        self.m2 += delta * (x - self.mean);
+    }
 
-            viewfactors.push(views_i);
+    /// Number of samples.
+    pub fn samples(&self) -> usize { self.n }
+
+    /// Estimated view factor.
+    pub fn value(&self) -> f32 { self.mean }
+
+    /// Standard error of the estimate, $\sqrt{s^2/n}$. With low
+    /// discrepancy samples it overestimates the actual error.
+    pub fn std_error(&self) -> f32 {
+        match self.n > 1 {
+            true => (self.m2 / (self.n - 1) as f32 / self.n as f32).sqrt(),
+            false => f32::INFINITY,
         }
-        Vfs::from(viewfactors)
+    }
+
+    /// Standard error relative to the estimate.
+    pub fn relative_error(&self) -> f32 {
+        self.std_error() / self.mean.abs()
+    }
+
+    /// Confidence interval $F \pm z\,\sigma$, clipped to $[0, 1]$.
+    ///
+    /// # Parameters:
+    /// * `z` - Normal distribution factor, e.g. `Z_95`.
+    pub fn confidence_interval(&self, z: f32) -> (f32, f32) {
+        let half: f32 = z * self.std_error();
+        ((self.mean - half).max(0e0), (self.mean + half).min(1e0))
+    }
+}
+
+/// Convergence target of view factor estimates. Sampling goes on, in
+/// batches, until the standard error is below ``absolute`` or below
+/// ``relative`` times the estimate, or ``max_samples`` are taken.
+#[derive(Copy, Clone)]
+pub struct VfTarget {
+    /// Absolute standard error target.
+    pub absolute: f32,
+    /// Relative standard error target.
+    pub relative: f32,
+    /// Samples taken before the first convergence check, also the
+    /// batch size between checks.
+    pub min_samples: usize,
+    /// Maximum number of samples.
+    pub max_samples: usize,
+}
+
+impl VfTarget {
+    /// VfTarget constructor, checking every 1024 samples.
+    ///
+    /// # Parameters:
+    /// * `absolute` - Absolute standard error target (0 to disable it).
+    /// * `relative` - Relative standard error target (0 to disable it).
+    /// * `max_samples` - Maximum number of samples.
+    pub fn new(absolute: f32, relative: f32, max_samples: usize) -> VfTarget {
+        VfTarget { absolute, relative, min_samples: VF_BATCH.min(max_samples), max_samples }
+    }
+
+    /// Exactly ``n`` samples, whatever the error.
+    pub fn fixed(n: usize) -> VfTarget {
+        VfTarget { absolute: 0e0, relative: 0e0, min_samples: n, max_samples: n }
+    }
+
+    /// Whether the estimate meets the target.
+    pub fn converged(&self, estimate: &VfEstimate) -> bool {
+        let error: f32 = estimate.std_error();
+        error <= self.absolute || error <= self.relative * estimate.value().abs()
+    }
+
+    /// Whether sampling of the estimate must go on, never past
+    /// ``max_samples``.
+    pub fn needs_samples(&self, estimate: &VfEstimate) -> bool {
+        let n: usize = estimate.samples();
+        match n < self.max_samples {
+            true => n < self.min_samples || !self.converged(estimate),
+            false => false,
+        }
+    }
+
+    /// Size of the next batch after ``n`` samples.
+    fn batch(&self, n: usize) -> usize {
+        self.min_samples.max(1).min(self.max_samples.saturating_sub(n))
+    }
+}
+
+/// View factor estimates of the world forms, stored as `Vfs`:
+/// ``estimates[i][j - i - 1]`` is the estimate of $F_{ij}$, $j > i$.
+pub struct VfEstimates {
+    pub estimates: Vec<Vec<VfEstimate>>,
+    /// Estimates of $F_{ii}$ on the forms that see themselves.
+    pub self_estimates: Vec<Option<VfEstimate>>
+}
+
+impl VfEstimates {
+    /// View factor values.
+    pub fn vfs(&self) -> Vfs {
+        Vfs::with_self(
+            self.estimates.iter().map(|row| row.iter().map(|e| e.value()).collect()).collect(),
+            self.self_estimates.iter().map(|e| e.map_or(0e0, |e| e.value())).collect()
+        )
+    }
+
+    /// Total number of samples taken.
+    pub fn samples(&self) -> usize {
+        self.estimates.iter().flatten().chain(self.self_estimates.iter().flatten()).map(|e| e.samples()).sum()
+    }
+}
+
+/// Display trait implementation on VfEstimates.
+impl Display for VfEstimates {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        for (i, row) in self.estimates.iter().enumerate() {
+            let self_estimate: Option<(usize, &VfEstimate)> = self.self_estimates.get(i).and_then(|e| e.as_ref()).map(|e| (i, e));
+            for (j, estimate) in self_estimate.into_iter().chain(row.iter().enumerate().map(|(k, e)| (k + i + 1, e))) {
+                writeln!(
+                    f,
+                    "F({},{}) = {:.4} ± {:.4} ({} samples)",
+                    i,
+                    j,
+                    estimate.value(),
+                    Z_95 * estimate.std_error(),
+                    estimate.samples())?;
+            }
+        }
+        Ok(())
     }
 }
 
@@ -159,6 +580,9 @@ impl Display for Vfs {
         let mut vjs: &Vec<f32>;
         for i in 0..n {
             vjs = self.vfs.get(i).unwrap();
+            if let Some(f_ii) = self.self_vfs.get(i).filter(|f_ii| **f_ii > 0e0) {
+                writeln!(f, "F({},{}) = {:.4}", i, i, f_ii)?;
+            }
             for j in 0..(n-i-1) {
                 writeln!(
                     f,
@@ -1,14 +1,15 @@
 //! Ray class implementation.
 //! Includes basic ray functions and ray spawning methods for
 //! getting the ray color.
-use std::borrow::{BorrowMut};
-
 extern crate rand;
+use self::rand::random;
 
 use crate::vectors::{Vec3, Vec3Methods};
 use crate::objects::{HittableList};
 use crate::hittable::{HitRecord, Hittable};
-use crate::materials::{Material, MaterialScatter};
+use crate::materials::{Material, MaterialScatter, MaterialEmit};
+use crate::spectrum::Spectrum;
+use crate::integrator::{PathSettings, PathEnd};
 
 /// Background color.
 const BACKGROUND_COLOR: Vec3 = Vec3::new_const(5e-1, 7e-1, 1e0);
@@ -18,7 +19,11 @@ pub struct Ray{
     /// Ray origin.
     pub a: Vec3,
     /// Ray direction.
-    pub b: Vec3
+    pub b: Vec3,
+    /// Wavelength (nm) carried by monochromatic rays, ``None`` for RGB rays.
+    pub wavelength: Option<f32>,
+    /// Time at which the ray travels, moving objects are intersected at this time.
+    pub time: f32
 }
 
 /// Ray member functions.
@@ -29,7 +34,24 @@ impl Ray{
     ///
     /// * `a` - Ray origin.
     /// * `b` - Ray direction.
-    pub fn new(a: Vec3, b: Vec3) -> Ray{ Ray {a, b } }
+    pub fn new(a: Vec3, b: Vec3) -> Ray{ Ray {a, b, wavelength: None, time: 0e0 } }
+
+    /// Ray constructor at a given time.
+    ///
+    /// # Parameters:
+    ///
+    /// * `a` - Ray origin.
+    /// * `b` - Ray direction.
+    /// * `time` - Ray time.
+    pub fn timed(a: Vec3, b: Vec3, time: f32) -> Ray{ Ray {a, b, wavelength: None, time } }
+
+    /// New ray sharing the wavelength and time of the current one.
+    ///
+    /// # Parameters:
+    ///
+    /// * `a` - Ray origin.
+    /// * `b` - Ray direction.
+    pub fn spawn(&self, a: Vec3, b: Vec3) -> Ray{ Ray {a, b, wavelength: self.wavelength, time: self.time } }
 
     /// Getter for ray origin.
     pub fn origin(&self) -> Vec3{ self.a }
@@ -44,7 +66,8 @@ impl Ray{
     /// Get the ray color.
     ///
     /// If the ray hits a world object, a new ray is spawned (based on the hit material)
-    /// at least ``max_depth`` times.
+    /// at least ``max_depth`` times. Monochromatic rays return the radiance at
+    /// their wavelength on the three channels.
     ///
     /// # Parameters:
     ///
@@ -57,31 +80,69 @@ impl Ray{
     ///
     /// * `Vec3` - color of the ray after object reflections and refractions.
     pub fn color(&self, world: &HittableList, depth: usize, max_depth: usize) -> Vec3 {
-        let mut rec: Option<HitRecord> = None;
+        self.trace(world, &PathSettings::fixed(max_depth.saturating_sub(depth))).0
+    }
 
-        // Hit something on World.
-        if world.hit(self, 1e-3, std::f32::MAX, &mut rec.borrow_mut()) {
-            let mut scattered: Ray = Ray::new(Vec3::new(0e0, 0e0, 0e0), Vec3::new(0e0, 0e0, 0e0));
-            let mut attenuation: Vec3 = Vec3::new(0e0, 0e0, 0e0);
+    /// Get the ray color following a single path, without recursion.
+    ///
+    /// The path throughput $\beta$ (product of the material attenuations)
+    /// weights the emission found at each vertex. With Russian roulette,
+    /// after ``settings.rr_depth`` scattering events the path survives with
+    /// probability $q=\min(\max_c\beta_c, q_{max})$ and its throughput is
+    /// divided by $q$, so that the expected radiance is unchanged.
+    ///
+    /// # Parameters:
+    ///
+    /// * `self` - camera ray.
+    /// * `world` - world of objects where the ray may hit.
+    /// * `settings` - maximum depth and Russian roulette settings.
+    ///
+    /// # Returns:
+    ///
+    /// * `(Vec3, usize, PathEnd)` - radiance, number of scattering events
+    ///   and reason why the path ended.
+    pub fn trace(&self, world: &HittableList, settings: &PathSettings) -> (Vec3, usize, PathEnd) {
+        let mut radiance: Vec3 = Vec3::zeros();
+        let mut throughput: Vec3 = Vec3::ones();
+        let mut ray: Ray = self.spawn(self.origin(), self.direction());
+        let mut depth: usize = 0;
+        loop {
+            let mut rec: Option<HitRecord> = None;
+            // Nothing hit, background.
+            if !world.hit(&ray, 1e-3, f32::MAX, &mut rec) {
+                return (radiance + ray.background() * throughput, depth, PathEnd::Escaped);
+            }
             let hit_rec: HitRecord = rec.unwrap();
-            let mat: Material = hit_rec.material;
-            let do_scatter: bool = mat.scatter(&self, &hit_rec, &mut attenuation, &mut scattered);
+            let mat: &Material = &hit_rec.material;
+            radiance += mat.emitted(&ray, &hit_rec) * throughput;
+            if depth >= settings.max_depth {
+                return (radiance, depth, PathEnd::MaxDepth);
+            }
 
-            // New object hit, return color.
-            if depth < max_depth && do_scatter {
-                return scattered.color(world, depth+1, max_depth) * attenuation;
+            let mut scattered: Ray = ray.spawn(Vec3::zeros(), Vec3::zeros());
+            let mut attenuation: Vec3 = Vec3::zeros();
+            if !mat.scatter(&ray, &hit_rec, &mut attenuation, &mut scattered) {
+                return (radiance, depth, PathEnd::Absorbed);
             }
-            // Either max_depth reached or no-hit, return darkness.
-            else {
-                return Vec3::zeros();
+            throughput *= attenuation;
+            depth += 1;
+
+            let survival: f32 = settings.survival(depth, throughput.max());
+            if survival < 1e0 {
+                if random::<f32>() >= survival {
+                    return (radiance, depth, PathEnd::Roulette);
+                }
+                throughput /= survival;
             }
+            ray = scattered;
         }
-        // Background gradient color.
-        else {
-            let unit_direction: Vec3 = self.direction().unit_vector();
-            let t: f32 = 5e-1 * (unit_direction.y() + 1e0);
-            let col: Vec3 = Vec3::ones() * (1e0 - t) + BACKGROUND_COLOR * t;
-            return col;
-        }
+    }
+
+    /// Background gradient color seen by the ray.
+    fn background(&self) -> Vec3 {
+        let unit_direction: Vec3 = self.direction().unit_vector();
+        let t: f32 = 5e-1 * (unit_direction.y() + 1e0);
+        let col: Vec3 = Vec3::ones() * (1e0 - t) + BACKGROUND_COLOR * t;
+        Spectrum::Rgb(col).value(self.wavelength)
     }
 }
\ No newline at end of file
@@ -0,0 +1,229 @@
+//! Sampler module. Sequences of sample points on the unit hypercube
+//! used by the Monte Carlo estimators (pixel jitter, lens sampling
+//! and view factors). Stratified and low-discrepancy sequences cover
+//! the domain more evenly than independent random numbers, so that
+//! the estimators converge faster.
+
+extern crate rand;
+use self::rand::random;
+
+/// Number of dimensions with tabulated Sobol direction numbers.
+const SOBOL_DIMENSIONS: usize = 8;
+
+/// Sobol primitive polynomials and initial direction numbers
+/// ``(s, a, m)`` of dimensions 2 to 8 (Joe and Kuo, 2008).
+/// The first dimension is the van der Corput sequence.
+const SOBOL_POLYNOMIALS: [(u32, u32, [u32; 5]); SOBOL_DIMENSIONS - 1] = [
+    (1, 0, [1, 0, 0, 0, 0]),
+    (2, 1, [1, 3, 0, 0, 0]),
+    (3, 1, [1, 3, 1, 0, 0]),
+    (3, 2, [1, 1, 1, 0, 0]),
+    (4, 1, [1, 1, 3, 3, 0]),
+    (4, 4, [1, 3, 5, 13, 0]),
+    (5, 2, [1, 1, 5, 5, 17]),
+];
+
+/// Halton sequence bases, one prime per dimension.
+const PRIMES: [u32; 16] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53];
+
+/// Sample point generators.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum SamplerKind {
+    /// Independent uniform random numbers.
+    Independent,
+    /// Jittered strata, randomly permuted on each dimension
+    /// (Latin hypercube), for a known number of samples.
+    Stratified,
+    /// Halton sequence with a random shift (Cranley-Patterson rotation)
+    /// per dimension.
+    Halton,
+    /// Sobol sequence with Owen scrambling (Laine-Karras hash).
+    Sobol,
+}
+
+/// Generator of the sample points of one estimator. Each sample is a
+/// point whose coordinates are drawn one dimension at a time; dimensions
+/// beyond the tabulated ones fall back to independent random numbers.
+pub struct Sampler {
+    /// Generator type.
+    kind: SamplerKind,
+    /// Number of samples that will be drawn.
+    n_samples: usize,
+    /// Current sample index.
+    index: usize,
+    /// Next dimension of the current sample.
+    dim: usize,
+    /// Random scrambling seed of each dimension.
+    seeds: Vec<u32>,
+    /// Random permutation of the strata of each dimension (stratified sampler).
+    strata: Vec<Vec<u32>>,
+    /// Direction numbers of each dimension (Sobol sampler).
+    directions: Vec<[u32; 32]>,
+}
+
+impl Sampler {
+    /// Sampler constructor.
+    ///
+    /// # Parameters:
+    /// * `kind` - Generator type.
+    /// * `n_samples` - Number of samples that will be drawn (strata count).
+    pub fn new(kind: SamplerKind, n_samples: usize) -> Sampler {
+        Sampler {
+            kind,
+            n_samples: n_samples.max(1),
+            index: 0,
+            dim: 0,
+            seeds: Vec::new(),
+            strata: Vec::new(),
+            directions: Vec::new(),
+        }
+    }
+
+    /// Getter for the generator type.
+    pub fn kind(&self) -> SamplerKind { self.kind }
+
+    /// Starts the ``index``-th sample, from its first dimension.
+    pub fn start_sample(&mut self, index: usize) {
+        self.index = index;
+        self.dim = 0;
+    }
+
+    /// Next coordinate of the current sample, from 0 to 1.
+    pub fn next_1d(&mut self) -> f32 {
+        let dim: usize = self.dim;
+        self.dim += 1;
+        while self.seeds.len() <= dim {
+            self.seeds.push(random::<u32>());
+        }
+        match self.kind {
+            SamplerKind::Independent => random::<f32>(),
+            SamplerKind::Stratified => self.stratified(dim),
+            SamplerKind::Halton => match dim < PRIMES.len() {
+                true => {
+                    let shift: f32 = to_unit(self.seeds[dim]);
+                    let x: f32 = radical_inverse(self.index as u32, PRIMES[dim]) + shift;
+                    wrap(x)
+                }
+                false => random::<f32>(),
+            },
+            SamplerKind::Sobol => match dim < SOBOL_DIMENSIONS {
+                true => {
+                    while self.directions.len() <= dim {
+                        let d: usize = self.directions.len();
+                        self.directions.push(sobol_directions(d));
+                    }
+                    let x: u32 = sobol(self.index as u32, &self.directions[dim]);
+                    to_unit(owen_scramble(x, self.seeds[dim]))
+                }
+                false => random::<f32>(),
+            },
+        }
+    }
+
+    /// Next two coordinates of the current sample.
+    pub fn next_2d(&mut self) -> (f32, f32) {
+        let x: f32 = self.next_1d();
+        let y: f32 = self.next_1d();
+        (x, y)
+    }
+
+    /// Jittered sample on a randomly permuted stratum of ``dim``.
+    fn stratified(&mut self, dim: usize) -> f32 {
+        if self.index >= self.n_samples {
+            return random::<f32>();
+        }
+        while self.strata.len() <= dim {
+            let mut perm: Vec<u32> = (0..self.n_samples as u32).collect();
+            // Fisher-Yates shuffle.
+            for i in (1..perm.len()).rev() {
+                let j: usize = (random::<f32>() * (i + 1) as f32) as usize;
+                perm.swap(i, j.min(i));
+            }
+            self.strata.push(perm);
+        }
+        let stratum: u32 = self.strata[dim][self.index];
+        ((stratum as f32 + random::<f32>()) / self.n_samples as f32).min(ONE_MINUS_EPSILON)
+    }
+}
+
+/// Largest float below 1.
+const ONE_MINUS_EPSILON: f32 = 1e0 - f32::EPSILON / 2e0;
+
+/// Fraction from 0 to 1 of a 32 bit integer.
+fn to_unit(x: u32) -> f32 {
+    (x >> 8) as f32 / (1u32 << 24) as f32
+}
+
+/// Fractional part of ``x`` from 0 to 1.
+fn wrap(x: f32) -> f32 {
+    (x - x.floor()).min(ONE_MINUS_EPSILON)
+}
+
+/// Radical inverse of ``index`` in ``base``: its digits mirrored
+/// about the radix point.
+pub fn radical_inverse(mut index: u32, base: u32) -> f32 {
+    let inv_base: f32 = 1e0 / base as f32;
+    let mut inv_base_n: f32 = 1e0;
+    let mut reversed: f32 = 0e0;
+    while index > 0 {
+        let digit: u32 = index % base;
+        index /= base;
+        inv_base_n *= inv_base;
+        reversed += digit as f32 * inv_base_n;
+    }
+    reversed.min(ONE_MINUS_EPSILON)
+}
+
+/// Direction numbers of the ``dim``-th (from 0) Sobol dimension.
+fn sobol_directions(dim: usize) -> [u32; 32] {
+    let mut v: [u32; 32] = [0; 32];
+    if dim == 0 {
+        for (k, vk) in v.iter_mut().enumerate() {
+            *vk = 1u32 << (31 - k);
+        }
+        return v;
+    }
+    let (s, a, m) = SOBOL_POLYNOMIALS[dim - 1];
+    let s: usize = s as usize;
+    for k in 0..32 {
+        v[k] = match k < s {
+            true => m[k] << (31 - k),
+            false => {
+                let mut vk: u32 = v[k - s] ^ (v[k - s] >> s);
+                for l in 1..s {
+                    if (a >> (s - 1 - l)) & 1 == 1 {
+                        vk ^= v[k - l];
+                    }
+                }
+                vk
+            }
+        };
+    }
+    v
+}
+
+/// ``index``-th point of a Sobol dimension, as a 32 bit fraction.
+fn sobol(mut index: u32, directions: &[u32; 32]) -> u32 {
+    let mut x: u32 = 0;
+    let mut k: usize = 0;
+    while index > 0 {
+        if index & 1 == 1 {
+            x ^= directions[k];
+        }
+        index >>= 1;
+        k += 1;
+    }
+    x
+}
+
+/// Nested uniform (Owen) scrambling of a 32 bit fraction
+/// (Burley, 2020, with the Laine-Karras hash).
+fn owen_scramble(x: u32, seed: u32) -> u32 {
+    let mut v: u32 = x.reverse_bits();
+    v = v.wrapping_add(seed);
+    v ^= v.wrapping_mul(0x6c50_b47c);
+    v ^= v.wrapping_mul(0xb82f_1e52);
+    v ^= v.wrapping_mul(0xc7af_e638);
+    v ^= v.wrapping_mul(0x8d22_f6e6);
+    v.reverse_bits()
+}
@@ -0,0 +1,220 @@
+//! Spectrum module. Wavelength sampling and conversion of
+//! monochromatic radiance samples to RGB colors.
+//!
+//! Colors are computed from the CIE 1931 color matching functions
+//! (multi-lobe analytic fit by Wyman, Sloan and Shirley, 2013) and the
+//! linear sRGB primaries. RGB weights are normalized so that an
+//! equal-energy spectrum maps to white, $(1, 1, 1)$.
+
+use crate::vectors::{Vec3, Vec3Methods};
+
+/// Minimum sampled wavelength (nm).
+pub const LAMBDA_MIN: f32 = 380e0;
+/// Maximum sampled wavelength (nm).
+pub const LAMBDA_MAX: f32 = 780e0;
+/// Reference wavelength (nm), sodium D line.
+pub const LAMBDA_D: f32 = 589.3;
+
+/// Mean value of the linear sRGB color matching functions
+/// over $[\lambda_{min}, \lambda_{max}]$.
+const RGB_MEAN: Vec3 = Vec3::new_const(0.320_902_56, 0.253_845_2, 0.242_662);
+
+/// Piecewise gaussian lobe.
+fn lobe(lambda: f32, mu: f32, sigma_1: f32, sigma_2: f32) -> f32 {
+    let sigma: f32 = match lambda < mu {
+        true => sigma_1,
+        false => sigma_2,
+    };
+    (-5e-1 * ((lambda - mu) / sigma).powi(2)).exp()
+}
+
+/// CIE 1931 2° color matching functions $\bar x$, $\bar y$, $\bar z$.
+///
+/// # Parameters:
+/// * `lambda` - Wavelength (nm).
+pub fn cie_xyz(lambda: f32) -> Vec3 {
+    Vec3::new(
+        1.056 * lobe(lambda, 599.8, 37.9, 31.0)
+            + 0.362 * lobe(lambda, 442.0, 16.0, 26.7)
+            - 0.065 * lobe(lambda, 501.1, 20.4, 26.2),
+        0.821 * lobe(lambda, 568.8, 46.9, 40.5)
+            + 0.286 * lobe(lambda, 530.9, 16.3, 31.1),
+        1.217 * lobe(lambda, 437.0, 11.8, 36.0)
+            + 0.681 * lobe(lambda, 459.0, 26.0, 13.8)
+    )
+}
+
+/// CIE XYZ to linear sRGB (D65) conversion.
+pub fn xyz_to_rgb(xyz: Vec3) -> Vec3 {
+    Vec3::new(
+        3.240_454_2 * xyz.x() - 1.537_138_5 * xyz.y() - 0.498_531_4 * xyz.z(),
+        -0.969_266 * xyz.x() + 1.876_010_8 * xyz.y() + 0.041_556 * xyz.z(),
+        0.055_643_4 * xyz.x() - 0.204_025_9 * xyz.y() + 1.057_225_2 * xyz.z()
+    )
+}
+
+/// Uniform wavelength sample on $[\lambda_{min}, \lambda_{max}]$.
+///
+/// # Parameters:
+/// * `xi` - Uniform random number from 0 to 1.
+///
+/// # Returns:
+/// * `(f32, f32)` - Wavelength (nm) and its probability density (1/nm).
+pub fn sample_wavelength(xi: f32) -> (f32, f32) {
+    (LAMBDA_MIN + xi * (LAMBDA_MAX - LAMBDA_MIN), 1e0 / (LAMBDA_MAX - LAMBDA_MIN))
+}
+
+/// RGB weight of a monochromatic sample already divided by the
+/// uniform wavelength pdf, so that averaging it over the sampled
+/// wavelengths gives white:
+/// $$\mathbb{E}_\lambda\left[w(\lambda)\right]=(1, 1, 1)$$
+pub fn wavelength_to_rgb(lambda: f32) -> Vec3 {
+    let rgb: Vec3 = xyz_to_rgb(cie_xyz(lambda));
+    Vec3::new(rgb.r() / RGB_MEAN.r(), rgb.g() / RGB_MEAN.g(), rgb.b() / RGB_MEAN.b())
+}
+
+/// Number of tabulated values on sampled spectra.
+pub const SPECTRUM_SAMPLES: usize = 32;
+
+/// Inverse of the RGB response of the red, green and blue
+/// upsampling bands, $[590, 780]$, $[490, 590)$ and $[380, 490)$ nm.
+const RGB_TO_BANDS: [[f32; 3]; 3] = [
+    [1.013_222_6, -0.004_638_46, -0.008_584_19],
+    [0.015_381_92, 0.945_632_36, 0.038_985_71],
+    [0.023_857_8, 0.045_644_12, 0.930_498_1],
+];
+
+/// Spectral distribution of a reflectance, transmittance or radiance.
+#[derive(Copy, Clone)]
+pub enum Spectrum {
+    /// Wavelength independent value.
+    Constant(f32),
+    /// RGB color, upsampled to a piecewise constant spectrum whose
+    /// color is exactly the given one.
+    Rgb(Vec3),
+    /// Values tabulated at evenly spaced wavelengths between
+    /// $\lambda_{min}$ and $\lambda_{max}$, linearly interpolated,
+    /// and their color (see `Spectrum::from_samples`).
+    Sampled { values: [f32; SPECTRUM_SAMPLES], rgb: Vec3 },
+    /// Black body radiation at ``temperature`` (K), scaled so that
+    /// its peak value is ``scale``, and its color (see `Spectrum::blackbody`).
+    Blackbody { temperature: f32, scale: f32, rgb: Vec3 },
+}
+
+impl Spectrum {
+    /// Sampled spectrum from a list of ``(wavelength, value)`` pairs
+    /// sorted by wavelength (nm). Values are clamped outside the given range.
+    pub fn from_samples(samples: &[(f32, f32)]) -> Spectrum {
+        let mut values: [f32; SPECTRUM_SAMPLES] = [0e0; SPECTRUM_SAMPLES];
+        for (i, value) in values.iter_mut().enumerate() {
+            let lambda: f32 = LAMBDA_MIN + (LAMBDA_MAX - LAMBDA_MIN) * (i as f32) / ((SPECTRUM_SAMPLES - 1) as f32);
+            *value = interpolate(samples, lambda);
+        }
+        Spectrum::Sampled { values, rgb: Vec3::zeros() }.with_rgb()
+    }
+
+    /// Black body spectrum at ``temperature`` (K), scaled so that its
+    /// peak value is ``scale``.
+    pub fn blackbody(temperature: f32, scale: f32) -> Spectrum {
+        Spectrum::Blackbody { temperature, scale, rgb: Vec3::zeros() }.with_rgb()
+    }
+
+    /// Value at wavelength ``lambda`` (nm).
+    pub fn at(&self, lambda: f32) -> f32 {
+        match self {
+            Spectrum::Constant(value) => *value,
+            Spectrum::Rgb(rgb) => {
+                let band: usize = match lambda {
+                    l if l >= 590e0 => 0,
+                    l if l >= 490e0 => 1,
+                    _ => 2,
+                };
+                let row: [f32; 3] = RGB_TO_BANDS[band];
+                row[0] * rgb.r() + row[1] * rgb.g() + row[2] * rgb.b()
+            }
+            Spectrum::Sampled { values, .. } => {
+                let x: f32 = (lambda - LAMBDA_MIN) / (LAMBDA_MAX - LAMBDA_MIN) * ((SPECTRUM_SAMPLES - 1) as f32);
+                let x: f32 = x.max(0e0).min((SPECTRUM_SAMPLES - 1) as f32);
+                let i: usize = (x as usize).min(SPECTRUM_SAMPLES - 2);
+                let f: f32 = x - i as f32;
+                values[i] * (1e0 - f) + values[i + 1] * f
+            }
+            Spectrum::Blackbody { temperature, scale, .. } => {
+                let peak: f32 = 2.897_772e6 / temperature;
+                scale * planck(lambda, *temperature) / planck(peak, *temperature)
+            }
+        }
+    }
+
+    /// Value seen by a ray: the color of the spectrum for white rays
+    /// (see `Spectrum::to_rgb`) or the value at the ray's wavelength,
+    /// repeated on the three channels.
+    ///
+    /// # Parameters:
+    /// * `wavelength` - Ray's wavelength (nm), if any.
+    pub fn value(&self, wavelength: Option<f32>) -> Vec3 {
+        match wavelength {
+            Some(lambda) => Vec3::ones() * self.at(lambda),
+            None => self.to_rgb(),
+        }
+    }
+
+    /// Linear sRGB color of the spectrum, so that a constant
+    /// spectrum of value 1 is white.
+    pub fn to_rgb(&self) -> Vec3 {
+        match self {
+            Spectrum::Constant(value) => Vec3::ones() * *value,
+            Spectrum::Rgb(rgb) => *rgb,
+            Spectrum::Sampled { rgb, .. } | Spectrum::Blackbody { rgb, .. } => *rgb,
+        }
+    }
+
+    /// Same spectrum, with its color integrated from the color
+    /// matching functions.
+    fn with_rgb(self) -> Spectrum {
+        let n: usize = 4 * SPECTRUM_SAMPLES;
+        let mut color: Vec3 = Vec3::zeros();
+        for i in 0..n {
+            let (lambda, _pdf) = sample_wavelength((i as f32 + 5e-1) / (n as f32));
+            color += wavelength_to_rgb(lambda) * self.at(lambda);
+        }
+        color /= n as f32;
+        match self {
+            Spectrum::Sampled { values, .. } => Spectrum::Sampled { values, rgb: color },
+            Spectrum::Blackbody { temperature, scale, .. } => Spectrum::Blackbody { temperature, scale, rgb: color },
+            spectrum => spectrum,
+        }
+    }
+}
+
+/// Planck's law, spectral radiance of a black body (arbitrary units).
+///
+/// # Parameters:
+/// * `lambda` - Wavelength (nm).
+/// * `temperature` - Temperature (K).
+fn planck(lambda: f32, temperature: f32) -> f32 {
+    // Second radiation constant hc/k (nm K).
+    let c2: f32 = 1.438_777e7;
+    let l: f32 = lambda * 1e-3;
+    1e0 / (l.powi(5) * ((c2 / (lambda * temperature)).exp() - 1e0))
+}
+
+/// Piecewise linear interpolation of sorted ``(x, y)`` pairs.
+fn interpolate(samples: &[(f32, f32)], x: f32) -> f32 {
+    match samples.len() {
+        0 => return 0e0,
+        1 => return samples[0].1,
+        _ => {}
+    }
+    if x <= samples[0].0 {
+        return samples[0].1;
+    }
+    for pair in samples.windows(2) {
+        let (x0, y0) = pair[0];
+        let (x1, y1) = pair[1];
+        if x <= x1 {
+            return y0 + (y1 - y0) * (x - x0) / (x1 - x0).max(1e-6);
+        }
+    }
+    samples[samples.len() - 1].1
+}
@@ -1,7 +1,33 @@
 mod test_patch_sphere;
+mod test_medium;
+mod test_microfacet;
+mod test_dielectric;
+mod test_spectrum;
+mod test_layered;
+mod test_cameras;
+mod test_postprocess;
+mod test_motion;
+mod test_animation;
+mod test_adaptive;
+mod test_samplers;
+mod test_integrator;
+mod test_aov;
+mod test_denoise;
+mod test_progressive;
+mod test_vf_methods;
+mod test_patches;
+mod test_exchange;
+mod test_thermal;
+mod test_orbital;
+mod test_network;
+mod test_export;
+mod test_self_view;
+mod test_analytic;
 
 use crate::objects::{HittableList, Form, Rectangle};
-use crate::materials::{Material, LambertianKind};
+use crate::materials::{Material, LambertianKind, MaterialScatter, MaterialPdf};
+use crate::hittable::HitRecord;
+use crate::rays::Ray;
 use crate::Vec3;
 use crate::radiation::{ViewFactors, Vfs};
 use std::ops::{AddAssign};
@@ -38,6 +64,45 @@ pub fn test_world() -> HittableList {
     world
 }
 
+/// Hit record at ``p`` on a surface facing +Y.
+pub fn hit_record(material: Material, p: Vec3) -> HitRecord {
+    HitRecord {
+        t: 1e0,
+        p,
+        normal: Vec3::new(0e0, 1e0, 0e0),
+        material,
+        hit_elem: 0
+    }
+}
+
+/// Mean sample weight (directional albedo) of the material hit at
+/// ``p``, over ``n`` scattered rays. Given ``err``, every sample weight
+/// must also equal ``eval / pdf`` at the sampled direction up to that
+/// relative error.
+pub fn albedo(material: Material, ray_in: &Ray, p: Vec3, n: usize, err: Option<f32>) -> Vec3 {
+    let rec: HitRecord = hit_record(material.clone(), p);
+    let mut attenuation: Vec3 = Vec3::zeros();
+    let mut scattered: Ray = Ray::new(Vec3::zeros(), Vec3::zeros());
+    let mut total: Vec3 = Vec3::zeros();
+
+    for _ in 0..n {
+        if !material.scatter(ray_in, &rec, &mut attenuation, &mut scattered) {
+            continue;
+        }
+        if let Some(err) = err {
+            let pdf: f32 = material.pdf(ray_in, &rec, scattered.direction());
+            let eval: Vec3 = material.eval(ray_in, &rec, scattered.direction());
+            assert!(pdf > 0e0);
+            for c in 0..3 {
+                let rel: f32 = (eval[c] / pdf - attenuation[c]).abs() / attenuation[c].max(1e-3);
+                assert!(rel < err, "weight {} vs eval/pdf {}", attenuation[c], eval[c] / pdf);
+            }
+        }
+        total += attenuation / (n as f32);
+    }
+    total
+}
+
 pub fn test_vf(n: usize) {
     let world: HittableList = test_world();
     let output: Vfs = world.view_factors(n);
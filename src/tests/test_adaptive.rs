@@ -0,0 +1,67 @@
+use crate::adaptive::{AdaptiveSampling, PixelEstimate};
+use crate::cameras::{Camera, CameraSimple};
+use crate::objects::{HittableList, Form, Sphere};
+use crate::materials::{Material, LambertianKind};
+use crate::film::Framebuffer;
+use crate::samplers::SamplerKind;
+use crate::vectors::Vec3Methods;
+use crate::{Vec3, render_world_adaptive, RenderSettings};
+
+const N: usize = 10000;
+
+#[test]
+pub fn test_pixel_estimate() {
+    let mut estimate: PixelEstimate = PixelEstimate::new();
+    for _ in 0..N {
+        estimate.add(Vec3::ones() * 5e-1);
+    }
+    assert!(estimate.error() < 1e-6);
+    assert!(estimate.converged(1e-3));
+
+    // Uniform luminance on [0, 1]: standard error sqrt(1/12/n).
+    let mut estimate: PixelEstimate = PixelEstimate::new();
+    for _ in 0..N {
+        estimate.add(Vec3::ones() * rand::random::<f32>());
+    }
+    let expected: f32 = (1e0 / 12e0 / N as f32).sqrt();
+    assert!((estimate.error() - expected).abs() < 0.1 * expected);
+    assert!((estimate.mean().x() - 5e-1).abs() < 2e-2);
+}
+
+#[test]
+pub fn test_adaptive_render() {
+    // Diffuse sphere in front of the sky: sky pixels converge at once,
+    // sphere pixels (noisy diffuse bounces) take more samples.
+    let mut world: HittableList = HittableList::new();
+    world.forms.push(Form::Sphere(Sphere::new(
+        Vec3::zeros(),
+        5e-1,
+        Material::Lambertian(LambertianKind::new(Vec3::ones() * 5e-1))
+    )));
+    let cam: Camera = Camera::Simple(CameraSimple::new(
+        Vec3::new(0e0, 0e0, 3e0), Vec3::zeros(), Vec3::new(0e0, 1e0, 0e0), 40e0, 1e0
+    ));
+    let adaptive: AdaptiveSampling = AdaptiveSampling::new(8, 128, 1e-2);
+    let film: Framebuffer = render_world_adaptive(&world, &cam, &RenderSettings::new(12, 12, 1e0, 0, 8, false, SamplerKind::Sobol), &adaptive);
+    assert_eq!(film.samples(0, 0), 8);
+    assert!(film.samples(6, 6) > 8);
+    assert!(film.samples.iter().all(|n| *n >= 8 && *n <= 128));
+    let heatmap: Framebuffer = film.heatmap();
+    assert!(heatmap.get(0, 0).z() > heatmap.get(6, 6).z());
+}
+
+#[test]
+pub fn test_adaptive_bounds() {
+    // Fields set past the constructor: empty batches and a minimum
+    // above the maximum still stop at ``max_samples``.
+    let world: HittableList = HittableList::new();
+    let cam: Camera = Camera::Simple(CameraSimple::new(
+        Vec3::new(0e0, 0e0, 3e0), Vec3::zeros(), Vec3::new(0e0, 1e0, 0e0), 40e0, 1e0
+    ));
+    let settings: RenderSettings = RenderSettings::new(2, 2, 1e0, 0, 4, false, SamplerKind::Stratified);
+    for (min_samples, max_samples) in [(0, 4), (16, 4)].iter() {
+        let adaptive: AdaptiveSampling = AdaptiveSampling { min_samples: *min_samples, max_samples: *max_samples, threshold: 0e0 };
+        let film: Framebuffer = render_world_adaptive(&world, &cam, &settings, &adaptive);
+        assert!(film.samples.iter().all(|n| *n <= 4));
+    }
+}
@@ -0,0 +1,120 @@
+use crate::objects::{HittableList, Form, Rectangle, Motion, Keyframe};
+use crate::materials::{Material, LambertianKind};
+use crate::radiation::{ViewFactors, VfMethod, Vfs, VfTarget};
+use crate::samplers::SamplerKind;
+use crate::analytic::*;
+use crate::{Vec3, Square, Sphere};
+use super::test_vf_methods::world_perpendicular_rectangles;
+use super::test_exchange::cube_enclosure;
+
+const N_MC: usize = 32768;
+const ERR_MAX: f32 = 1e-2;
+
+fn material() -> Material {
+    Material::Lambertian(LambertianKind::new(Vec3::new(0.5, 0.5, 0.5)))
+}
+
+/// Rectangles ``a`` by ``b`` facing each other a distance ``c`` apart
+/// along Z, the second one shifted by ``offset`` on its plane.
+fn world_parallel_rectangles(a: f32, b: f32, c: f32, offset: Vec3) -> HittableList {
+    let mut world: HittableList = HittableList::new();
+    let (x, y, z): (Vec3, Vec3, Vec3) = (Vec3::new(1e0, 0e0, 0e0), Vec3::new(0e0, 1e0, 0e0), Vec3::new(0e0, 0e0, 1e0));
+    world.forms.push(Form::Rectangle(Rectangle::new(Vec3::zeros(), a, b, material(), x, y, z)));
+    world.forms.push(Form::Rectangle(Rectangle::new(z * c + offset, b, a, material(), y, x, -z)));
+    world
+}
+
+/// Differential patch at the origin facing +Z towards the given form.
+fn world_patch_to(form: Form) -> HittableList {
+    let mut world: HittableList = HittableList::new();
+    world.forms.push(Form::Square(Square::new(
+        Vec3::zeros(),
+        1e-6,
+        material(),
+        Vec3::new(1e0, 0e0, 0e0),
+        Vec3::new(0e0, 1e0, 0e0),
+        Vec3::new(0e0, 0e0, 1e0)
+    )));
+    world.forms.push(form);
+    world
+}
+
+#[test]
+pub fn test_analytic_values() {
+    // Tabulated values and limits.
+    assert!((perpendicular_rectangles(1e0, 1e0, 1e0) - 0.2).abs() < 1e-3);
+    assert!((parallel_rectangles(1e0, 1e0, 1e0) - 0.1998).abs() < 1e-3);
+    assert!((coaxial_disks(1e0, 1e0, 1e0) - (3e0 - 5e0_f32.sqrt()) / 2e0).abs() < 1e-5);
+    assert!((coaxial_disks(1e-3, 2e0, 1e0) - patch_to_disk(2e0, 1e0)).abs() < 1e-4);
+    assert!((4e0 * patch_to_rectangle_corner(1e0, 1e0, 1e0) - 0.5541).abs() < 1e-3);
+    assert!((small_sphere_to_sphere(1e0, 1e3) - patch_to_frontal_sphere(1e0, 1e3) / 4e0).abs() < 1e-6);
+    assert!((sphere_to_disk(1e3, 1e0) - 5e-1).abs() < 1e-3);
+    assert!((sphere_enclosure(1e0, 2e0) - 0.25).abs() < 1e-6);
+}
+
+#[test]
+pub fn test_analytic_monte_carlo() {
+    // Area integration is left out on forms sharing an edge, where its
+    // integrand is singular. Spheres, sampled with a varying area weight,
+    // converge slower than flat forms.
+    let check = |name: &str, world: HittableList, exact: f32, area_integration: bool, err: f32| {
+        let row: Vec<f32> = world.view_factor_row(N_MC, 0, SamplerKind::Sobol);
+        let area: f32 = world.view_factor(N_MC, 0, 1);
+        println!("{}: exact {}, ray shooting {}, area integration {}", name, exact, row[1], area);
+        assert!((row[1] - exact).abs() < err * exact.max(1e-1));
+        assert!(!area_integration || (area - exact).abs() < err * exact.max(1e-1));
+    };
+    check("parallel rectangles", world_parallel_rectangles(1e0, 2e0, 5e-1, Vec3::zeros()), parallel_rectangles(1e0, 2e0, 5e-1), true, ERR_MAX);
+    check("perpendicular rectangles", world_perpendicular_rectangles(1e0, 5e-1, 2e0), perpendicular_rectangles(1e0, 5e-1, 2e0), false, ERR_MAX);
+    check(
+        "patch to rectangle corner",
+        world_patch_to(Form::Rectangle(Rectangle::new(
+            Vec3::new(0.5, 1e0, 1e0), 2e0, 1e0, material(),
+            Vec3::new(0e0, 1e0, 0e0), Vec3::new(1e0, 0e0, 0e0), Vec3::new(0e0, 0e0, -1e0)))),
+        patch_to_rectangle_corner(1e0, 2e0, 1e0),
+        true,
+        ERR_MAX
+    );
+
+    let mut world: HittableList = HittableList::new();
+    world.forms.push(Form::Sphere(Sphere::new(Vec3::zeros(), 1e-3, material())));
+    world.forms.push(Form::Sphere(Sphere::new(Vec3::new(0e0, 3e0, 0e0), 2e0, material())));
+    check("small sphere to sphere", world, small_sphere_to_sphere(2e0, 3e0), true, 3e0 * ERR_MAX);
+}
+
+#[test]
+pub fn test_configurations() {
+    let world: HittableList = world_parallel_rectangles(1e0, 2e0, 5e-1, Vec3::zeros());
+    assert_eq!(world.configuration(0, 1), Some(Configuration::ParallelRectangles(1e0, 2e0, 5e-1)));
+    assert!(world_parallel_rectangles(1e0, 2e0, 5e-1, Vec3::new(0.1, 0e0, 0e0)).configuration(0, 1).is_none());
+
+    let world: HittableList = world_perpendicular_rectangles(1e0, 5e-1, 2e0);
+    assert_eq!(world.configuration(0, 1), Some(Configuration::PerpendicularRectangles(1e0, 5e-1, 2e0)));
+    assert_eq!(world.configuration(1, 0), Some(Configuration::PerpendicularRectangles(1e0, 2e0, 5e-1)));
+
+    let mut world: HittableList = HittableList::new();
+    world.forms.push(Form::Sphere(Sphere::hollow(Vec3::zeros(), 2e0, material())));
+    world.forms.push(Form::Sphere(Sphere::new(Vec3::new(0.5, 0e0, 0e0), 1e0, material())));
+    assert_eq!(world.exact_view_factor(0, 1), Some(0.25));
+    assert_eq!(world.exact_view_factor(1, 0), Some(1e0));
+    // Spheres are matched where the first keyframe puts them, here
+    // crossing the enclosure.
+    let motion: Motion = Motion::Keyframed(vec![Keyframe::new(0e0, Vec3::new(1.5, 0e0, 0e0), Vec3::zeros())]);
+    world.forms[1] = Form::Sphere(Sphere::moving(Vec3::new(0.5, 0e0, 0e0), 1e0, material(), motion));
+    assert!(world.exact_view_factor(0, 1).is_none());
+
+    // Every face pair of an empty cube is known, none once it holds a sphere.
+    let mut world: HittableList = cube_enclosure();
+    assert!((world.exact_view_factor(0, 3).unwrap() - 0.1998).abs() < 1e-3);
+    assert!((world.exact_view_factor(0, 1).unwrap() - 0.2).abs() < 1e-3);
+    let vfs: Vfs = world.view_factors_hybrid(VfMethod::AreaIntegration, &VfTarget::fixed(N_MC));
+    assert!(vfs.matrix(&world).iter().all(|row| (row.iter().sum::<f32>() - 1e0).abs() < 1e-3));
+
+    world.forms.push(Form::Sphere(Sphere::new(Vec3::zeros(), 0.25, material())));
+    assert!(world.exact_view_factor(0, 3).is_none());
+    let hybrid: Vfs = world.view_factors_hybrid(VfMethod::RayShooting, &VfTarget::fixed(N_MC / 8));
+    let sampled: Vfs = world.view_factors_converged(VfMethod::RayShooting, &VfTarget::fixed(N_MC / 8)).vfs();
+    for (row_hybrid, row_sampled) in hybrid.vfs.iter().zip(sampled.vfs.iter()) {
+        assert!(row_hybrid.iter().zip(row_sampled.iter()).all(|(a, b)| (a - b).abs() < 2.5 * ERR_MAX));
+    }
+}
@@ -0,0 +1,62 @@
+use crate::animation::{CameraAnimation, CameraKeyframe, Sequence};
+use crate::cameras::{Camera, CameraRay};
+use crate::objects::{HittableList, Form, Sphere, Motion};
+use crate::materials::{Material, LambertianKind};
+use crate::postprocess::PostProcess;
+use crate::samplers::SamplerKind;
+use crate::vectors::Vec3Methods;
+use crate::{Vec3, render_sequence, RenderSettings};
+
+const ERR_MAX: f32 = 1e-4;
+
+#[test]
+pub fn test_camera_keyframes() {
+    let mut animation: CameraAnimation = CameraAnimation::new(Vec3::new(0e0, 1e0, 0e0), 1e0, 0e0);
+    assert!(animation.camera_at(0e0).is_none());
+    // Keyframes are sorted on insertion.
+    animation.push(CameraKeyframe::new(2e0, Vec3::new(0e0, 0e0, 4e0), Vec3::zeros(), 60e0, 4e0));
+    animation.push(CameraKeyframe::new(0e0, Vec3::new(0e0, 0e0, 2e0), Vec3::zeros(), 20e0, 2e0));
+
+    let mid: CameraKeyframe = animation.keyframe_at(1e0).unwrap();
+    assert!((mid.look_from - Vec3::new(0e0, 0e0, 3e0)).length() < ERR_MAX);
+    assert!((mid.vfov - 40e0).abs() < ERR_MAX);
+    assert!((animation.keyframe_at(-1e0).unwrap().vfov - 20e0).abs() < ERR_MAX);
+    assert!((animation.keyframe_at(5e0).unwrap().focus_dist - 4e0).abs() < ERR_MAX);
+
+    // Central ray of the interpolated camera.
+    let cam: Camera = animation.camera_at(1e0).unwrap();
+    let ray = cam.get_ray(5e-1, 5e-1);
+    assert!((ray.origin() - Vec3::new(0e0, 0e0, 3e0)).length() < ERR_MAX);
+    assert!((ray.direction().unit_vector() - Vec3::new(0e0, 0e0, -1e0)).length() < ERR_MAX);
+
+    let sequence: Sequence = Sequence::new(1e0, 24e0, 48, 5e-1);
+    let (open, close) = sequence.frame_shutter(24);
+    assert!((open - 2e0).abs() < ERR_MAX);
+    assert!((close - open - 1e0 / 48e0).abs() < ERR_MAX);
+}
+
+#[test]
+pub fn test_render_sequence() {
+    let mut world: HittableList = HittableList::new();
+    world.forms.push(Form::Sphere(Sphere::moving(
+        Vec3::zeros(),
+        5e-1,
+        Material::Lambertian(LambertianKind::new(Vec3::ones() * 5e-1)),
+        Motion::linear(Vec3::new(1e0, 0e0, 0e0))
+    )));
+    let mut animation: CameraAnimation = CameraAnimation::new(Vec3::new(0e0, 1e0, 0e0), 1e0, 0e0);
+    animation.push(CameraKeyframe::new(0e0, Vec3::new(0e0, 0e0, 3e0), Vec3::zeros(), 60e0, 3e0));
+    let sequence: Sequence = Sequence::new(0e0, 2e0, 3, 5e-1);
+    let prefix: String = std::env::temp_dir().join("raytracing_test_frame").to_string_lossy().into_owned();
+    let paths: Vec<String> = render_sequence(
+        &world, &animation, &sequence, &RenderSettings::new(8, 6, 1e0, 2, 4, false, SamplerKind::Stratified), &PostProcess::new(), &prefix
+    ).unwrap();
+    assert_eq!(paths.len(), 3);
+    for (k, path) in paths.iter().enumerate() {
+        assert!(path.ends_with(&format!("_{:04}.ppm", k)));
+        let image: String = std::fs::read_to_string(path).unwrap();
+        assert!(image.starts_with("P3\n8 6\n255\n"));
+        assert_eq!(image.lines().count(), 3 + 8 * 6);
+        std::fs::remove_file(path).unwrap();
+    }
+}
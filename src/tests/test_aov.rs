@@ -0,0 +1,63 @@
+use crate::objects::{HittableList, Form};
+use crate::materials::{Material, LambertianKind, EmissiveKind, MixKind, MaterialAlbedo};
+use crate::cameras::{Camera, CameraSimple};
+use crate::hittable::HitRecord;
+use crate::samplers::SamplerKind;
+use crate::aov::{Aov, AovBuffers};
+use crate::vectors::Vec3Methods;
+use crate::{Vec3, Sphere, render_aovs, RenderSettings};
+use super::hit_record;
+
+const N_PX: u16 = 21;
+const ERR_MAX: f32 = 1e-2;
+
+/// Red sphere of radius 0.5, at distance 2 in front of ``camera``.
+pub(super) fn world_sphere() -> HittableList {
+    let mut world: HittableList = HittableList::new();
+    let mat: Material = Material::Lambertian(LambertianKind::new(Vec3::new(0.8, 0.1, 0.1)));
+    world.forms.push(Form::Sphere(Sphere::new(Vec3::new(0e0, 0e0, -2e0), 5e-1, mat)));
+    world
+}
+
+/// Pinhole camera at the origin looking down -Z.
+pub(super) fn camera() -> Camera {
+    Camera::Simple(CameraSimple::new(Vec3::zeros(), Vec3::new(0e0, 0e0, -1e0), Vec3::new(0e0, 1e0, 0e0), 60e0, 1e0))
+}
+
+#[test]
+pub fn test_primary_hit_buffers() {
+    let world: HittableList = world_sphere();
+    let cam: Camera = camera();
+    let aovs: AovBuffers = render_aovs(&world, &cam, &RenderSettings::new(N_PX, N_PX, 1e0, 4, 0, false, SamplerKind::Sobol));
+
+    // Center pixel, front of the sphere.
+    let c: u16 = N_PX / 2;
+    assert!((aovs.depth.get(c, c).x() - 1.5).abs() < ERR_MAX);
+    assert!((aovs.normal.get(c, c) - Vec3::new(0e0, 0e0, 1e0)).length() < 5e-2);
+    assert!((aovs.albedo.get(c, c) - Vec3::new(0.8, 0.1, 0.1)).length() < 1e-6);
+    assert!((aovs.position.get(c, c) - Vec3::new(0e0, 0e0, -1.5)).length() < 5e-2);
+    assert_eq!(aovs.object_id.get(c, c).x(), 0e0);
+
+    // Corner pixel, background.
+    assert!(aovs.depth.get(0, 0).x().is_infinite());
+    assert_eq!(aovs.albedo.get(0, 0).length(), 0e0);
+    assert_eq!(aovs.object_id.get(0, 0).x(), -1e0);
+
+    // Previews are displayable.
+    for aov in Aov::ALL.iter() {
+        for col in aovs.preview(*aov).pixels.iter() {
+            assert!(col.e.iter().all(|x| (0e0..=1e0).contains(x)));
+        }
+    }
+}
+
+#[test]
+pub fn test_mix_albedo() {
+    let white: Material = Material::Lambertian(LambertianKind::new(Vec3::ones()));
+    let light: Material = Material::Emissive(EmissiveKind::new(Vec3::new(4e0, 5e-1, 0e0)));
+    let mix: Material = Material::Mix(MixKind::new(white, light, 0.25));
+    let rec: HitRecord = hit_record(mix.clone(), Vec3::zeros());
+    // Emitters are clamped to 1.
+    let expected: Vec3 = Vec3::ones() * 0.25 + Vec3::new(1e0, 5e-1, 0e0) * 0.75;
+    assert!((mix.albedo(&rec) - expected).length() < 1e-6);
+}
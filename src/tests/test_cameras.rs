@@ -0,0 +1,64 @@
+use crate::objects::HittableList;
+use crate::cameras::{CameraRay, CameraFisheye, CameraOrthographic, CameraEquirectangular};
+use crate::hittable::{HitRecord, Hittable};
+use crate::vectors::Vec3Methods;
+use crate::rays::Ray;
+use crate::Vec3;
+use super::test_patch_sphere::world_patch_frontal_sphere;
+
+const N_PX: usize = 400;
+const ERR_MAX: f32 = 1e-2;
+
+/// Nusselt analog: the fraction of the fisheye image circle covered by
+/// the sphere is the view factor from the patch to the sphere.
+#[test]
+pub fn test_fisheye_nusselt_analog() {
+    let (r, h): (f32, f32) = (1e0, 2e0);
+    let world: HittableList = world_patch_frontal_sphere(r, h);
+    let camera: CameraFisheye = CameraFisheye::on_surface(
+        world.forms.first().unwrap(),
+        5e-1,
+        5e-1,
+        Vec3::new(0e0, 1e0, 0e0),
+        1e0
+    );
+    let mut n_circle: usize = 0;
+    let mut n_sphere: usize = 0;
+    let mut rec: Option<HitRecord> = None;
+    for i in 0..N_PX {
+        for j in 0..N_PX {
+            let s: f32 = (i as f32 + 5e-1) / (N_PX as f32);
+            let t: f32 = (j as f32 + 5e-1) / (N_PX as f32);
+            if !camera.covers(s, t) {
+                continue;
+            }
+            n_circle += 1;
+            if world.hit(&camera.get_ray(s, t), 1e-6, f32::MAX, &mut rec) {
+                n_sphere += (rec.as_ref().unwrap().hit_elem == 1) as usize;
+            }
+        }
+    }
+    let vf: f32 = n_sphere as f32 / n_circle as f32;
+    println!("VF (fisheye) = {}, VF (exact) = {}", vf, (r / h).powi(2));
+    assert!((vf - (r / h).powi(2)).abs() < ERR_MAX);
+}
+
+#[test]
+pub fn test_orthographic_and_equirectangular() {
+    let from: Vec3 = Vec3::new(0e0, 0e0, 5e0);
+    let at: Vec3 = Vec3::zeros();
+    let up: Vec3 = Vec3::new(0e0, 1e0, 0e0);
+
+    // Parallel rays, image spans the given height.
+    let ortho: CameraOrthographic = CameraOrthographic::new(from, at, up, 2e0, 1.5);
+    let ray_1: Ray = ortho.get_ray(0e0, 0e0);
+    let ray_2: Ray = ortho.get_ray(1e0, 1e0);
+    assert!((ray_1.direction() - ray_2.direction()).length() < 1e-6);
+    assert!((ray_2.origin() - ray_1.origin() - Vec3::new(3e0, 2e0, 0e0)).length() < 1e-5);
+
+    // Image center looks at ``look_at``, edges look backwards, top looks up.
+    let equirect: CameraEquirectangular = CameraEquirectangular::new(from, at, up);
+    assert!((equirect.get_ray(5e-1, 5e-1).direction() - Vec3::new(0e0, 0e0, -1e0)).length() < 1e-5);
+    assert!((equirect.get_ray(0e0, 5e-1).direction() - Vec3::new(0e0, 0e0, 1e0)).length() < 1e-5);
+    assert!((equirect.get_ray(5e-1, 1e0).direction() - up).length() < 1e-5);
+}
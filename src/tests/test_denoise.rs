@@ -0,0 +1,59 @@
+extern crate rand;
+use self::rand::random;
+
+use crate::film::Framebuffer;
+use crate::aov::AovBuffers;
+use crate::denoise::Denoiser;
+use crate::vectors::Vec3Methods;
+use crate::Vec3;
+
+const N_PX: u16 = 32;
+
+/// Flat image split in two surfaces of different albedo (left and
+/// right halves), under unit irradiance: returns the exact image, a
+/// noisy one and its auxiliary buffers.
+fn split_image() -> (Framebuffer, Framebuffer, AovBuffers) {
+    let mut exact: Framebuffer = Framebuffer::new(N_PX, N_PX);
+    let mut noisy: Framebuffer = Framebuffer::new(N_PX, N_PX);
+    let mut aovs: AovBuffers = AovBuffers::new(N_PX, N_PX);
+    for j in 0..N_PX {
+        for i in 0..N_PX {
+            let albedo: Vec3 = match i < N_PX / 2 {
+                true => Vec3::new(0.8, 0.2, 0.2),
+                false => Vec3::new(0.2, 0.2, 0.8),
+            };
+            exact.set(i, j, albedo);
+            noisy.set(i, j, albedo * (5e-1 + random::<f32>()));
+            aovs.albedo.set(i, j, albedo);
+            aovs.normal.set(i, j, Vec3::new(0e0, 0e0, 1e0));
+        }
+    }
+    (exact, noisy, aovs)
+}
+
+fn rmse(a: &Framebuffer, b: &Framebuffer) -> f32 {
+    let sum: f32 = a.pixels.iter().zip(b.pixels.iter()).map(|(x, y)| (*x - *y).square_length()).sum();
+    (sum / a.pixels.len() as f32).sqrt()
+}
+
+#[test]
+pub fn test_denoise_split_image() {
+    let (exact, noisy, aovs) = split_image();
+
+    // No strength, no change.
+    let unchanged: Framebuffer = Denoiser::new(0e0).denoise(&noisy, &aovs);
+    assert!(rmse(&unchanged, &noisy) < 1e-6);
+
+    let denoised: Framebuffer = Denoiser::new(1e0).denoise(&noisy, &aovs);
+    let (err_noisy, err_denoised) = (rmse(&noisy, &exact), rmse(&denoised, &exact));
+    println!("RMSE noisy: {}, denoised: {}", err_noisy, err_denoised);
+    assert!(err_denoised < 0.4 * err_noisy);
+
+    // The albedo edge is not blurred.
+    for j in 0..N_PX {
+        let left: Vec3 = denoised.get(N_PX / 2 - 1, j);
+        let right: Vec3 = denoised.get(N_PX / 2, j);
+        assert!(left.r() > 2e0 * left.b());
+        assert!(right.b() > 2e0 * right.r());
+    }
+}
@@ -0,0 +1,43 @@
+use crate::materials::{Dispersion, DielectricKind};
+use crate::spectrum::{wavelength_to_rgb, sample_wavelength};
+use crate::vectors::Vec3Methods;
+use crate::Vec3;
+
+const N: usize = 4000;
+const ERR_MAX: f32 = 1e-2;
+
+#[test]
+pub fn test_dispersion_index() {
+    // Refractive indices at the F (486.1 nm), d (587.6 nm) and C (656.3 nm) lines.
+    let bk7: Dispersion = Dispersion::bk7();
+    let n_f: f32 = bk7.index(486.1).unwrap();
+    let n_d: f32 = bk7.index(587.6).unwrap();
+    let n_c: f32 = bk7.index(656.3).unwrap();
+    println!("BK7: n_F = {}, n_d = {}, n_C = {}", n_f, n_d, n_c);
+    assert!((n_d - 1.5168).abs() < 1e-3);
+    // Abbe number.
+    assert!(((n_d - 1e0) / (n_f - n_c) - 64.17).abs() < 1e0);
+    assert!(Dispersion::None.index(587.6).is_none());
+}
+
+#[test]
+pub fn test_wavelength_weights_white() {
+    let mut white: Vec3 = Vec3::zeros();
+    for i in 0..N {
+        let (lambda, _pdf) = sample_wavelength((i as f32 + 5e-1) / (N as f32));
+        white += wavelength_to_rgb(lambda) / (N as f32);
+    }
+    println!("White = {}", white);
+    for c in 0..3 {
+        assert!((white[c] - 1e0).abs() < ERR_MAX);
+    }
+}
+
+#[test]
+pub fn test_beer_lambert() {
+    let glass: DielectricKind = DielectricKind::absorbing(1.5, Vec3::new(0e0, 0.5, 2e0));
+    let tau: Vec3 = glass.transmittance(2e0);
+    assert!((tau.r() - 1e0).abs() < 1e-6);
+    assert!((tau.g() - (-1e0f32).exp()).abs() < 1e-6);
+    assert!((tau.b() - (-4e0f32).exp()).abs() < 1e-6);
+}
@@ -0,0 +1,78 @@
+use crate::objects::{HittableList, Form, Rectangle};
+use crate::materials::{Material, LambertianKind};
+use crate::exchange::{ExchangeFactors, Gebhart, SurfaceOptics};
+use crate::vectors::Vec3Methods;
+use crate::Vec3;
+use super::test_vf_methods::world_perpendicular_rectangles;
+use crate::analytic::{parallel_rectangles, perpendicular_rectangles};
+
+const N_MC: usize = 32768;
+const ERR_MAX: f32 = 1e-2;
+
+/// Closed cube of side 1 made of six squares facing inwards.
+pub fn cube_enclosure() -> HittableList {
+    let mut world: HittableList = HittableList::new();
+    let mat: Material = Material::Lambertian(LambertianKind::new(Vec3::new(0.5, 0.5, 0.5)));
+    for k in 0..6 {
+        let mut axis: Vec3 = Vec3::zeros();
+        axis.e[k % 3] = match k < 3 { true => 1e0, false => -1e0 };
+        let (u, _) = axis.orthonormal_basis();
+        world.forms.push(Form::Rectangle(Rectangle::new(axis * 5e-1, 1e0, 1e0, mat.clone(), u, (-axis).cross(&u), -axis)));
+    }
+    world
+}
+
+#[test]
+pub fn test_gebhart_black() {
+    let (l, w, h): (f32, f32, f32) = (0.8, 0.4, 0.1);
+    let world: HittableList = world_perpendicular_rectangles(l, w, h);
+    let row: Vec<f32> = world.gebhart_row(N_MC, 0, &[SurfaceOptics::black(); 2]);
+    let exact: f32 = perpendicular_rectangles(l, w, h);
+    assert!((row[1] - exact).abs() < ERR_MAX * exact.max(1e-1));
+    assert_eq!(row[0], 0e0);
+}
+
+#[test]
+pub fn test_gebhart_mirror() {
+    // A black plate facing a mirror sees its image, twice as far.
+    let (a, d): (f32, f32) = (1e0, 5e-1);
+    let mat: Material = Material::Lambertian(LambertianKind::new(Vec3::new(0.5, 0.5, 0.5)));
+    let mut world: HittableList = HittableList::new();
+    for (z, w) in [(0e0, 1e0), (d, -1e0)].iter() {
+        world.forms.push(Form::Rectangle(Rectangle::new(
+            Vec3::new(0e0, 0e0, *z),
+            a,
+            a,
+            mat.clone(),
+            Vec3::new(1e0, 0e0, 0e0),
+            Vec3::new(0e0, *w, 0e0),
+            Vec3::new(0e0, 0e0, *w)
+        )));
+    }
+    let optics: [SurfaceOptics; 2] = [SurfaceOptics::black(), SurfaceOptics::new(0e0, 1e0)];
+    let row: Vec<f32> = world.gebhart_row(N_MC, 0, &optics);
+    let exact: f32 = parallel_rectangles(a, a, 2e0 * d);
+    println!("B00: {}, exact {}", row[0], exact);
+    assert!((row[0] - exact).abs() < ERR_MAX * exact);
+    assert_eq!(row[1], 0e0);
+}
+
+#[test]
+pub fn test_gebhart_enclosure() {
+    let world: HittableList = cube_enclosure();
+    let mut optics: Vec<SurfaceOptics> = vec![SurfaceOptics::new(5e-1, 0e0); 6];
+    optics[0] = SurfaceOptics::new(0.1, 0.8);
+    let gebhart: Gebhart = world.gebhart_factors(N_MC / 2, &optics);
+    let conductors: Vec<Vec<f32>> = gebhart.radiative_conductors(&world, &optics);
+    for (i, row) in gebhart.factors.iter().enumerate() {
+        // Closed enclosure, every ray is absorbed (but for the few
+        // leaking through the edges).
+        assert!((row.iter().sum::<f32>() - 1e0).abs() < 2e-3);
+        // Reflections come back to the emitter.
+        assert!(row[i] > 0e0);
+        // Reciprocity, GR_ij = GR_ji.
+        for (j, gr_ij) in conductors[i].iter().enumerate().take(i) {
+            assert!((gr_ij - conductors[j][i]).abs() < 1e-2);
+        }
+    }
+}
@@ -0,0 +1,53 @@
+use crate::objects::{HittableList, SurfaceFunctions};
+use crate::radiation::Vfs;
+use crate::export::NodeTable;
+use super::test_vf_methods::world_perpendicular_rectangles;
+
+#[test]
+pub fn test_names() {
+    let mut world: HittableList = world_perpendicular_rectangles(1e0, 2e0, 1e0);
+    assert_eq!(world.names(), vec!["rectangle_0", "rectangle_1"]);
+    world.set_name(1, "radiator");
+    assert_eq!(world.name(1), "radiator");
+    assert_eq!(world.snapshot(0e0).name(1), "radiator");
+
+    // Reciprocity completes the matrix, A_0 F_01 = A_1 F_10.
+    let matrix: Vec<Vec<f32>> = Vfs::from(vec![vec![0.15], vec![]]).matrix(&world);
+    assert_eq!(matrix[0][0], 0e0);
+    assert_eq!(matrix[0][1], 0.15);
+    assert!((world.forms[0].area() * matrix[0][1] - world.forms[1].area() * matrix[1][0]).abs() < 1e-6);
+
+    // Names follow their forms on removal.
+    let (form, name) = world.remove(0);
+    assert_eq!(name, None);
+    assert_eq!(world.names(), vec!["radiator"]);
+    // Forms pushed straight onto the vector are unnamed.
+    world.forms.push(form);
+    assert_eq!(world.names(), vec!["radiator", "rectangle_1"]);
+}
+
+#[test]
+pub fn test_formats() {
+    let table: NodeTable = NodeTable::new(
+        vec![String::from("panel"), String::from("box, top"), String::from("space")],
+        vec![vec![0e0, 0.5, 0.25], vec![0.5, 0e0, 0e0], vec![0.25, 0e0, 0e0]]
+    );
+    assert_eq!(table.csv(), "node,panel,\"box, top\",space\npanel,0,0.5,0.25\n\"box, top\",0.5,0,0\nspace,0.25,0,0\n");
+    assert!(table.json().contains("\"names\": [\"panel\", \"box, top\", \"space\"]"));
+    assert!(table.json().contains("[0, 0.5, 0.25]"));
+
+    let npy: Vec<u8> = table.npy();
+    let header_len: usize = u16::from_le_bytes([npy[8], npy[9]]) as usize;
+    assert_eq!(&npy[..6], b"\x93NUMPY");
+    assert_eq!((10 + header_len) % 64, 0);
+    assert!(String::from_utf8_lossy(&npy[10..(10 + header_len)]).contains("'shape': (3, 3)"));
+    assert_eq!(npy.len(), 10 + header_len + 36);
+    assert_eq!(f32::from_le_bytes([npy[14 + header_len], npy[15 + header_len], npy[16 + header_len], npy[17 + header_len]]), 0.5);
+
+    let sinda: String = table.sinda("RAD");
+    assert!(sinda.contains("C Node 2 = box, top\n"));
+    assert!(sinda.contains("HEADER CONDUCTOR DATA, RAD\n-1, 1, 2, 5.000000E-1\n-2, 1, 3, 2.500000E-1\n"));
+    let esatan: String = table.esatan();
+    assert!(esatan.contains("$CONDUCTORS\nGR(1, 2) = 5.000000E-1;\nGR(1, 3) = 2.500000E-1;\n"));
+    assert!(!esatan.contains("GR(2, 3)"));
+}
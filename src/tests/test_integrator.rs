@@ -0,0 +1,68 @@
+use crate::objects::{HittableList, Form, Sphere};
+use crate::materials::{Material, LambertianKind};
+use crate::integrator::{PathSettings, PathStats, PathEnd};
+use crate::rays::Ray;
+use crate::vectors::Vec3Methods;
+use crate::Vec3;
+
+const N: usize = 40000;
+const MAX_DEPTH: usize = 50;
+const ERR_MAX: f32 = 3e-2;
+
+/// Bright diffuse sphere resting on a diffuse ground, so that
+/// paths close to the contact point bounce many times.
+fn world_sphere_on_ground() -> HittableList {
+    let mut world: HittableList = HittableList::new();
+    let mat: Material = Material::Lambertian(LambertianKind::new(Vec3::new(0.9, 0.9, 0.9)));
+    world.forms.push(Form::Sphere(Sphere::new(Vec3::new(0e0, -100.5, 0e0), 100e0, mat.clone())));
+    world.forms.push(Form::Sphere(Sphere::new(Vec3::zeros(), 0.5, mat)));
+    world
+}
+
+fn mean_radiance(world: &HittableList, settings: &PathSettings, stats: &mut PathStats) -> f32 {
+    let mut total: f32 = 0e0;
+    for _ in 0..N {
+        let ray: Ray = Ray::new(Vec3::new(0e0, 0e0, 2e0), Vec3::new(0e0, -0.45, -1.5));
+        let (radiance, length, end) = ray.trace(world, settings);
+        stats.record(length, end);
+        total += radiance.r();
+    }
+    total / N as f32
+}
+
+#[test]
+pub fn test_russian_roulette() {
+    let world: HittableList = world_sphere_on_ground();
+
+    let mut fixed_stats: PathStats = PathStats::new();
+    let fixed: f32 = mean_radiance(&world, &PathSettings::fixed(MAX_DEPTH), &mut fixed_stats);
+    let mut stats: PathStats = PathStats::new();
+    let roulette: f32 = mean_radiance(&world, &PathSettings::new(MAX_DEPTH), &mut stats);
+
+    // Roulette terminates paths early without biasing the estimate.
+    assert!((roulette - fixed).abs() < ERR_MAX * fixed);
+    assert_eq!(stats.paths(), N as u64);
+    assert!(stats.roulette > 0);
+    assert!(stats.mean_length() < fixed_stats.mean_length());
+    assert_eq!(fixed_stats.roulette, 0);
+
+    // Truncated paths lose energy.
+    let mut truncated_stats: PathStats = PathStats::new();
+    let truncated: f32 = mean_radiance(&world, &PathSettings::fixed(1), &mut truncated_stats);
+    assert!(truncated < fixed);
+    assert_eq!(truncated_stats.max_length(), 1);
+}
+
+#[test]
+pub fn test_path_stats() {
+    let mut stats: PathStats = PathStats::new();
+    stats.record(0, PathEnd::Escaped);
+    stats.record(2, PathEnd::Absorbed);
+    let mut other: PathStats = PathStats::new();
+    other.record(4, PathEnd::Roulette);
+    stats.merge(&other);
+    assert_eq!(stats.paths(), 3);
+    assert!((stats.mean_length() - 2e0).abs() < 1e-6);
+    assert_eq!(stats.max_length(), 4);
+    assert_eq!((stats.escaped, stats.absorbed, stats.roulette, stats.max_depth), (1, 1, 1, 0));
+}
@@ -0,0 +1,54 @@
+use crate::materials::{Material, MaterialPdf, LambertianKind, MetalKind, CoatedDiffuseKind, MixKind, Texture};
+use crate::hittable::HitRecord;
+use crate::rays::Ray;
+use crate::vectors::Vec3Methods;
+use crate::Vec3;
+use super::{hit_record, albedo};
+
+const N: usize = 20000;
+const ERR_MAX: f32 = 5e-2;
+
+#[test]
+pub fn test_coated_diffuse_energy() {
+    // White base under a clear coat: (almost) every ray is reflected.
+    let mat: Material = Material::CoatedDiffuse(CoatedDiffuseKind::new(Vec3::ones(), 1.5));
+    for dir in [Vec3::new(0e0, -1e0, 0e0), Vec3::new(1e0, -1e0, 0e0), Vec3::new(1e0, -0.2, 0e0)].iter() {
+        let ray: Ray = Ray::new(Vec3::zeros() - *dir, *dir);
+        let a: Vec3 = albedo(mat.clone(), &ray, Vec3::zeros(), N, None);
+        println!("direction = {}, albedo = {}", dir, a);
+        assert!((a.r() - 1e0).abs() < 1e-1);
+    }
+
+    // Black base: only the coat's Fresnel reflection remains (4% at normal incidence).
+    let mat: Material = Material::CoatedDiffuse(CoatedDiffuseKind::new(Vec3::zeros(), 1.5));
+    let ray: Ray = Ray::new(Vec3::new(0e0, 1e0, 0e0), Vec3::new(0e0, -1e0, 0e0));
+    let a: Vec3 = albedo(mat, &ray, Vec3::zeros(), N, None);
+    assert!((a.r() - 0.04).abs() < 1e-2);
+}
+
+#[test]
+pub fn test_mix() {
+    let red: Material = Material::Lambertian(LambertianKind::new(Vec3::new(1e0, 0e0, 0e0)));
+    let blue: Material = Material::Metal(MetalKind::new(Vec3::new(0e0, 0e0, 1e0), 0e0));
+    let ray: Ray = Ray::new(Vec3::new(0e0, 1e0, 0e0), Vec3::new(0.1, -1e0, 0e0));
+
+    // Weighted blend.
+    let mix: Material = Material::Mix(MixKind::new(red.clone(), blue.clone(), 0.25));
+    let a: Vec3 = albedo(mix.clone(), &ray, Vec3::zeros(), N, None);
+    println!("Mix albedo = {}", a);
+    assert!((a.r() - 0.25).abs() < ERR_MAX);
+    assert!((a.b() - 0.75).abs() < ERR_MAX);
+    assert!(!mix.is_specular());
+
+    // Texture driven blend.
+    let checker: Texture = Texture::Checker { scale: 1e0, odd: 1e0, even: 0e0 };
+    let mix: Material = Material::Mix(MixKind::textured(red.clone(), blue.clone(), checker));
+    assert!(albedo(mix.clone(), &ray, Vec3::new(0.5, 0.5, 0.5), N, None).r() < 1e-6);
+    assert!(albedo(mix, &ray, Vec3::new(1.5, 0.5, 0.5), N, None).b() < 1e-6);
+
+    // Evaluation only sees the non specular part.
+    let rec: HitRecord = hit_record(Material::Mix(MixKind::new(red, blue, 0.25)), Vec3::zeros());
+    let up: Vec3 = Vec3::new(0e0, 1e0, 0e0);
+    let pdf: f32 = rec.material.pdf(&ray, &rec, up);
+    assert!((pdf - 0.25 / std::f32::consts::PI).abs() < 1e-5);
+}
@@ -0,0 +1,81 @@
+use crate::objects::{HittableList, Form, ConstantMedium, Square};
+use crate::{Vec3, Sphere};
+use crate::materials::{Material, LambertianKind, HenyeyGreensteinKind};
+use crate::radiation::ViewFactors;
+use crate::rays::Ray;
+use crate::vectors::Vec3Methods;
+
+const SIGMA: f32 = 0.7;
+const R: f32 = 1.5;
+const ERR_MAX: f32 = 1e-3;
+
+fn world_gas_sphere(sigma: f32, r: f32) -> HittableList {
+    let mut world: HittableList = HittableList::new();
+    let boundary: Sphere = Sphere::new(
+        Vec3::zeros(),
+        r,
+        Material::Lambertian(LambertianKind::new(Vec3::ones()))
+    );
+    world.forms.push(Form::Medium(ConstantMedium::isotropic(Form::Sphere(boundary), sigma, Vec3::ones())));
+    world
+}
+
+#[test]
+pub fn test_medium_transmittance() {
+    let world: HittableList = world_gas_sphere(SIGMA, R);
+    let mut tau: f32;
+    let mut tau_calc: f32;
+    let mut y: f32;
+
+    // Rays crossing the whole sphere at several heights.
+    for i in 0..5 {
+        y = R * (i as f32) / 5e0;
+        let ray: Ray = Ray::new(Vec3::new(-3e0 * R, y, 0e0), Vec3::new(1e0, 0e0, 0e0));
+        tau = world.transmittance(&ray, 0e0, f32::MAX);
+        tau_calc = (-SIGMA * 2e0 * (R.powi(2) - y.powi(2)).sqrt()).exp();
+        println!("y={}, T (raytracing) = {}, T (exact) = {}", y, tau, tau_calc);
+        assert!((tau - tau_calc).abs() < ERR_MAX);
+    }
+
+    // Ray starting at the center (inside the medium).
+    let ray: Ray = Ray::new(Vec3::zeros(), Vec3::new(0e0, 0e0, 2e0));
+    tau = world.transmittance(&ray, 0e0, f32::MAX);
+    assert!((tau - (-SIGMA * R).exp()).abs() < ERR_MAX);
+}
+
+#[test]
+pub fn test_medium_view_factor() {
+    // Two small facing plates 2 apart, with a gas sphere of diameter 1.8
+    // in between: every line of sight is attenuated by about exp(-1.8 sigma).
+    let mat: Material = Material::Lambertian(LambertianKind::new(Vec3::ones()));
+    let (x, y, z): (Vec3, Vec3, Vec3) = (Vec3::new(1e0, 0e0, 0e0), Vec3::new(0e0, 1e0, 0e0), Vec3::new(0e0, 0e0, 1e0));
+    let mut world: HittableList = HittableList::new();
+    world.forms.push(Form::Square(Square::new(Vec3::zeros(), 0.1, mat.clone(), x, y, z)));
+    world.forms.push(Form::Square(Square::new(2e0 * z, 0.1, mat.clone(), y, x, -z)));
+    let vf_clear: f32 = world.view_factor(4096, 0, 1);
+
+    let gas: Sphere = Sphere::new(z, 0.9, mat);
+    world.forms.push(Form::Medium(ConstantMedium::isotropic(Form::Sphere(gas), SIGMA, Vec3::ones())));
+    let vf_gas: f32 = world.view_factor(4096, 0, 1);
+    let ratio_calc: f32 = (-SIGMA * 1.8).exp();
+    println!("F (clear) = {}, F (gas) = {}, ratio = {}, exact = {}", vf_clear, vf_gas, vf_gas / vf_clear, ratio_calc);
+    assert!((vf_gas / vf_clear - ratio_calc).abs() < 1e-2 * ratio_calc);
+}
+
+#[test]
+pub fn test_henyey_greenstein_sampling() {
+    // The mean cosine of the scattering angle is the asymmetry parameter g.
+    let v: Vec3 = Vec3::new(1e0, 2e0, -1e0);
+    let n: usize = 100000;
+    for g in [-0.6, 0e0, 0.3, 0.85].iter() {
+        let hg: HenyeyGreensteinKind = HenyeyGreensteinKind::new(Vec3::ones(), *g);
+        let mut mean_cos: f32 = 0e0;
+        for _ in 0..n {
+            let direction: Vec3 = hg.sample(v);
+            assert!((direction.length() - 1e0).abs() < ERR_MAX);
+            mean_cos += direction.dot(&v.unit_vector()) / n as f32;
+        }
+        println!("g = {}, mean cosine = {}", g, mean_cos);
+        assert!((mean_cos - g).abs() < 1e-2);
+    }
+}
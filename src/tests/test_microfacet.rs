@@ -0,0 +1,55 @@
+use crate::materials::{Material, MicrofacetConductorKind, RoughDielectricKind};
+use crate::spectrum::Spectrum;
+use crate::rays::Ray;
+use crate::vectors::Vec3Methods;
+use crate::Vec3;
+use super::albedo;
+
+const N: usize = 2000;
+const ERR_MAX: f32 = 1e-2;
+
+#[test]
+pub fn test_microfacet_conductor() {
+    // Every sample weight must equal ``eval / pdf`` at the sampled
+    // direction, and the mean weight must not create energy.
+    for roughness in [0.1f32, 0.4, 0.8].iter() {
+        let mat: Material = Material::MicrofacetConductor(MicrofacetConductorKind::gold(*roughness));
+        let ray: Ray = Ray::new(Vec3::new(-1e0, 1e0, 0e0), Vec3::new(1e0, -1e0, 0.2));
+        let a: f32 = albedo(mat, &ray, Vec3::zeros(), N, Some(ERR_MAX)).max();
+        println!("roughness={}, albedo = {}", roughness, a);
+        assert!(a <= 1e0 + ERR_MAX);
+    }
+}
+
+#[test]
+pub fn test_spectral_conductor() {
+    // Gold-like index: reflective in the red, less so in the blue.
+    let eta: Spectrum = Spectrum::from_samples(&[(450e0, 1.4), (650e0, 0.2)]);
+    let k: Spectrum = Spectrum::from_samples(&[(450e0, 1.9), (650e0, 3.5)]);
+    let conductor: MicrofacetConductorKind = MicrofacetConductorKind::spectral(eta, k, 0.3);
+    let blue: Vec3 = conductor.fresnel(1e0, Some(400e0));
+    let red: Vec3 = conductor.fresnel(1e0, Some(700e0));
+    let grey = |eta: f32, k: f32| MicrofacetConductorKind::new(Vec3::ones() * eta, Vec3::ones() * k, 0.3).fresnel(1e0, None);
+    assert!((blue.x() - grey(1.4, 1.9).x()).abs() < 1e-4);
+    assert!((red.x() - grey(0.2, 3.5).x()).abs() < 1e-4);
+    assert!(red.x() > blue.x());
+
+    let mut ray: Ray = Ray::new(Vec3::new(-1e0, 1e0, 0e0), Vec3::new(1e0, -1e0, 0.2));
+    ray.wavelength = Some(550e0);
+    let a: f32 = albedo(Material::MicrofacetConductor(conductor), &ray, Vec3::zeros(), N, Some(ERR_MAX)).max();
+    assert!(a <= 1e0 + ERR_MAX);
+}
+
+#[test]
+pub fn test_rough_dielectric() {
+    for roughness in [0.1f32, 0.4, 0.8].iter() {
+        let mat: Material = Material::RoughDielectric(RoughDielectricKind::new(1.5, *roughness));
+        // From outside and from inside the dielectric.
+        for dir in [Vec3::new(1e0, -1e0, 0.2), Vec3::new(0.3, 1e0, 0e0)].iter() {
+            let ray: Ray = Ray::new(Vec3::zeros() - *dir, *dir);
+            let a: f32 = albedo(mat.clone(), &ray, Vec3::zeros(), N, Some(ERR_MAX)).max();
+            println!("roughness={}, albedo = {}", roughness, a);
+            assert!(a <= 1e0 + ERR_MAX);
+        }
+    }
+}
@@ -0,0 +1,85 @@
+use crate::objects::{HittableList, Form, Sphere, Cube, Rectangle, Motion, Keyframe};
+use crate::materials::{Material, LambertianKind};
+use crate::hittable::{HitRecord, Hittable};
+use crate::radiation::ViewFactors;
+use crate::rays::Ray;
+use crate::Vec3;
+
+const N: usize = 20000;
+const ERR_MAX: f32 = 2e-2;
+
+fn grey() -> Material {
+    Material::Lambertian(LambertianKind::new(Vec3::ones() * 5e-1))
+}
+
+#[test]
+pub fn test_moving_sphere() {
+    // Unit sphere crossing the x axis from x = -2 to x = 2 during [0, 1].
+    let sphere: Sphere = Sphere::moving(Vec3::new(-2e0, 0e0, 0e0), 1e0, grey(), Motion::linear(Vec3::new(4e0, 0e0, 0e0)));
+    let mut rec: Option<HitRecord> = None;
+    let down: Vec3 = Vec3::new(0e0, 0e0, -1e0);
+    assert!(!sphere.hit(&Ray::timed(Vec3::new(0e0, 0e0, 5e0), down, 0e0), 1e-3, f32::MAX, &mut rec));
+    assert!(sphere.hit(&Ray::timed(Vec3::new(0e0, 0e0, 5e0), down, 5e-1), 1e-3, f32::MAX, &mut rec));
+    assert!((rec.as_ref().unwrap().t - 4e0).abs() < 1e-4);
+
+    // Blurred coverage of a fixed ray: the sphere is over it during 2/4 of the shutter.
+    let mut hits: usize = 0;
+    for _ in 0..N {
+        let ray: Ray = Ray::timed(Vec3::new(0e0, 0e0, 5e0), down, rand::random::<f32>());
+        if sphere.hit(&ray, 1e-3, f32::MAX, &mut rec) {
+            hits += 1;
+        }
+    }
+    assert!((hits as f32 / N as f32 - 5e-1).abs() < ERR_MAX);
+}
+
+#[test]
+pub fn test_rotating_cube() {
+    // Unit cube spinning about the vertical axis, keyframed from 0 to 90 degrees.
+    let keys: Vec<Keyframe> = vec![
+        Keyframe::new(0e0, Vec3::zeros(), Vec3::zeros()),
+        Keyframe::new(1e0, Vec3::new(0e0, 1e0, 0e0), Vec3::new(0e0, std::f32::consts::FRAC_PI_2, 0e0)),
+    ];
+    let cube: Cube = Cube::moving(Vec3::zeros(), 1e0, grey(), Motion::Keyframed(keys));
+    let mut rec: Option<HitRecord> = None;
+    let down: Vec3 = Vec3::new(0e0, -1e0, 0e0);
+    // The top face rises by one unit.
+    for (time, t_hit) in [(0e0, 4.5), (5e-1, 4e0), (1e0, 3.5), (2e0, 3.5)].iter() {
+        assert!(cube.hit(&Ray::timed(Vec3::new(0e0, 5e0, 0e0), down, *time), 1e-3, f32::MAX, &mut rec));
+        assert!((rec.as_ref().unwrap().t - t_hit).abs() < 1e-3);
+    }
+    // A quarter turn brings the cube back to the same shape.
+    let side: Vec3 = Vec3::new(-1e0, 0e0, 0e0);
+    let origin: Vec3 = Vec3::new(5e0, 0e0, 0.3);
+    let snap_0: Cube = cube.snapshot(0e0);
+    let snap_1: Cube = cube.snapshot(1e0);
+    let mut rec_1: Option<HitRecord> = None;
+    let hit_0: bool = snap_0.hit(&Ray::new(origin, side), 1e-3, f32::MAX, &mut rec);
+    let hit_1: bool = snap_1.hit(&Ray::new(origin + Vec3::new(0e0, 1e0, 0e0), side), 1e-3, f32::MAX, &mut rec_1);
+    assert!(hit_0 && hit_1);
+    assert!((rec.unwrap().t - rec_1.unwrap().t).abs() < 1e-3);
+}
+
+#[test]
+pub fn test_snapshot_view_factor() {
+    // Sphere moving away from a plate: the view factor at t = 1 is the
+    // one of a static sphere at the final position.
+    let plate = || Rectangle::new(
+        Vec3::zeros(), 1e0, 1e0, grey(),
+        Vec3::new(1e0, 0e0, 0e0), Vec3::new(0e0, 1e0, 0e0), Vec3::new(0e0, 0e0, 1e0)
+    );
+    let mut moving: HittableList = HittableList::new();
+    moving.forms.push(Form::Rectangle(plate()));
+    moving.forms.push(Form::Sphere(Sphere::moving(
+        Vec3::new(0e0, 0e0, 1e0), 0.3, grey(), Motion::linear(Vec3::new(0e0, 0e0, 1e0))
+    )));
+    let mut fixed: HittableList = HittableList::new();
+    fixed.forms.push(Form::Rectangle(plate()));
+    fixed.forms.push(Form::Sphere(Sphere::new(Vec3::new(0e0, 0e0, 2e0), 0.3, grey())));
+
+    let f_snapshot: f32 = moving.snapshot(1e0).view_factor(N, 0, 1);
+    let f_fixed: f32 = fixed.view_factor(N, 0, 1);
+    let f_rest: f32 = moving.view_factor(N, 0, 1);
+    assert!((f_snapshot - f_fixed).abs() < 0.1 * f_fixed);
+    assert!(f_rest > 1.5 * f_snapshot);
+}
@@ -0,0 +1,77 @@
+use crate::objects::HittableList;
+use crate::exchange::{ExchangeFactors, Gebhart, SurfaceOptics};
+use crate::network::{ThermalNetwork, Transient, TimeScheme, NewtonError, STEFAN_BOLTZMANN};
+use super::test_vf_methods::world_perpendicular_rectangles;
+
+#[test]
+pub fn test_steady_state() {
+    // Heated node conducting to a 300 K sink.
+    let mut network: ThermalNetwork = ThermalNetwork::new();
+    let node: usize = network.add_node(1e0, 250e0);
+    let sink: usize = network.add_boundary(300e0);
+    network.add_conductor(node, sink, 2e0);
+    network.set_loads(&[10e0]);
+    let temps: Vec<f32> = network.steady_state().unwrap();
+    assert!((temps[node] - 305e0).abs() < 1e-2);
+    assert_eq!(temps[sink], 300e0);
+
+    // Heated plate radiating to space, Q = sigma GR T^4.
+    let mut network: ThermalNetwork = ThermalNetwork::new();
+    let plate: usize = network.add_node(1e0, 10e0);
+    let space: usize = network.add_boundary(0e0);
+    network.add_radiative(plate, space, 0.8);
+    network.set_loads(&[300e0]);
+    let exact: f32 = (300e0 / (STEFAN_BOLTZMANN * 0.8)).powf(0.25);
+    assert!((network.steady_state().unwrap()[plate] - exact).abs() < 1e-2);
+
+    // Floating node without capacitance: singular Jacobian.
+    let mut network: ThermalNetwork = ThermalNetwork::new();
+    network.add_node(0e0, 300e0);
+    network.add_boundary(300e0);
+    assert_eq!(network.steady_state(), Err(NewtonError::Singular));
+    assert_eq!(network.transient(1e0, 1, TimeScheme::ImplicitEuler).err(), Some(NewtonError::Singular));
+}
+
+#[test]
+pub fn test_transient() {
+    // Cooling of a 400 K node towards a 300 K sink, time constant 100 s.
+    let mut network: ThermalNetwork = ThermalNetwork::new();
+    let node: usize = network.add_node(100e0, 400e0);
+    let sink: usize = network.add_boundary(300e0);
+    network.add_conductor(node, sink, 1e0);
+    let exact: f32 = 300e0 + 100e0 * (-1e0_f32).exp();
+    let euler: Transient = network.transient(5e0, 20, TimeScheme::ImplicitEuler).unwrap();
+    let crank: Transient = network.transient(5e0, 20, TimeScheme::CrankNicolson).unwrap();
+    assert_eq!(euler.times.len(), 21);
+    assert!((euler.times[20] - 100e0).abs() < 1e-4);
+    let (euler_error, crank_error): (f32, f32) = ((euler.temperatures[20][node] - exact).abs(), (crank.temperatures[20][node] - exact).abs());
+    println!("Implicit Euler error: {}, Crank-Nicolson error: {}", euler_error, crank_error);
+    assert!(euler_error < 1e0);
+    assert!(crank_error < 5e-2);
+}
+
+#[test]
+pub fn test_world_network() {
+    // Two black plates, one heated, radiating to each other and to space.
+    let world: HittableList = world_perpendicular_rectangles(1e0, 1e0, 1e0);
+    let optics: Vec<SurfaceOptics> = vec![SurfaceOptics::new(0.8, 0e0); 2];
+    let gebhart: Gebhart = world.gebhart_factors(16384, &optics);
+    let conductors: Vec<Vec<f32>> = gebhart.radiative_conductors(&world, &optics);
+    let mut network: ThermalNetwork = ThermalNetwork::from_world(&world, &optics, &conductors, &[1e3, 1e3], 293e0, 3e0);
+    assert_eq!(network.nodes.len(), 3);
+    network.set_loads(&[100e0, 0e0]);
+    let temps: Vec<f32> = network.steady_state().unwrap();
+    assert!(temps[0] > temps[1] && temps[1] > 3e0);
+    // Energy balance, space absorbs the load.
+    let space: f32 = network.net_power(&temps, &[0e0; 3])[2];
+    assert!((space - 100e0).abs() < 1e-1);
+}
+
+#[test]
+#[should_panic(expected = "one capacitance per form expected")]
+pub fn test_world_network_sizes() {
+    // A missing capacitance would shift the space node onto a form.
+    let world: HittableList = world_perpendicular_rectangles(1e0, 1e0, 1e0);
+    let optics: Vec<SurfaceOptics> = vec![SurfaceOptics::new(0.8, 0e0); 2];
+    ThermalNetwork::from_world(&world, &optics, &[vec![0e0; 2], vec![0e0; 2]], &[1e3], 293e0, 3e0);
+}
@@ -0,0 +1,71 @@
+use std::f32::consts::PI;
+
+use crate::objects::{HittableList, Form, Rectangle};
+use crate::materials::{Material, LambertianKind, ThermoOptics, ThermoOpticalKind};
+use crate::orbital::{Orbit, Attitude, Environment, HeatLoad, OrbitalHeatLoads, EARTH_RADIUS, SOLAR_CONSTANT, EARTH_IR};
+use crate::Vec3;
+use crate::vectors::Vec3Methods;
+
+const N_MC: usize = 16384;
+
+/// Black painted unit plate at ``center`` facing ``w``.
+fn plate(center: Vec3, w: Vec3, u: Vec3) -> Form {
+    let visible: Material = Material::Lambertian(LambertianKind::new(Vec3::new(0.1, 0.1, 0.1)));
+    let black: Material = Material::ThermoOptical(ThermoOpticalKind::coating(visible, "black paint").unwrap());
+    Form::Rectangle(Rectangle::new(center, 1e0, 1e0, black, u, w.cross(&u), w))
+}
+
+#[test]
+pub fn test_orbit_geometry() {
+    let orbit: Orbit = Orbit::new(400e3, 0e0, PI);
+    assert!(orbit.in_eclipse(orbit.sun()));
+    assert!(!Orbit::new(400e3, 0e0, 0e0).in_eclipse(orbit.sun()));
+    // High beta orbits are always sunlit.
+    let high_beta: Orbit = Orbit::new(400e3, 75e0_f32.to_radians(), PI);
+    assert!(!high_beta.in_eclipse(high_beta.sun()));
+    // Equatorial orbit at an equinox, the sun on the orbit plane.
+    assert!(Orbit::from_inclination(400e3, 0e0, 0e0, 0e0, 0e0).beta.abs() < 1e-6);
+    // Polar orbit with the sun along the orbit normal.
+    let beta: f32 = Orbit::from_inclination(400e3, PI / 2e0, PI / 2e0, 0e0, 0e0).beta;
+    assert!((beta - PI / 2e0).abs() < 1e-3);
+}
+
+#[test]
+pub fn test_heat_loads() {
+    let black: ThermoOptics = ThermoOptics::coating("black paint").unwrap();
+    let attitude: Attitude = Attitude::new(Vec3::new(0e0, -1e0, 0e0), Vec3::new(1e0, 0e0, 0e0));
+    let up: Vec3 = Vec3::new(0e0, 1e0, 0e0);
+    let down: Vec3 = Vec3::new(0e0, -1e0, 0e0);
+    let x: Vec3 = Vec3::new(1e0, 0e0, 0e0);
+    let mut world: HittableList = HittableList::new();
+    world.forms.push(plate(Vec3::zeros(), up, x));
+    world.forms.push(plate(Vec3::new(0e0, -1e0, 0e0), down, x));
+
+    // Noon, the sun at the zenith: the top plate sees the sun, the
+    // bottom one the Earth.
+    let orbit: Orbit = Orbit::new(400e3, 0e0, 0e0);
+    let env: Environment = Environment::new(orbit, attitude);
+    let top: HeatLoad = world.heat_load(N_MC, 0, &env);
+    let bottom: HeatLoad = world.heat_load(N_MC, 1, &env);
+    assert!((top.solar - black.absorptivity * SOLAR_CONSTANT).abs() < 1e-2 * SOLAR_CONSTANT);
+    assert_eq!(top.earth_ir, 0e0);
+    assert_eq!(bottom.solar, 0e0);
+    // Plate facing the Earth's center, F = (R / r)^2.
+    let f: f32 = (EARTH_RADIUS / orbit.radius()).powi(2);
+    println!("Earth IR: {}, exact {}", bottom.earth_ir, black.emissivity * EARTH_IR * f);
+    assert!((bottom.earth_ir - black.emissivity * EARTH_IR * f).abs() < 1e-2 * EARTH_IR * f);
+    assert!(bottom.albedo > 0e0 && bottom.albedo < black.absorptivity * 0.3 * SOLAR_CONSTANT * f);
+
+    // Midnight, in eclipse over the night side.
+    let env: Environment = Environment::new(Orbit::new(400e3, 0e0, PI), attitude);
+    let bottom: HeatLoad = world.heat_load(N_MC, 1, &env);
+    assert_eq!(world.heat_load(N_MC, 0, &env).solar, 0e0);
+    assert_eq!(bottom.albedo, 0e0);
+    assert!(bottom.earth_ir > 0e0);
+
+    // Self-shadowing, a plate hiding half of the top one from the sun.
+    world.forms.push(plate(Vec3::new(5e-1, 1e0, 0e0), up, x));
+    let env: Environment = Environment::new(orbit, attitude);
+    let shaded: HeatLoad = world.heat_load(N_MC, 0, &env);
+    assert!((shaded.solar - 5e-1 * top.solar).abs() < 1e-2 * top.solar);
+}
@@ -2,9 +2,10 @@ use crate::objects::{HittableList, Form};
 use crate::materials::{Material, LambertianKind};
 use crate::{Vec3, Square, Sphere};
 use crate::radiation::ViewFactors;
+use crate::analytic::{patch_to_frontal_sphere, patch_to_leveled_sphere};
 
 const N: usize = 10;
-const N_MC: usize = 224000;
+const N_MC: usize = 32768;
 const EPS: f32 = 1e-8;
 const ERR_MAX: f32 = 7.5e-2;
 
@@ -12,20 +13,17 @@ fn materials() -> Vec<Material> {
     let mat_1: Material = Material::Lambertian(LambertianKind::new(Vec3::new_const(0.81, 0.3, 0.3)));
     let mat_2: Material = Material::Lambertian(LambertianKind::new(Vec3::new_const(0.3, 0.3, 0.81)));
 
-    let mut v = Vec::new();
-    v.push(mat_1);
-    v.push(mat_2);
-    v
+    vec![mat_1, mat_2]
 }
 
-fn world_patch_frontal_sphere(r: f32, h: f32) -> HittableList {
+pub(super) fn world_patch_frontal_sphere(r: f32, h: f32) -> HittableList {
     let mut world: HittableList = HittableList::new();
     let mats: Vec<Material> = materials();
 
     let patch: Square = Square::new(
         Vec3::zeros(),
         r*EPS*EPS,
-        *mats.get(0).unwrap(),
+        mats.first().unwrap().clone(),
         Vec3::new(0e0, 1e0, 0e0),
         Vec3::new(0e0, 0e0, 1e0),
         Vec3::new(1e0, 0e0, 0e0)
@@ -34,7 +32,7 @@ fn world_patch_frontal_sphere(r: f32, h: f32) -> HittableList {
     let sph: Sphere = Sphere::new(
         Vec3::new(h, 0e0, 0e0),
         r,
-        *mats.get(1).unwrap()
+        mats.get(1).unwrap().clone()
     );
 
     world.forms.push(Form::Square(patch));
@@ -49,7 +47,7 @@ fn world_patch_leveled_sphere(r: f32, h: f32) -> HittableList {
     let patch: Square = Square::new(
         Vec3::zeros(),
         r*EPS*EPS,
-        *mats.get(0).unwrap(),
+        mats.first().unwrap().clone(),
         Vec3::new(0e0, 0e0, 1e0),
         Vec3::new(1e0, 0e0, 0e0),
         Vec3::new(0e0, 1e0, 0e0)
@@ -58,7 +56,7 @@ fn world_patch_leveled_sphere(r: f32, h: f32) -> HittableList {
     let sph: Sphere = Sphere::new(
         Vec3::new(h, 0e0, 0e0),
         r,
-        *mats.get(1).unwrap()
+        mats.get(1).unwrap().clone()
     );
 
     world.forms.push(Form::Square(patch));
@@ -66,16 +64,6 @@ fn world_patch_leveled_sphere(r: f32, h: f32) -> HittableList {
     world
 }
 
-fn view_patch_frontal_sphere(r: f32, h: f32) -> f32 {
-    return (r/h).powi(2)
-}
-
-fn view_patch_leveled_sphere(r: f32, h: f32) -> f32 {
-    let h_min: f32 = h / r;
-    let x: f32 = (h_min.powi(2) - 1e0).sqrt();
-    return (x.recip().atan() - x / h_min.powi(2)) / std::f32::consts::PI;
-}
-
 #[test]
 pub fn test_patch_frontal_sphere() {
     let mut h: f32;
@@ -86,8 +74,8 @@ pub fn test_patch_frontal_sphere() {
     for i in 0..N {
         h = 2e0 - (i as f32) / (N as f32);
         world = world_patch_frontal_sphere(1e0, h);
-        vf = *world.view_factors(N_MC).vfs.get(0).unwrap().get(0).unwrap();
-        vf_calc = view_patch_frontal_sphere(1e0, h);
+        vf = *world.view_factors(N_MC).vfs.first().unwrap().first().unwrap();
+        vf_calc = patch_to_frontal_sphere(1e0, h);
         println!("h={}, VF (raytracing) = {}, VF (aprox) = {}", h, vf, vf_calc);
         assert!((vf - vf_calc).abs() < ERR_MAX);
     }
@@ -104,8 +92,8 @@ pub fn test_patch_leveled_sphere() {
     for i in 0..N {
         h = 2e0 - (i as f32) / (N as f32);
         world = world_patch_leveled_sphere(1e0, h);
-        vf = *world.view_factors(N_MC).vfs.get(0).unwrap().get(0).unwrap();
-        vf_calc = view_patch_leveled_sphere(1e0, h);
+        vf = *world.view_factors(N_MC).vfs.first().unwrap().first().unwrap();
+        vf_calc = patch_to_leveled_sphere(1e0, h);
         println!("h={}, VF (raytracing) = {}, VF (aprox) = {}", h, vf, vf_calc);
         assert!((vf - vf_calc).abs() < ERR_MAX);
     }
@@ -0,0 +1,71 @@
+use crate::objects::{HittableList, Form, SurfaceFunctions, Rectangle};
+use crate::materials::{Material, LambertianKind};
+use crate::patches::{Mesh, radiosity};
+use crate::{Vec3, Sphere};
+use super::test_vf_methods::world_perpendicular_rectangles;
+use crate::analytic::perpendicular_rectangles;
+
+const N_MC: usize = 8192;
+const ERR_MAX: f32 = 2e-2;
+
+#[test]
+pub fn test_surface_parameters() {
+    let mat: Material = Material::Lambertian(LambertianKind::new(Vec3::new(0.5, 0.5, 0.5)));
+    let forms: [Form; 2] = [
+        Form::Sphere(Sphere::new(Vec3::new(1e0, 2e0, 3e0), 0.5, mat.clone())),
+        Form::Rectangle(Rectangle::new(
+            Vec3::new(0e0, 1e0, 0e0),
+            2e0,
+            0.5,
+            mat,
+            Vec3::new(0e0, 0e0, 1e0),
+            Vec3::new(1e0, 0e0, 0e0),
+            Vec3::new(0e0, 1e0, 0e0)
+        )),
+    ];
+    for form in forms.iter() {
+        for (s, t) in [(0.1, 0.2), (0.75, 0.5), (0.4, 0.9)].iter() {
+            let (s2, t2) = form.parameters(form.point(*s, *t));
+            assert!((s2 - s).abs() < 1e-4 && (t2 - t).abs() < 1e-4);
+        }
+    }
+}
+
+#[test]
+pub fn test_patch_view_factors() {
+    let (l, w, h): (f32, f32, f32) = (0.8, 0.4, 0.1);
+    let world: HittableList = world_perpendicular_rectangles(l, w, h);
+    let mesh: Mesh = Mesh::new(&world, 4, 2);
+    assert_eq!(mesh.len(), 16);
+    assert_eq!(mesh.form_patches(1), 8..16);
+    assert_eq!(mesh.patch_at(1, 0.9, 0.9), Some(15));
+    let area: f32 = mesh.patches.iter().map(|patch| patch.area).sum();
+    assert!((area - l * (w + h)).abs() < 1e-5);
+
+    let vfs: Vec<Vec<f32>> = mesh.view_factors(&world, N_MC);
+    for row in vfs.iter() {
+        assert!(row.iter().sum::<f32>() <= 1e0 + 1e-4);
+    }
+    // Aggregation back to the forms.
+    let forms: Vec<Vec<f32>> = mesh.aggregate(&vfs);
+    let exact: f32 = perpendicular_rectangles(l, w, h);
+    println!("F12 exact: {}, aggregated: {}, F21 {}", exact, forms[0][1], forms[1][0]);
+    assert!((forms[0][1] - exact).abs() < ERR_MAX * exact);
+    assert_eq!(forms[0][0], 0e0);
+    // Reciprocity, A1 F12 = A2 F21.
+    assert!((w * forms[0][1] - h * forms[1][0]).abs() < ERR_MAX * w * exact);
+}
+
+#[test]
+pub fn test_radiosity() {
+    // Two facing patches, B1 = 1 + B2 / 4, B2 = B1 / 4.
+    let vfs: Vec<Vec<f32>> = vec![vec![0e0, 5e-1], vec![5e-1, 0e0]];
+    let b: Vec<f32> = radiosity(&vfs, &[1e0, 0e0], &[5e-1, 5e-1]);
+    assert!((b[0] - 16e0 / 15e0).abs() < 1e-5);
+    assert!((b[1] - 4e0 / 15e0).abs() < 1e-5);
+
+    let world: HittableList = world_perpendicular_rectangles(0.8, 0.4, 0.1);
+    let mesh: Mesh = Mesh::new(&world, 2, 2);
+    let means: Vec<f32> = mesh.form_means(&mesh.per_patch(&[2e0, 3e0]));
+    assert!((means[0] - 2e0).abs() < 1e-6 && (means[1] - 3e0).abs() < 1e-6);
+}
@@ -0,0 +1,49 @@
+use crate::postprocess::{PostProcess, Exposure, ToneMapping, Transfer};
+use crate::cameras::CameraFocus;
+use crate::Vec3;
+
+const ERR_MAX: f32 = 1e-3;
+
+#[test]
+pub fn test_no_overflow() {
+    // Emitter radiance above 1 saturates instead of wrapping around.
+    let mut post: PostProcess = PostProcess::new();
+    assert_eq!(post.apply(Vec3::new(5e0, 1e0, 2.1e2)), [255, 255, 255]);
+    assert_eq!(post.apply(Vec3::new(-1e0, 0e0, f32::NAN)), [0, 0, 0]);
+    post.dither = true;
+    for _ in 0..100 {
+        assert_eq!(post.apply(Vec3::new(1e3, 1e0, 0e0)), [255, 255, 0]);
+    }
+    // Tone mapped highlights keep their hue ordering.
+    post.tone_mapping = ToneMapping::AcesFilmic;
+    post.dither = false;
+    let rgb: [u8; 3] = post.apply(Vec3::new(8e0, 4e0, 2e0));
+    assert!(rgb[0] > rgb[1] && rgb[1] > rgb[2]);
+}
+
+#[test]
+pub fn test_transfer_and_exposure() {
+    let srgb: Transfer = Transfer::Srgb;
+    assert!(srgb.encode(0e0).abs() < ERR_MAX);
+    assert!((srgb.encode(1e0) - 1e0).abs() < ERR_MAX);
+    assert!((srgb.encode(0.18) - 0.4614).abs() < ERR_MAX);
+    assert!((ToneMapping::ReinhardExtended { white: 4e0 }.map(4e0) - 1e0).abs() < ERR_MAX);
+    assert!((ToneMapping::Reinhard.map(1e0) - 5e-1).abs() < ERR_MAX);
+
+    // "Sunny 16" rule: ISO 100, 1/100 s at f/16 is the reference exposure.
+    let sunny: Exposure = Exposure::Physical { iso: 100e0, shutter: 1e-2, f_number: 16e0 };
+    assert!((sunny.scale() - 1e0).abs() < ERR_MAX);
+    // One stop brighter.
+    assert!((Exposure::Ev(1e0).scale() - 2e0).abs() < ERR_MAX);
+    let cam: CameraFocus = CameraFocus::new(
+        Vec3::new(0e0, 0e0, 1e0),
+        Vec3::zeros(),
+        Vec3::new(0e0, 1e0, 0e0),
+        20e0,
+        1.5,
+        0.625,
+        10e0
+    );
+    let exposure: Exposure = Exposure::from_camera(&cam, 200e0, 5e-3);
+    assert!((exposure.scale() - 1e0).abs() < ERR_MAX);
+}
@@ -0,0 +1,85 @@
+use crate::objects::HittableList;
+use crate::cameras::Camera;
+use crate::samplers::SamplerKind;
+use crate::integrator::PathEnd;
+use crate::film::Framebuffer;
+use crate::progressive::Progressive;
+use crate::adaptive::luminance;
+use crate::vectors::Vec3Methods;
+use crate::{Vec3, render_world, render_progressive, RenderSettings};
+use super::test_aov::{world_sphere, camera};
+
+const N_PX: u16 = 16;
+const MAX_DEPTH: usize = 10;
+
+fn mean_luminance(film: &Framebuffer) -> f32 {
+    film.pixels.iter().map(|col| luminance(*col)).sum::<f32>() / film.pixels.len() as f32
+}
+
+fn checkpoint_path(name: &str) -> String {
+    std::env::temp_dir().join(name).to_str().unwrap().to_string()
+}
+
+#[test]
+pub fn test_accumulation_and_checkpoint() {
+    let mut film: Framebuffer = Framebuffer::new(2, 3);
+    film.add_samples(1, 2, Vec3::ones(), 1);
+    film.add_samples(1, 2, Vec3::new(4e0, 4e0, 4e0), 3);
+    assert!((film.get(1, 2) - Vec3::new(3.25, 3.25, 3.25)).length() < 1e-6);
+    assert_eq!(film.samples(1, 2), 4);
+    film.paths.record(2, PathEnd::Roulette);
+    film.paths.record(0, PathEnd::Escaped);
+
+    let path: String = checkpoint_path("raytracing_test_accumulation.ckpt");
+    film.write_checkpoint(&path).unwrap();
+    let read: Framebuffer = Framebuffer::read_checkpoint(&path).unwrap();
+    assert_eq!((read.width_px, read.height_px), (2, 3));
+    assert_eq!(read.samples, film.samples);
+    assert_eq!(read.pixels.iter().map(|c| c.e).collect::<Vec<[f32; 3]>>(), film.pixels.iter().map(|c| c.e).collect::<Vec<[f32; 3]>>());
+    assert_eq!(read.paths.histogram, film.paths.histogram);
+    assert_eq!((read.paths.escaped, read.paths.roulette), (1, 1));
+
+    // Truncated checkpoints are rejected.
+    let data: Vec<u8> = std::fs::read(&path).unwrap();
+    std::fs::write(&path, &data[..data.len() - 1]).unwrap();
+    assert!(Framebuffer::read_checkpoint(&path).is_err());
+    // So are headers asking for more pixels than the file holds.
+    let mut header: Vec<u8> = data[..8].to_vec();
+    header.extend_from_slice(&[0xff; 4]);
+    std::fs::write(&path, &header).unwrap();
+    assert_eq!(Framebuffer::read_checkpoint(&path).err().map(|e| e.kind()), Some(std::io::ErrorKind::InvalidData));
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+pub fn test_progressive_resume() {
+    let (world, cam): (HittableList, Camera) = (world_sphere(), camera());
+    let path: String = checkpoint_path("raytracing_test_resume.ckpt");
+    let settings: RenderSettings = RenderSettings::new(N_PX, N_PX, 1e0, 0, MAX_DEPTH, false, SamplerKind::Sobol);
+
+    // Passes of 3, 3 and 2 samples.
+    let progressive: Progressive = Progressive::new(3, 8, Some(&path), 2);
+    assert_eq!(progressive.passes_left(0), 3);
+    // Targets near the top of the u32 range do not overflow.
+    assert_eq!(Progressive::new(3, u32::MAX, None, 1).passes_left(0), 1431655765);
+    let film: Framebuffer = render_progressive(
+        &world, &cam, &settings, &progressive, Framebuffer::new(N_PX, N_PX)
+    ).unwrap();
+    assert!(film.samples.iter().all(|n| *n == 8));
+
+    // Resume from the checkpoint up to 32 samples.
+    let resumed: Framebuffer = Framebuffer::read_checkpoint(&path).unwrap();
+    assert!(resumed.samples.iter().all(|n| *n == 8));
+    let progressive: Progressive = Progressive::new(8, 32, Some(&path), 1);
+    let film: Framebuffer = render_progressive(
+        &world, &cam, &settings, &progressive, resumed
+    ).unwrap();
+    assert!(film.samples.iter().all(|n| *n == 32));
+    assert_eq!(film.paths.paths(), 32 * N_PX as u64 * N_PX as u64);
+    assert!(Framebuffer::read_checkpoint(&path).unwrap().samples.iter().all(|n| *n == 32));
+    std::fs::remove_file(&path).unwrap();
+
+    let reference: Framebuffer = render_world(&world, &cam, &RenderSettings { n_smooth: 64, ..settings });
+    let (lum, lum_ref) = (mean_luminance(&film), mean_luminance(&reference));
+    assert!((lum - lum_ref).abs() < 2e-2 * lum_ref);
+}
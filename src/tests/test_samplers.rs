@@ -0,0 +1,53 @@
+use crate::samplers::{Sampler, SamplerKind, radical_inverse};
+
+/// First ``n`` points of the first two dimensions of a sampler.
+fn points(kind: SamplerKind, n: usize) -> Vec<(f32, f32)> {
+    let mut sampler: Sampler = Sampler::new(kind, n);
+    (0..n).map(|k| {
+        sampler.start_sample(k);
+        sampler.next_2d()
+    }).collect()
+}
+
+#[test]
+pub fn test_sobol_net() {
+    // Scrambled Sobol points are a (0, m, 2)-net: every elementary
+    // interval of area 1/2^m holds exactly one of the first 2^m points.
+    let m: u32 = 8;
+    let pts: Vec<(f32, f32)> = points(SamplerKind::Sobol, 1 << m);
+    for a in 0..=m {
+        let (nx, ny) = (1usize << a, 1usize << (m - a));
+        let mut cells: Vec<u32> = vec![0; nx * ny];
+        for (x, y) in pts.iter() {
+            assert!(*x >= 0e0 && *x < 1e0 && *y >= 0e0 && *y < 1e0);
+            cells[(x * nx as f32) as usize * ny + (y * ny as f32) as usize] += 1;
+        }
+        assert!(cells.iter().all(|c| *c == 1));
+    }
+}
+
+#[test]
+pub fn test_stratified_and_halton() {
+    let n: usize = 64;
+    for kind in [SamplerKind::Stratified, SamplerKind::Halton].iter() {
+        let pts: Vec<(f32, f32)> = points(*kind, n);
+        // One point per 1D stratum on each dimension.
+        let mut strata_x: Vec<u32> = vec![0; n];
+        for (x, _y) in pts.iter() {
+            strata_x[(x * n as f32) as usize] += 1;
+        }
+        match kind {
+            // Halton's base 3 dimension is stratified on powers of 3 only.
+            SamplerKind::Halton => assert!(strata_x.iter().all(|c| *c == 1)),
+            _ => {
+                let mut strata_y: Vec<u32> = vec![0; n];
+                for (_x, y) in pts.iter() {
+                    strata_y[(y * n as f32) as usize] += 1;
+                }
+                assert!(strata_x.iter().all(|c| *c == 1) && strata_y.iter().all(|c| *c == 1));
+            }
+        }
+    }
+    assert!((radical_inverse(6, 2) - 0.375).abs() < 1e-6);
+    assert!((radical_inverse(5, 3) - 7e0 / 9e0).abs() < 1e-6);
+}
@@ -0,0 +1,63 @@
+use crate::objects::{HittableList, Form};
+use crate::radiation::{ViewFactors, VfMethod, Vfs, VfEstimate, VfEstimates, VfTarget};
+use crate::samplers::SamplerKind;
+use crate::exchange::{ExchangeFactors, SurfaceOptics};
+use crate::materials::{Material, LambertianKind};
+use crate::{Vec3, Sphere};
+
+const N_MC: usize = 16384;
+const ERR_MAX: f32 = 1e-2;
+
+/// Hollow sphere of radius ``r_out`` enclosing a concentric sphere of
+/// radius ``r_in``.
+fn world_spherical_enclosure(r_out: f32, r_in: f32) -> HittableList {
+    let mut world: HittableList = HittableList::new();
+    let mat: Material = Material::Lambertian(LambertianKind::new(Vec3::new(0.5, 0.5, 0.5)));
+    world.forms.push(Form::Sphere(Sphere::hollow(Vec3::zeros(), r_out, mat.clone())));
+    world.forms.push(Form::Sphere(Sphere::new(Vec3::zeros(), r_in, mat)));
+    world
+}
+
+#[test]
+pub fn test_hollow_sphere() {
+    // An empty spherical enclosure only sees itself.
+    let mut world: HittableList = HittableList::new();
+    world.forms.push(Form::Sphere(Sphere::hollow(
+        Vec3::new(1e0, 2e0, 3e0),
+        2e0,
+        Material::Lambertian(LambertianKind::new(Vec3::ones()))
+    )));
+    assert!(world.forms[0].sees_itself());
+    assert!((world.view_factor(N_MC, 0, 0) - 1e0).abs() < ERR_MAX);
+    assert!((world.view_factor_row(N_MC, 0, SamplerKind::Sobol)[0] - 1e0).abs() < ERR_MAX);
+
+    // Grey walls absorb every ray eventually.
+    let b_ii: f32 = world.gebhart_row(N_MC, 0, &[SurfaceOptics::new(0.3, 0.5)])[0];
+    assert!((b_ii - 1e0).abs() < ERR_MAX);
+}
+
+#[test]
+pub fn test_spherical_enclosure() {
+    // F_21 = 1, so F_12 = (r/R)^2 and F_11 = 1 - (r/R)^2.
+    let (r_out, r_in): (f32, f32) = (2e0, 1e0);
+    let world: HittableList = world_spherical_enclosure(r_out, r_in);
+    let f_11: f32 = 1e0 - (r_in / r_out).powi(2);
+    assert!(!world.forms[1].sees_itself());
+
+    for method in [VfMethod::AreaIntegration, VfMethod::RayShooting].iter() {
+        let vfs: Vfs = world.view_factors_with(N_MC, *method);
+        println!("{:?}:\n{}", method, vfs);
+        assert!((vfs.self_vfs[0] - f_11).abs() < ERR_MAX);
+        assert_eq!(vfs.self_vfs[1], 0e0);
+        let matrix: Vec<Vec<f32>> = vfs.matrix(&world);
+        assert!((matrix[0][1] - (1e0 - f_11)).abs() < ERR_MAX);
+        assert!((matrix[1][0] - 1e0).abs() < 4e0 * ERR_MAX);
+    }
+
+    let estimates: VfEstimates = world.view_factors_converged(VfMethod::RayShooting, &VfTarget::new(0e0, 1e-2, 1 << 16));
+    let f_11_estimate: VfEstimate = estimates.self_estimates[0].unwrap();
+    let (low, high): (f32, f32) = f_11_estimate.confidence_interval(4e0);
+    assert!(low <= f_11 && f_11 <= high);
+    assert!(estimates.self_estimates[1].is_none());
+    assert!(format!("{}", estimates).starts_with("F(0,0) = "));
+}
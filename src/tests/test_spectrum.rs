@@ -0,0 +1,57 @@
+use crate::spectrum::{Spectrum, wavelength_to_rgb, sample_wavelength};
+use crate::vectors::Vec3Methods;
+use crate::Vec3;
+
+const N: usize = 4000;
+const ERR_MAX: f32 = 1e-2;
+
+/// Color of a spectrum seen by monochromatic rays.
+fn spectral_color(spectrum: &Spectrum) -> Vec3 {
+    let mut rgb: Vec3 = Vec3::zeros();
+    for i in 0..N {
+        let (lambda, _pdf) = sample_wavelength((i as f32 + 5e-1) / (N as f32));
+        rgb += wavelength_to_rgb(lambda) * spectrum.at(lambda) / (N as f32);
+    }
+    rgb
+}
+
+#[test]
+pub fn test_rgb_upsampling() {
+    let colors: [Vec3; 4] = [
+        Vec3::new(0.81, 0.3, 0.3),
+        Vec3::new(0.3, 0.3, 0.81),
+        Vec3::new(0.5, 0.5, 0.5),
+        Vec3::new(0.1, 0.9, 0.2),
+    ];
+    for rgb in colors.iter() {
+        let col: Vec3 = spectral_color(&Spectrum::Rgb(*rgb));
+        println!("RGB = {}, spectral = {}", rgb, col);
+        for c in 0..3 {
+            assert!((col[c] - rgb[c]).abs() < ERR_MAX);
+        }
+    }
+}
+
+#[test]
+pub fn test_sampled_spectrum() {
+    let flat: Spectrum = Spectrum::from_samples(&[(400e0, 0.5), (700e0, 0.5)]);
+    let col: Vec3 = flat.to_rgb();
+    for c in 0..3 {
+        assert!((col[c] - 0.5).abs() < ERR_MAX);
+    }
+    let ramp: Spectrum = Spectrum::from_samples(&[(380e0, 0e0), (780e0, 1e0)]);
+    assert!((ramp.at(580e0) - 0.5).abs() < ERR_MAX);
+    // Red-ish reflectance.
+    assert!(ramp.to_rgb().r() > ramp.to_rgb().b());
+}
+
+#[test]
+pub fn test_blackbody() {
+    // Wien's displacement law, peak at 2898 um K / T.
+    let sun: Spectrum = Spectrum::blackbody(5800e0, 1e0);
+    assert!((sun.at(2.897_772e6 / 5800e0) - 1e0).abs() < 1e-4);
+    assert!(sun.at(500e0) > sun.at(700e0));
+    let lamp: Spectrum = Spectrum::blackbody(2700e0, 1e0);
+    let rgb: Vec3 = lamp.to_rgb();
+    assert!(rgb.r() > rgb.g() && rgb.g() > rgb.b());
+}
@@ -0,0 +1,51 @@
+use crate::objects::{HittableList, Form, Rectangle};
+use crate::materials::{Material, LambertianKind, MetalKind, MaterialThermoOptics, MaterialAlbedo, ThermoOptics, ThermoOpticalKind, DEFAULT_EMISSIVITY};
+use crate::exchange::{Band, SurfaceOptics};
+use crate::hittable::HitRecord;
+use crate::Vec3;
+use crate::vectors::Vec3Methods;
+use super::hit_record;
+
+#[test]
+pub fn test_coating_library() {
+    let osr: ThermoOptics = ThermoOptics::coating(" OSR ").unwrap();
+    assert!(osr.absorptivity < 0.1 && osr.emissivity > 0.7);
+    assert_eq!(ThermoOptics::coating("unobtainium"), None);
+    for name in ThermoOptics::coatings().iter() {
+        assert!(ThermoOptics::coating(name).is_some());
+    }
+
+    // By default, the solar absorptivity follows the visible reflectance
+    // and the infrared emissivity is the default one.
+    let white: Material = Material::Lambertian(LambertianKind::new(Vec3::new(0.8, 0.8, 0.8)));
+    let optics: ThermoOptics = white.thermo_optics(Vec3::zeros());
+    assert!((optics.emissivity - DEFAULT_EMISSIVITY).abs() < 1e-6 && (optics.absorptivity - 0.2).abs() < 1e-6);
+    assert_eq!(optics.specularity, 0e0);
+    let mirror: Material = Material::Metal(MetalKind::new(Vec3::ones(), 0e0));
+    assert_eq!(mirror.thermo_optics(Vec3::zeros()), ThermoOptics::new(DEFAULT_EMISSIVITY, 0e0, 1e0));
+}
+
+#[test]
+pub fn test_visible_and_thermal_bands() {
+    // A white looking surface painted black in the infrared.
+    let visible: Material = Material::Lambertian(LambertianKind::new(Vec3::new(0.9, 0.9, 0.9)));
+    let painted: Material = Material::ThermoOptical(ThermoOpticalKind::coating(visible, "white paint").unwrap());
+    let rec: HitRecord = hit_record(painted.clone(), Vec3::zeros());
+    assert_eq!(painted.albedo(&rec).x(), 0.9);
+
+    let mut world: HittableList = HittableList::new();
+    world.forms.push(Form::Rectangle(Rectangle::new(
+        Vec3::zeros(),
+        1e0,
+        1e0,
+        painted,
+        Vec3::new(1e0, 0e0, 0e0),
+        Vec3::new(0e0, 0e0, -1e0),
+        Vec3::new(0e0, 1e0, 0e0)
+    )));
+    let ir: SurfaceOptics = world.surface_optics(Band::Infrared)[0];
+    let solar: SurfaceOptics = world.surface_optics(Band::Solar)[0];
+    assert!((ir.emissivity - 0.88).abs() < 1e-6);
+    assert!((solar.emissivity - 0.2).abs() < 1e-6);
+    assert!((solar.reflectivity() - 0.8).abs() < 1e-6);
+}
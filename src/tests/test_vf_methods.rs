@@ -0,0 +1,120 @@
+use crate::objects::{HittableList, Form, Rectangle};
+use crate::materials::{Material, LambertianKind};
+use crate::radiation::{ViewFactors, VfMethod, Vfs, VfEstimate, VfEstimates, VfTarget, Z_95};
+use crate::samplers::SamplerKind;
+use crate::analytic::{perpendicular_rectangles, patch_to_frontal_sphere};
+use crate::Vec3;
+use super::test_patch_sphere::world_patch_frontal_sphere;
+
+const N_MC: usize = 32768;
+const ERR_MAX: f32 = 1e-2;
+
+/// Perpendicular rectangles facing each other, sharing an edge of
+/// length ``l`` along Z: the first one (width ``w``) lies on the XZ
+/// plane, the second one (height ``h``) on the YZ plane.
+pub fn world_perpendicular_rectangles(l: f32, w: f32, h: f32) -> HittableList {
+    let mut world: HittableList = HittableList::new();
+    let mat: Material = Material::Lambertian(LambertianKind::new(Vec3::new(0.5, 0.5, 0.5)));
+    world.forms.push(Form::Rectangle(Rectangle::new(
+        Vec3::new(w / 2e0, 0e0, -l / 2e0),
+        l,
+        w,
+        mat.clone(),
+        Vec3::new(0e0, 0e0, 1e0),
+        Vec3::new(1e0, 0e0, 0e0),
+        Vec3::new(0e0, 1e0, 0e0)
+    )));
+    world.forms.push(Form::Rectangle(Rectangle::new(
+        Vec3::new(0e0, h / 2e0, -l / 2e0),
+        l,
+        h,
+        mat,
+        Vec3::new(0e0, 0e0, -1e0),
+        Vec3::new(0e0, 1e0, 0e0),
+        Vec3::new(1e0, 0e0, 0e0)
+    )));
+    world
+}
+
+#[test]
+pub fn test_ray_shooting_perpendicular_rectangles() {
+    // Adjacent faces of a cube.
+    assert!((perpendicular_rectangles(1e0, 1e0, 1e0) - 0.2).abs() < 1e-3);
+
+    let (l, w, h): (f32, f32, f32) = (0.8, 0.4, 0.1);
+    let world: HittableList = world_perpendicular_rectangles(l, w, h);
+    let exact: f32 = perpendicular_rectangles(l, w, h);
+    let row: Vec<f32> = world.view_factor_row(N_MC, 0, SamplerKind::Sobol);
+    let area: f32 = world.view_factor_with(N_MC, 0, 1, VfMethod::AreaIntegration);
+    println!("F12 exact: {}, ray shooting: {}, area integration: {}", exact, row[1], area);
+    assert!((row[1] - exact).abs() < ERR_MAX * exact.max(1e-1));
+    assert_eq!(row[0], 0e0);
+
+    // Reciprocity, A1 F12 = A2 F21.
+    let f21: f32 = world.view_factor_with(N_MC, 1, 0, VfMethod::RayShooting);
+    assert!((w * row[1] - h * f21).abs() < ERR_MAX * w * exact);
+
+    let vfs: Vfs = world.view_factors_with(N_MC, VfMethod::RayShooting);
+    assert_eq!(vfs.vfs.len(), 2);
+    assert_eq!(vfs.vfs[0].len(), 1);
+    assert!(vfs.vfs[1].is_empty());
+}
+
+#[test]
+pub fn test_ray_shooting_patch_sphere() {
+    let (r, h): (f32, f32) = (1e0, 1.5);
+    let world: HittableList = world_patch_frontal_sphere(r, h);
+    let row: Vec<f32> = world.view_factor_row(N_MC, 0, SamplerKind::Sobol);
+    assert!((row[1] - patch_to_frontal_sphere(r, h)).abs() < ERR_MAX);
+    assert!(row.iter().sum::<f32>() <= 1e0);
+}
+
+#[test]
+pub fn test_vf_estimate_statistics() {
+    let mut estimate: VfEstimate = VfEstimate::new();
+    assert!(estimate.std_error().is_infinite());
+    for x in [0e0, 1e0, 0e0, 1e0].iter() {
+        estimate.add(*x);
+    }
+    assert_eq!(estimate.samples(), 4);
+    assert!((estimate.value() - 5e-1).abs() < 1e-6);
+    assert!((estimate.std_error() - (1e0 / 12e0_f32).sqrt()).abs() < 1e-6);
+    let (lo, hi) = estimate.confidence_interval(Z_95);
+    assert_eq!((lo, hi), (0e0, 1e0));
+}
+
+#[test]
+pub fn test_vf_convergence() {
+    let (l, w, h): (f32, f32, f32) = (0.8, 0.4, 0.1);
+    let world: HittableList = world_perpendicular_rectangles(l, w, h);
+    let exact: f32 = perpendicular_rectangles(l, w, h);
+
+    // The confidence interval covers the exact value.
+    let estimate: VfEstimate = world.view_factor_estimate(4096, 0, 1, VfMethod::RayShooting);
+    let (lo, hi) = estimate.confidence_interval(Z_95);
+    println!("F12 = {} in [{}, {}], exact {}", estimate.value(), lo, hi, exact);
+    assert!(lo <= exact && exact <= hi);
+    assert_eq!(estimate.samples(), 4096);
+
+    // Relative error target.
+    let target: VfTarget = VfTarget::new(0e0, 2e-2, 1 << 20);
+    let estimate: VfEstimate = world.view_factor_converged(0, 1, VfMethod::RayShooting, &target);
+    assert!(estimate.relative_error() <= 2e-2);
+    assert!(estimate.samples() < 1 << 20 && estimate.samples() % 1024 == 0);
+    assert!((estimate.value() - exact).abs() < 4e0 * estimate.std_error());
+
+    // Sample cap, the target cannot be met.
+    let target: VfTarget = VfTarget::new(1e-9, 0e0, 3000);
+    let estimate: VfEstimate = world.view_factor_converged(0, 1, VfMethod::AreaIntegration, &target);
+    assert_eq!(estimate.samples(), 3000);
+    // Also with a minimum above the cap.
+    let target: VfTarget = VfTarget { min_samples: 4096, ..target };
+    let estimate: VfEstimate = world.view_factor_converged(0, 1, VfMethod::RayShooting, &target);
+    assert_eq!(estimate.samples(), 3000);
+
+    let estimates: VfEstimates = world.view_factors_converged(VfMethod::RayShooting, &VfTarget::new(1e-2, 0e0, 1 << 16));
+    assert_eq!(estimates.estimates.len(), 2);
+    assert!(estimates.estimates[0][0].std_error() <= 1e-2);
+    assert_eq!(estimates.samples(), estimates.estimates[0][0].samples());
+    assert!((estimates.vfs().vfs[0][0] - exact).abs() < 4e-2);
+}
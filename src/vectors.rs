@@ -19,7 +19,7 @@ use std::ops::MulAssign;
 use std::ops::Div;
 use std::ops::DivAssign;
 use std::fmt;
-use std::convert::{Into, From};
+use std::convert::From;
 extern crate rand;
 use rand::Rng;
 extern crate nalgebra;
@@ -45,6 +45,18 @@ impl Vec3{
         let mut rng = rand::thread_rng();
         Vec3::new(rng.gen::<f32>(), rng.gen::<f32>(), rng.gen::<f32>())
     }
+
+    /// Two unit vectors $\vec u$, $\vec v$ completing an orthonormal
+    /// basis $\left\{\vec u, \vec v, \vec w\right\}$ with $\vec w$ = ``self`` (unit vector).
+    pub fn orthonormal_basis(&self) -> (Vec3, Vec3) {
+        let a: Vec3 = match self.x().abs() > 0.9 {
+            true => Vec3::new(0e0, 1e0, 0e0),
+            false => Vec3::new(1e0, 0e0, 0e0),
+        };
+        let v: Vec3 = self.cross(&a).unit_vector();
+        let u: Vec3 = v.cross(self);
+        (u, v)
+    }
 }
 
 impl Vec3Methods for Vec3{
@@ -175,9 +187,9 @@ impl fmt::Display for Vec3{
     }
 }
 
-impl Into<V3> for Vec3{
-    fn into(self) -> V3 {
-        V3::new(self.x(), self.y(), self.z())
+impl From<Vec3> for V3{
+    fn from(v: Vec3) -> V3 {
+        V3::new(v.x(), v.y(), v.z())
     }
 }
 